@@ -0,0 +1,210 @@
+# English messages, also the fallback for messages missing from other locales.
+#
+# Every `<language-tag>.ftl` file in this directory adds a locale. Message ids are part of
+# the API (error responses carry them as `message_id`), so keep them stable across releases.
+
+## Site
+
+app-name = Micro Frontend App
+app-description = A high-performance, containerized micro web-application demonstrating modern web development constraints with Rust, Docker, and micro front-end architecture
+app-keywords = microservice, frontend, rust, docker, web application, performance
+og-locale = en_US
+
+## Relative times
+
+# $unit is now, minute, hour, day, month or year
+time-ago = { $unit ->
+    [minute] { $count ->
+        [one] { $count } minute ago
+       *[other] { $count } minutes ago
+    }
+    [hour] { $count ->
+        [one] { $count } hour ago
+       *[other] { $count } hours ago
+    }
+    [day] { $count ->
+        [one] { $count } day ago
+       *[other] { $count } days ago
+    }
+    [month] { $count ->
+        [one] { $count } month ago
+       *[other] { $count } months ago
+    }
+    [year] { $count ->
+        [one] { $count } year ago
+       *[other] { $count } years ago
+    }
+   *[now] just now
+}
+
+## Display page
+
+display-page-title = Display - { $username }
+display-page-description = View the display name for user { $username }
+display-page-keywords = user, display, profile, username
+display-heading = Username Display
+display-avatar-alt = Avatar of { $username }
+display-username-label = Username:
+display-no-display-name = No display name set
+display-last-updated = Last updated
+display-website = Website
+display-language = Language
+display-time-zone = Time zone
+display-user-not-found = User not found
+display-no-username = No username provided
+display-edit-link = Edit display name
+
+## Edit page
+
+edit-page-title = Edit - { $username }
+edit-page-description = Content management system to edit display name for user { $username }
+edit-page-keywords = edit, cms, content management, display name, profile
+edit-heading = Content Management System
+edit-authenticated-as = Authenticated as:
+edit-editing-user = Editing user:
+edit-awaiting-review-label = Awaiting review:
+edit-awaiting-review = “{ $display_name }” will be shown once a moderator approves it.
+edit-not-approved-label = Not approved:
+edit-rejected = “{ $display_name }” was rejected
+edit-rejected-with-reason = “{ $display_name }” was rejected: { $reason }
+edit-avatar-form-label = Upload avatar form
+edit-avatar-label = Avatar
+edit-avatar-alt = Your avatar
+edit-avatar-help = PNG, JPEG, GIF or WebP, up to { $max_mb } MB. Images are cropped to a square.
+edit-avatar-submit = Upload Avatar
+edit-form-label = Update display name form
+edit-display-name-label = Display Name
+edit-display-name-placeholder = Enter your display name
+edit-display-name-help = This is the name that will be displayed publicly.
+edit-pronouns-label = Pronouns
+edit-pronouns-placeholder = e.g. she/her
+edit-bio-label = Bio
+edit-bio-placeholder = Tell people a little about yourself
+edit-website-label = Website
+edit-locale-label = Language
+edit-locale-placeholder = e.g. en-US
+edit-locale-help = A language tag such as en, de-DE or zh-Hant.
+edit-timezone-label = Time zone
+edit-timezone-placeholder = e.g. Europe/Berlin
+edit-profile-help = Profile fields are optional; leave a field empty to remove it.
+edit-submit = Update Display Name
+edit-submit-help = Click to save your display name changes.
+edit-overrides-heading = Per-app display names
+edit-overrides-help = Apps can show a different name, e.g. your formal name in billing and a nickname in chat. Other apps use your display name.
+edit-overrides-form-label = Per-app display name form
+edit-override-app-label = App
+edit-override-app-placeholder = e.g. billing
+edit-override-app-help = Letters, numbers, dots, underscores and hyphens.
+edit-override-name-label = Display name in this app
+edit-override-submit = Save Per-app Name
+edit-localized-heading = Localized display names
+edit-localized-help = Readers whose browser prefers one of these languages see the matching name, e.g. your name in Japanese script for “ja”.
+edit-localized-form-label = Localized display name form
+edit-localized-locale-label = Language
+edit-localized-locale-placeholder = e.g. ja or en-Latn
+edit-localized-locale-help = A language tag such as ja, en-Latn or zh-Hant.
+edit-localized-name-label = Display name in this language
+edit-localized-submit = Save Localized Name
+edit-remove = Remove
+edit-remove-label = Remove display name for { $key }
+edit-variant-saved = Display name for { $key } saved.
+edit-variant-removed = Display name for { $key } removed.
+edit-error = Error: { $message }
+edit-avatar-too-large = That image is too large.
+edit-avatar-updated = Avatar updated successfully!
+edit-updating = Updating...
+edit-submitted-for-review = Display name submitted for review. It will be shown once a moderator approves it.
+edit-updated = Display name updated successfully!
+edit-login-required = Authentication required. Please log in.
+edit-view-display = View display
+
+## Errors
+
+user-not-found = User '{ $username }' not found
+username-reserved = Username '{ $username }' is reserved
+username-empty = Username cannot be empty
+username-too-short = Username must be at least { $min } characters long
+username-too-long = Username must be at most { $max } characters long
+username-invalid-characters = Username must contain only alphanumeric characters, underscores, and hyphens
+app-key-invalid = App must be 1-64 characters: letters, numbers, dots, underscores and hyphens
+tenant-invalid = Tenant must be 1-64 characters: letters, numbers, underscores and hyphens
+tenant-claim-invalid = Token does not name a valid tenant
+tenant-mismatch = Token was issued for a different tenant
+token-missing = Authentication required
+token-malformed = The token is malformed
+token-expired = The token has expired
+token-signature-invalid = The token signature is invalid
+token-audience-invalid = The token is not intended for this service
+token-issuer-invalid = The token issuer is not trusted
+token-revoked = The token has been revoked
+token-too-old = The token is too old
+token-issued-in-future = The token is issued in the future
+token-not-fresh = A more recent authentication is required
+token-invalid = The token is invalid
+token-claim-invalid = Token claim “{ $claim }” does not identify a valid user
+moderator-required = Moderator permission required
+admin-role-required = Admin role required
+csrf-check-failed = The request did not come from this site; reload the page and try again
+api-key-invalid = The API key is invalid
+api-key-forbidden = This API key is not allowed to make this request
+api-key-not-found = No API key '{ $id }'
+display-name-empty = Display name cannot be empty
+display-name-too-short = { $min ->
+    [one] Display name must be at least { $min } character long
+   *[other] Display name must be at least { $min } characters long
+}
+display-name-too-long = { $max ->
+    [one] Display name must be at most { $max } character long
+   *[other] Display name must be at most { $max } characters long
+}
+display-name-html-characters = Display name cannot contain HTML characters
+display-name-control-characters = Display name cannot contain control characters
+display-name-bidi-characters = Display name cannot contain bidirectional control characters
+display-name-invisible-characters = Display name cannot contain invisible characters
+display-name-mixed-scripts = Display name cannot mix characters from different scripts
+display-name-script-not-allowed = Display name cannot contain { $script } characters
+display-name-denied-word = Display name contains a word that is not allowed
+display-name-impersonation = Display name cannot impersonate another user
+profile-locale-invalid = Locale must be a language tag such as 'en' or 'en-US'
+profile-timezone-invalid = Timezone must be an IANA time zone name such as 'Europe/Berlin'
+profile-website-invalid = Website must be a valid URL
+profile-website-scheme = Website must be an http or https URL
+profile-website-credentials = Website cannot contain credentials
+profile-website-too-long = Website must be at most { $max } characters long
+# $field is bio or pronouns
+profile-field-too-long = { $field ->
+    [bio] Bio
+    [pronouns] Pronouns
+   *[other] { $field }
+} must be at most { $max } characters long
+profile-field-control-characters = { $field ->
+    [bio] Bio
+    [pronouns] Pronouns
+   *[other] { $field }
+} cannot contain control characters
+profile-field-hidden-characters = { $field ->
+    [bio] Bio
+    [pronouns] Pronouns
+   *[other] { $field }
+} cannot contain invisible or bidirectional control characters
+overrides-unavailable = Display name overrides are unavailable while display name moderation is enabled
+too-many-overrides = At most { $max } display name overrides are allowed
+override-not-found = No display name override for '{ $username }' in '{ $app }'
+localized-names-unavailable = Localized display names are unavailable while display name moderation is enabled
+too-many-localized-names = At most { $max } localized display names are allowed
+localized-name-not-found = No '{ $locale }' display name for '{ $username }'
+pending-display-name-not-found = No pending display name for '{ $username }'
+rejection-reason-empty = Rejection reason cannot be empty
+rejection-reason-too-long = Rejection reason cannot be longer than { $max } characters
+rejection-reason-control-characters = Rejection reason cannot contain control characters
+avatar-not-found = No avatar for '{ $username }'
+avatar-missing-field = Missing multipart field '{ $field }'
+avatar-upload-too-large = Avatar upload is too large
+avatar-empty = Avatar file is empty
+avatar-too-many-bytes = Avatar must be at most { $max } bytes
+avatar-too-many-pixels = Avatar must be at most { $max }x{ $max } pixels
+avatar-unreadable = Avatar could not be read as an image
+avatar-unsupported-format = Avatar must be a PNG, JPEG, GIF or WebP image
+revocation-target-invalid = Revoke either a token id (jti) or a subject
+revocation-id-invalid = '{ $field }' must be between 1 and { $max } characters long
+revocation-issuer-unknown = '{ $issuer }' is not a trusted issuer
@@ -0,0 +1,204 @@
+# Messages en français
+
+## Site
+
+app-name = Micro Frontend App
+app-description = Une micro-application web performante et conteneurisée qui illustre les contraintes du développement web moderne avec Rust, Docker et une architecture micro-frontend
+app-keywords = microservice, frontend, rust, docker, application web, performance
+og-locale = fr_FR
+
+## Relative times
+
+# $unit vaut now, minute, hour, day, month ou year
+time-ago = { $unit ->
+    [minute] { $count ->
+        [one] il y a { $count } minute
+       *[other] il y a { $count } minutes
+    }
+    [hour] { $count ->
+        [one] il y a { $count } heure
+       *[other] il y a { $count } heures
+    }
+    [day] { $count ->
+        [one] il y a { $count } jour
+       *[other] il y a { $count } jours
+    }
+    [month] il y a { $count } mois
+    [year] { $count ->
+        [one] il y a { $count } an
+       *[other] il y a { $count } ans
+    }
+   *[now] à l’instant
+}
+
+## Display page
+
+display-page-title = Affichage - { $username }
+display-page-description = Voir le nom d’affichage de l’utilisateur { $username }
+display-page-keywords = utilisateur, affichage, profil, nom d’utilisateur
+display-heading = Affichage du nom d’utilisateur
+display-avatar-alt = Avatar de { $username }
+display-username-label = Nom d’utilisateur :
+display-no-display-name = Aucun nom d’affichage défini
+display-last-updated = Mis à jour
+display-website = Site web
+display-language = Langue
+display-time-zone = Fuseau horaire
+display-user-not-found = Utilisateur introuvable
+display-no-username = Aucun nom d’utilisateur fourni
+display-edit-link = Modifier le nom d’affichage
+
+## Edit page
+
+edit-page-title = Modifier - { $username }
+edit-page-description = Système de gestion de contenu pour modifier le nom d’affichage de l’utilisateur { $username }
+edit-page-keywords = modifier, cms, gestion de contenu, nom d’affichage, profil
+edit-heading = Système de gestion de contenu
+edit-authenticated-as = Connecté en tant que :
+edit-editing-user = Modification de l’utilisateur :
+edit-awaiting-review-label = En attente de validation :
+edit-awaiting-review = « { $display_name } » sera affiché dès qu’un modérateur l’aura approuvé.
+edit-not-approved-label = Refusé :
+edit-rejected = « { $display_name } » a été refusé
+edit-rejected-with-reason = « { $display_name } » a été refusé : { $reason }
+edit-avatar-form-label = Formulaire d’envoi d’avatar
+edit-avatar-label = Avatar
+edit-avatar-alt = Votre avatar
+edit-avatar-help = PNG, JPEG, GIF ou WebP, jusqu’à { $max_mb } Mo. Les images sont recadrées au format carré.
+edit-avatar-submit = Envoyer l’avatar
+edit-form-label = Formulaire de modification du nom d’affichage
+edit-display-name-label = Nom d’affichage
+edit-display-name-placeholder = Saisissez votre nom d’affichage
+edit-display-name-help = C’est le nom qui sera affiché publiquement.
+edit-pronouns-label = Pronoms
+edit-pronouns-placeholder = p. ex. elle
+edit-bio-label = Bio
+edit-bio-placeholder = Présentez-vous en quelques mots
+edit-website-label = Site web
+edit-locale-label = Langue
+edit-locale-placeholder = p. ex. fr-FR
+edit-locale-help = Une étiquette de langue comme fr, de-DE ou zh-Hant.
+edit-timezone-label = Fuseau horaire
+edit-timezone-placeholder = p. ex. Europe/Paris
+edit-profile-help = Les champs du profil sont facultatifs ; laissez un champ vide pour le supprimer.
+edit-submit = Mettre à jour le nom d’affichage
+edit-submit-help = Cliquez pour enregistrer les modifications de votre nom d’affichage.
+edit-overrides-heading = Noms d’affichage par application
+edit-overrides-help = Chaque application peut afficher un nom différent, p. ex. votre nom officiel pour la facturation et un surnom dans le chat. Les autres applications utilisent votre nom d’affichage.
+edit-overrides-form-label = Formulaire de nom d’affichage par application
+edit-override-app-label = Application
+edit-override-app-placeholder = p. ex. billing
+edit-override-app-help = Lettres, chiffres, points, tirets bas et tirets.
+edit-override-name-label = Nom d’affichage dans cette application
+edit-override-submit = Enregistrer le nom pour l’application
+edit-localized-heading = Noms d’affichage localisés
+edit-localized-help = Les lecteurs dont le navigateur préfère l’une de ces langues voient le nom correspondant, p. ex. votre nom en écriture japonaise pour « ja ».
+edit-localized-form-label = Formulaire de nom d’affichage localisé
+edit-localized-locale-label = Langue
+edit-localized-locale-placeholder = p. ex. ja ou en-Latn
+edit-localized-locale-help = Une étiquette de langue comme ja, en-Latn ou zh-Hant.
+edit-localized-name-label = Nom d’affichage dans cette langue
+edit-localized-submit = Enregistrer le nom localisé
+edit-remove = Supprimer
+edit-remove-label = Supprimer le nom d’affichage pour { $key }
+edit-variant-saved = Nom d’affichage pour { $key } enregistré.
+edit-variant-removed = Nom d’affichage pour { $key } supprimé.
+edit-error = Erreur : { $message }
+edit-avatar-too-large = Cette image est trop volumineuse.
+edit-avatar-updated = Avatar mis à jour !
+edit-updating = Mise à jour…
+edit-submitted-for-review = Nom d’affichage soumis pour validation. Il sera affiché dès qu’un modérateur l’aura approuvé.
+edit-updated = Nom d’affichage mis à jour !
+edit-login-required = Authentification requise. Veuillez vous connecter.
+edit-view-display = Voir l’affichage
+
+## Errors
+
+user-not-found = Utilisateur « { $username } » introuvable
+username-reserved = Le nom d’utilisateur « { $username } » est réservé
+username-empty = Le nom d’utilisateur ne peut pas être vide
+username-too-short = Le nom d’utilisateur doit comporter au moins { $min } caractères
+username-too-long = Le nom d’utilisateur doit comporter au plus { $max } caractères
+username-invalid-characters = Le nom d’utilisateur ne peut contenir que des caractères alphanumériques, des tirets bas et des tirets
+app-key-invalid = L’application doit comporter de 1 à 64 caractères : lettres, chiffres, points, tirets bas et tirets
+tenant-invalid = Le locataire doit comporter de 1 à 64 caractères : lettres, chiffres, tirets bas et tirets
+tenant-claim-invalid = Le jeton ne désigne pas un locataire valide
+tenant-mismatch = Le jeton a été émis pour un autre locataire
+token-missing = Authentification requise
+token-malformed = Le jeton est mal formé
+token-expired = Le jeton a expiré
+token-signature-invalid = La signature du jeton n’est pas valide
+token-audience-invalid = Le jeton n’est pas destiné à ce service
+token-issuer-invalid = L’émetteur du jeton n’est pas reconnu
+token-revoked = Le jeton a été révoqué
+token-too-old = Le jeton est trop ancien
+token-issued-in-future = Le jeton est émis dans le futur
+token-not-fresh = Une authentification plus récente est requise
+token-invalid = Le jeton n’est pas valide
+token-claim-invalid = La revendication « { $claim } » du jeton ne désigne pas un utilisateur valide
+moderator-required = Droits de modérateur requis
+admin-role-required = Rôle d’administrateur requis
+csrf-check-failed = La requête ne provient pas de ce site ; rechargez la page et réessayez
+api-key-invalid = La clé d’API n’est pas valide
+api-key-forbidden = Cette clé d’API n’est pas autorisée à effectuer cette requête
+api-key-not-found = Aucune clé d’API « { $id } »
+display-name-empty = Le nom d’affichage ne peut pas être vide
+display-name-too-short = { $min ->
+    [one] Le nom d’affichage doit comporter au moins { $min } caractère
+   *[other] Le nom d’affichage doit comporter au moins { $min } caractères
+}
+display-name-too-long = { $max ->
+    [one] Le nom d’affichage doit comporter au plus { $max } caractère
+   *[other] Le nom d’affichage doit comporter au plus { $max } caractères
+}
+display-name-html-characters = Le nom d’affichage ne peut pas contenir de caractères HTML
+display-name-control-characters = Le nom d’affichage ne peut pas contenir de caractères de contrôle
+display-name-bidi-characters = Le nom d’affichage ne peut pas contenir de caractères de contrôle bidirectionnels
+display-name-invisible-characters = Le nom d’affichage ne peut pas contenir de caractères invisibles
+display-name-mixed-scripts = Le nom d’affichage ne peut pas mélanger des caractères de plusieurs écritures
+display-name-script-not-allowed = Le nom d’affichage ne peut pas contenir de caractères de l’écriture { $script }
+display-name-denied-word = Le nom d’affichage contient un mot non autorisé
+display-name-impersonation = Le nom d’affichage ne peut pas usurper l’identité d’un autre utilisateur
+profile-locale-invalid = La langue doit être une étiquette de langue comme « fr » ou « fr-FR »
+profile-timezone-invalid = Le fuseau horaire doit être un nom de fuseau IANA comme « Europe/Paris »
+profile-website-invalid = Le site web doit être une URL valide
+profile-website-scheme = Le site web doit être une URL http ou https
+profile-website-credentials = Le site web ne peut pas contenir d’identifiants
+profile-website-too-long = Le site web doit comporter au plus { $max } caractères
+# $field vaut bio ou pronouns
+profile-field-too-long = { $field ->
+    [bio] La bio
+    [pronouns] Les pronoms
+   *[other] Le champ { $field }
+} doit comporter au plus { $max } caractères
+profile-field-control-characters = { $field ->
+    [bio] La bio
+    [pronouns] Les pronoms
+   *[other] Le champ { $field }
+} ne peut pas contenir de caractères de contrôle
+profile-field-hidden-characters = { $field ->
+    [bio] La bio
+    [pronouns] Les pronoms
+   *[other] Le champ { $field }
+} ne peut pas contenir de caractères invisibles ou de contrôle bidirectionnels
+overrides-unavailable = Les noms d’affichage par application sont indisponibles lorsque la modération des noms d’affichage est activée
+too-many-overrides = Au plus { $max } noms d’affichage par application sont autorisés
+override-not-found = Aucun nom d’affichage pour « { $username } » dans « { $app } »
+localized-names-unavailable = Les noms d’affichage localisés sont indisponibles lorsque la modération des noms d’affichage est activée
+too-many-localized-names = Au plus { $max } noms d’affichage localisés sont autorisés
+localized-name-not-found = Aucun nom d’affichage « { $locale } » pour « { $username } »
+pending-display-name-not-found = Aucun nom d’affichage en attente pour « { $username } »
+rejection-reason-empty = Le motif du refus ne peut pas être vide
+rejection-reason-too-long = Le motif du refus ne peut pas dépasser { $max } caractères
+rejection-reason-control-characters = Le motif du refus ne peut pas contenir de caractères de contrôle
+avatar-not-found = Aucun avatar pour « { $username } »
+avatar-missing-field = Champ multipart « { $field } » manquant
+avatar-upload-too-large = L’avatar envoyé est trop volumineux
+avatar-empty = Le fichier d’avatar est vide
+avatar-too-many-bytes = L’avatar doit faire au plus { $max } octets
+avatar-too-many-pixels = L’avatar doit faire au plus { $max }x{ $max } pixels
+avatar-unreadable = L’avatar n’a pas pu être lu comme une image
+avatar-unsupported-format = L’avatar doit être une image PNG, JPEG, GIF ou WebP
+revocation-target-invalid = Révoquez soit un identifiant de jeton (jti), soit un sujet
+revocation-id-invalid = « { $field } » doit contenir entre 1 et { $max } caractères
+revocation-issuer-unknown = « { $issuer } » n’est pas un émetteur de confiance
@@ -0,0 +1,2 @@
+-- Display names are limited to 100 graphemes, which can span more than 100 code points
+ALTER TABLE users MODIFY display_name VARCHAR(255) NOT NULL;
@@ -0,0 +1,15 @@
+-- Usernames are case-insensitive and stored in lowercase.
+-- Users whose names differ only in case would become unreachable, so this migration fails
+-- with a duplicate key error while any exist; rename or merge them, then run it again.
+CREATE TEMPORARY TABLE canonical_username_check (
+    canonical_username VARCHAR(50) COLLATE utf8mb4_bin NOT NULL PRIMARY KEY
+);
+
+INSERT INTO canonical_username_check (canonical_username)
+SELECT LOWER(username) FROM users;
+
+DROP TEMPORARY TABLE canonical_username_check;
+
+UPDATE users
+SET username = LOWER(username)
+WHERE BINARY username <> BINARY LOWER(username);
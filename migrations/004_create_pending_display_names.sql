@@ -0,0 +1,12 @@
+-- Display name changes awaiting moderator review
+CREATE TABLE pending_display_names (
+    username VARCHAR(50) NOT NULL PRIMARY KEY,
+    display_name VARCHAR(255) NOT NULL,
+    status ENUM('pending', 'rejected') NOT NULL DEFAULT 'pending',
+    rejection_reason VARCHAR(500) NULL,
+    submitted_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    reviewed_at TIMESTAMP NULL,
+
+    -- The moderation queue is listed oldest first
+    INDEX idx_status_submitted_at (status, submitted_at)
+);
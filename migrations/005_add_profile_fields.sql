@@ -0,0 +1,7 @@
+-- Optional public profile fields; NULL means not set
+ALTER TABLE users
+    ADD COLUMN pronouns VARCHAR(100) NULL AFTER display_name,
+    ADD COLUMN bio VARCHAR(2000) NULL AFTER pronouns,
+    ADD COLUMN locale VARCHAR(35) NULL AFTER bio,
+    ADD COLUMN timezone VARCHAR(64) NULL AFTER locale,
+    ADD COLUMN website VARCHAR(255) NULL AFTER timezone;
@@ -0,0 +1,8 @@
+-- Processed avatar images (AVATAR_STORAGE=database)
+CREATE TABLE avatars (
+    username VARCHAR(50) NOT NULL PRIMARY KEY,
+    content_type VARCHAR(50) NOT NULL,
+    data MEDIUMBLOB NOT NULL,
+    etag VARCHAR(64) NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
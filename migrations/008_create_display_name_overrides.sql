@@ -0,0 +1,10 @@
+-- Display names shown to specific applications instead of users.display_name
+CREATE TABLE display_name_overrides (
+    username VARCHAR(50) NOT NULL,
+    app VARCHAR(64) NOT NULL,
+    display_name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (username, app)
+);
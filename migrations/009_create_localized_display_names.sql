@@ -0,0 +1,10 @@
+-- Display name variants per BCP 47 language tag, chosen from Accept-Language
+CREATE TABLE localized_display_names (
+    username VARCHAR(50) NOT NULL,
+    locale VARCHAR(35) NOT NULL,
+    display_name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (username, locale)
+);
@@ -0,0 +1,28 @@
+-- Scope every record to a tenant; usernames only need to be unique within one.
+-- Existing rows belong to the 'default' tenant.
+ALTER TABLE users
+    ADD COLUMN tenant VARCHAR(64) NOT NULL DEFAULT 'default' FIRST,
+    DROP PRIMARY KEY,
+    ADD PRIMARY KEY (tenant, username);
+
+ALTER TABLE pending_display_names
+    ADD COLUMN tenant VARCHAR(64) NOT NULL DEFAULT 'default' FIRST,
+    DROP PRIMARY KEY,
+    ADD PRIMARY KEY (tenant, username),
+    DROP INDEX idx_status_submitted_at,
+    ADD INDEX idx_tenant_status_submitted_at (tenant, status, submitted_at);
+
+ALTER TABLE avatars
+    ADD COLUMN tenant VARCHAR(64) NOT NULL DEFAULT 'default' FIRST,
+    DROP PRIMARY KEY,
+    ADD PRIMARY KEY (tenant, username);
+
+ALTER TABLE display_name_overrides
+    ADD COLUMN tenant VARCHAR(64) NOT NULL DEFAULT 'default' FIRST,
+    DROP PRIMARY KEY,
+    ADD PRIMARY KEY (tenant, username, app);
+
+ALTER TABLE localized_display_names
+    ADD COLUMN tenant VARCHAR(64) NOT NULL DEFAULT 'default' FIRST,
+    DROP PRIMARY KEY,
+    ADD PRIMARY KEY (tenant, username, locale);
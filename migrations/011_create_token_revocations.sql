@@ -0,0 +1,24 @@
+-- Revoked JWTs, checked on every authenticated request.
+-- Token ids and subjects are only unique per issuer, and revocations are made and checked
+-- within one tenant. Rows are deleted once every token they cover has expired.
+CREATE TABLE revoked_tokens (
+    tenant VARCHAR(64) NOT NULL,
+    issuer VARCHAR(255) NOT NULL,
+    jti VARCHAR(255) NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tenant, issuer, jti),
+    INDEX idx_expires_at (expires_at)
+);
+
+-- All tokens of a subject (the `sub` claim) issued before `issued_before` are revoked
+CREATE TABLE revoked_subjects (
+    tenant VARCHAR(64) NOT NULL,
+    issuer VARCHAR(255) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    issued_before TIMESTAMP NOT NULL,
+    expires_at TIMESTAMP NOT NULL,
+    revoked_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP ON UPDATE CURRENT_TIMESTAMP,
+    PRIMARY KEY (tenant, issuer, subject),
+    INDEX idx_expires_at (expires_at)
+);
@@ -0,0 +1,16 @@
+-- API keys of backend services, scoped to one tenant.
+-- Only the SHA-256 hash of the key is stored; the key itself is shown once, on creation.
+CREATE TABLE api_keys (
+    tenant VARCHAR(64) NOT NULL,
+    id CHAR(36) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    key_hash CHAR(64) NOT NULL,
+    scope VARCHAR(10) NOT NULL,
+    -- Comma-separated CIDR ranges the key may be used from; empty allows any address
+    allowed_ips TEXT NOT NULL,
+    quota_per_minute INT UNSIGNED NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tenant, id),
+    UNIQUE INDEX idx_tenant_key_hash (tenant, key_hash)
+);
@@ -0,0 +1,428 @@
+use chrono::Utc;
+use serde::{Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::database::{ApiKey, ApiKeyScope, UserDatabase};
+use crate::errors::AppError;
+use crate::tenant::Tenant;
+
+/// Prefix of every API key, so leaked keys are easy to recognize
+pub const API_KEY_PREFIX: &str = "mfe_";
+
+/// Longest API key name
+pub const MAX_API_KEY_NAME_LENGTH: usize = 100;
+
+/// Most address ranges a key can be restricted to
+pub const MAX_API_KEY_IP_RANGES: usize = 20;
+
+/// Cached key lookups by tenant and key hash, including unknown keys, with the time they were made
+type LookupCache = HashMap<(Tenant, String), (Option<ApiKey>, Instant)>;
+
+/// Entries kept in the lookup cache before it is cleared, bounding memory used by unknown keys
+const MAX_CACHED_LOOKUPS: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyConfig {
+    /// How long a key lookup is cached; keys deleted on other instances keep working this long
+    pub cache_ttl: Duration,
+    /// Reverse proxies whose `X-Forwarded-For` header is believed when checking a key's address ranges
+    pub trusted_proxies: Vec<IpRange>,
+}
+
+impl Default for ApiKeyConfig {
+    fn default() -> Self {
+        Self {
+            cache_ttl: Duration::from_secs(30),
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+/// An IPv4 or IPv6 address range in CIDR notation (`10.0.0.0/8`, `2001:db8::/32`); a bare address is a single host
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpRange {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpRange {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 clients reaching an IPv6 listener show up as mapped addresses
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            IpAddr::V4(_) => ip,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                prefix_matches(u32::from(network).into(), u32::from(ip).into(), self.prefix, 32)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => prefix_matches(network.into(), ip.into(), self.prefix, 128),
+            _ => false,
+        }
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, prefix: u8, bits: u8) -> bool {
+    let host_bits = u32::from(bits - prefix);
+    network.checked_shr(host_bits).unwrap_or(0) == ip.checked_shr(host_bits).unwrap_or(0)
+}
+
+impl FromStr for IpRange {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (address, prefix) = match value.trim().split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (value.trim(), None),
+        };
+        let network: IpAddr = address
+            .parse()
+            .map_err(|_| format!("'{value}' is not an IP address or CIDR range"))?;
+        let bits = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            None => bits,
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= bits)
+                .ok_or_else(|| format!("'{value}' has an invalid prefix length"))?,
+        };
+        Ok(IpRange { network, prefix })
+    }
+}
+
+impl std::fmt::Display for IpRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+impl Serialize for IpRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Settings of a key to create
+#[derive(Debug, Clone)]
+pub struct NewApiKey {
+    pub name: String,
+    pub scope: ApiKeyScope,
+    pub allowed_ips: Vec<String>,
+    pub quota_per_minute: Option<u32>,
+}
+
+/// Why a request's API key was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyRejection {
+    /// The scope does not allow the request's method
+    Scope,
+    /// The client address is outside the key's ranges
+    IpAddress,
+    /// The key used up its requests for this minute
+    Quota,
+}
+
+impl ApiKeyRejection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyRejection::Scope => "api_key_scope",
+            ApiKeyRejection::IpAddress => "api_key_ip",
+            ApiKeyRejection::Quota => "api_key_quota",
+        }
+    }
+}
+
+/// API keys of backend services, which call the `/api/username` routes without a user's JWT.
+///
+/// Keys belong to one tenant and are only accepted on its hosts. Lookups are cached for the
+/// cache TTL, and quotas are counted per instance.
+#[derive(Clone)]
+pub struct ApiKeyService {
+    database: Arc<dyn UserDatabase>,
+    config: ApiKeyConfig,
+    lookups: Arc<RwLock<LookupCache>>,
+    /// Start of each key's current quota minute and the requests made in it
+    usage: Arc<Mutex<HashMap<String, (Instant, u32)>>>,
+}
+
+impl ApiKeyService {
+    pub fn new(database: Arc<dyn UserDatabase>, config: ApiKeyConfig) -> Self {
+        Self {
+            database,
+            config,
+            lookups: Arc::new(RwLock::new(HashMap::new())),
+            usage: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Create a key, returning it with the secret key, which is not stored and cannot be shown again
+    pub async fn create(
+        &self,
+        tenant: &Tenant,
+        new_key: NewApiKey,
+        created_by: &str,
+    ) -> Result<(ApiKey, String), AppError> {
+        let name = new_key.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_API_KEY_NAME_LENGTH || name.chars().any(char::is_control) {
+            return Err(AppError::validation_failed(format!(
+                "API key name must be 1-{MAX_API_KEY_NAME_LENGTH} characters without control characters"
+            )));
+        }
+        if new_key.allowed_ips.len() > MAX_API_KEY_IP_RANGES {
+            return Err(AppError::validation_failed(format!(
+                "An API key can be restricted to at most {MAX_API_KEY_IP_RANGES} address ranges"
+            )));
+        }
+        let allowed_ips = new_key
+            .allowed_ips
+            .iter()
+            .map(|range| range.parse::<IpRange>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(AppError::validation_failed)?;
+        if new_key.quota_per_minute == Some(0) {
+            return Err(AppError::validation_failed(
+                "API key quota must be at least 1 request per minute",
+            ));
+        }
+
+        let secret = format!(
+            "{API_KEY_PREFIX}{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        let key = ApiKey {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            key_hash: hash_api_key(&secret),
+            scope: new_key.scope,
+            allowed_ips,
+            quota_per_minute: new_key.quota_per_minute,
+            created_by: created_by.to_string(),
+            created_at: Utc::now(),
+        };
+
+        self.database
+            .create_api_key(tenant, &key)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to create API key: {}", e)))?;
+        Ok((key, secret))
+    }
+
+    pub async fn list(&self, tenant: &Tenant) -> Result<Vec<ApiKey>, AppError> {
+        self.database
+            .list_api_keys(tenant)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to list API keys: {}", e)))
+    }
+
+    /// Delete a key; other instances stop accepting it within the cache TTL
+    pub async fn delete(&self, tenant: &Tenant, id: &str) -> Result<bool, AppError> {
+        let deleted = self
+            .database
+            .delete_api_key(tenant, id)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to delete API key: {}", e)))?;
+
+        let mut lookups = self.lookups.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        lookups.retain(|(key_tenant, _), (key, _)| key_tenant != tenant || key.as_ref().is_none_or(|key| key.id != id));
+        Ok(deleted)
+    }
+
+    /// The tenant's key matching `secret`, if any
+    pub async fn find(&self, tenant: &Tenant, secret: &str) -> Option<ApiKey> {
+        let lookup = (tenant.clone(), hash_api_key(secret));
+        let cached = {
+            let lookups = self.lookups.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+            lookups.get(&lookup).cloned()
+        };
+        if let Some((key, looked_up_at)) = &cached {
+            if looked_up_at.elapsed() < self.config.cache_ttl {
+                return key.clone();
+            }
+        }
+
+        let key = match self.database.find_api_key(tenant, &lookup.1).await {
+            Ok(key) => key,
+            Err(e) => {
+                // Keep accepting a key that was valid when the database last answered
+                tracing::warn!("Failed to look up API key, using the cached result: {}", e);
+                return cached.and_then(|(key, _)| key);
+            }
+        };
+
+        let mut lookups = self.lookups.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if lookups.len() >= MAX_CACHED_LOOKUPS {
+            lookups.clear();
+        }
+        lookups.insert(lookup, (key.clone(), Instant::now()));
+        key
+    }
+
+    /// Address of the client behind a connection from `peer`.
+    ///
+    /// `X-Forwarded-For` is client-controlled, so it only counts when `peer` is a trusted proxy:
+    /// the client is then the rightmost forwarded address that is not a trusted proxy itself.
+    /// A malformed header gives no address, so keys restricted to address ranges are refused.
+    pub fn client_ip(&self, peer: Option<IpAddr>, forwarded_for: Option<&str>) -> Option<IpAddr> {
+        let is_trusted = |ip: IpAddr| self.config.trusted_proxies.iter().any(|proxy| proxy.contains(ip));
+
+        let mut client = peer?;
+        let Some(forwarded_for) = forwarded_for.filter(|_| is_trusted(client)) else {
+            return Some(client);
+        };
+        for hop in forwarded_for.rsplit(',') {
+            client = hop.trim().parse().ok()?;
+            if !is_trusted(client) {
+                break;
+            }
+        }
+        Some(client)
+    }
+
+    /// Check a request made with `key` against its scope, address ranges and quota.
+    ///
+    /// Only requests that pass count against the quota.
+    pub fn authorize(
+        &self,
+        key: &ApiKey,
+        required: ApiKeyScope,
+        client_ip: Option<IpAddr>,
+    ) -> Result<(), ApiKeyRejection> {
+        if !key.scope.allows(required) {
+            return Err(ApiKeyRejection::Scope);
+        }
+        if !key.allowed_ips.is_empty()
+            && !client_ip.is_some_and(|ip| key.allowed_ips.iter().any(|range| range.contains(ip)))
+        {
+            return Err(ApiKeyRejection::IpAddress);
+        }
+        if let Some(quota) = key.quota_per_minute {
+            let mut usage = self.usage.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let (minute_start, count) = usage.entry(key.id.clone()).or_insert((Instant::now(), 0));
+            if minute_start.elapsed() >= Duration::from_secs(60) {
+                *minute_start = Instant::now();
+                *count = 0;
+            }
+            if *count >= quota {
+                return Err(ApiKeyRejection::Quota);
+            }
+            *count += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Hex-encoded SHA-256 of an API key, as stored
+pub fn hash_api_key(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes()).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::mock::MockUserDatabase;
+
+    #[test]
+    fn test_ip_ranges() {
+        let range: IpRange = "10.1.0.0/16".parse().unwrap();
+        assert!(range.contains("10.1.200.3".parse().unwrap()));
+        assert!(range.contains("::ffff:10.1.0.1".parse().unwrap()));
+        assert!(!range.contains("10.2.0.1".parse().unwrap()));
+        assert!(!range.contains("2001:db8::1".parse().unwrap()));
+        assert_eq!(range.to_string(), "10.1.0.0/16");
+
+        let host: IpRange = "2001:db8::1".parse().unwrap();
+        assert!(host.contains("2001:db8::1".parse().unwrap()));
+        assert!(!host.contains("2001:db8::2".parse().unwrap()));
+        assert!("0.0.0.0/0".parse::<IpRange>().unwrap().contains("192.0.2.1".parse().unwrap()));
+
+        for invalid in ["10.0.0.0/33", "example.com", "10.0.0.0/", "2001:db8::/129"] {
+            assert!(invalid.parse::<IpRange>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_client_ip_trusts_only_configured_proxies() {
+        let config = ApiKeyConfig {
+            trusted_proxies: vec!["10.0.0.0/8".parse().unwrap()],
+            ..ApiKeyConfig::default()
+        };
+        let service = ApiKeyService::new(Arc::new(MockUserDatabase::new()), config);
+        let ip = |value: &str| Some(value.parse::<IpAddr>().unwrap());
+
+        // Direct clients cannot pick their address with a header
+        assert_eq!(service.client_ip(ip("198.51.100.7"), Some("192.0.2.10")), ip("198.51.100.7"));
+        assert_eq!(service.client_ip(None, Some("192.0.2.10")), None);
+
+        // Behind trusted proxies, the last hop they did not add is the client
+        assert_eq!(service.client_ip(ip("10.0.0.5"), Some("192.0.2.10")), ip("192.0.2.10"));
+        assert_eq!(
+            service.client_ip(ip("10.0.0.5"), Some("192.0.2.99, 192.0.2.10, 10.0.0.9")),
+            ip("192.0.2.10")
+        );
+        assert_eq!(service.client_ip(ip("10.0.0.5"), None), ip("10.0.0.5"));
+        assert_eq!(service.client_ip(ip("10.0.0.5"), Some("192.0.2.10, garbage")), None);
+    }
+
+    #[tokio::test]
+    async fn test_create_find_and_delete() {
+        let service = ApiKeyService::new(Arc::new(MockUserDatabase::new()), ApiKeyConfig::default());
+        let tenant = Tenant::default();
+        let new_key = |name: &str, allowed_ips: &[&str], quota_per_minute| NewApiKey {
+            name: name.to_string(),
+            scope: ApiKeyScope::Read,
+            allowed_ips: allowed_ips.iter().map(|range| range.to_string()).collect(),
+            quota_per_minute,
+        };
+
+        let (key, secret) = service.create(&tenant, new_key("sync", &[], None), "admin").await.unwrap();
+        assert!(secret.starts_with(API_KEY_PREFIX));
+        assert_ne!(key.key_hash, secret);
+        assert_eq!(service.find(&tenant, &secret).await, Some(key.clone()));
+        assert_eq!(service.find(&Tenant::new("other").unwrap(), &secret).await, None);
+        assert_eq!(service.find(&tenant, "mfe_unknown").await, None);
+
+        assert!(service.create(&tenant, new_key(" ", &[], None), "admin").await.is_err());
+        assert!(service
+            .create(&tenant, new_key("sync", &["10.0.0.0/99"], None), "admin")
+            .await
+            .is_err());
+        assert!(service.create(&tenant, new_key("sync", &[], Some(0)), "admin").await.is_err());
+
+        assert!(service.delete(&tenant, &key.id).await.unwrap());
+        assert_eq!(service.find(&tenant, &secret).await, None);
+        assert!(!service.delete(&tenant, &key.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_authorize() {
+        let service = ApiKeyService::new(Arc::new(MockUserDatabase::new()), ApiKeyConfig::default());
+        let new_key = NewApiKey {
+            name: "sync".to_string(),
+            scope: ApiKeyScope::Read,
+            allowed_ips: vec!["192.0.2.0/24".to_string()],
+            quota_per_minute: Some(2),
+        };
+        let (key, _) = service.create(&Tenant::default(), new_key, "admin").await.unwrap();
+        let inside = Some("192.0.2.10".parse().unwrap());
+
+        assert_eq!(service.authorize(&key, ApiKeyScope::Write, inside), Err(ApiKeyRejection::Scope));
+        assert_eq!(
+            service.authorize(&key, ApiKeyScope::Read, Some("198.51.100.1".parse().unwrap())),
+            Err(ApiKeyRejection::IpAddress)
+        );
+        assert_eq!(
+            service.authorize(&key, ApiKeyScope::Read, None),
+            Err(ApiKeyRejection::IpAddress)
+        );
+        assert_eq!(service.authorize(&key, ApiKeyScope::Read, inside), Ok(()));
+        assert_eq!(service.authorize(&key, ApiKeyScope::Read, inside), Ok(()));
+        assert_eq!(service.authorize(&key, ApiKeyScope::Read, inside), Err(ApiKeyRejection::Quota));
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+
+use super::{processing::content_etag, AvatarStore};
+use crate::database::StoredAvatar;
+use crate::tenant::Tenant;
+use crate::validation::canonical_username;
+
+/// Stores avatars as `{username}.png` files in a local directory.
+///
+/// The default tenant's avatars sit directly in the directory, as they did before tenancy;
+/// every other tenant gets a `{tenant}/` subdirectory.
+pub struct DirectoryAvatarStore {
+    root: PathBuf,
+}
+
+impl DirectoryAvatarStore {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root)
+            .with_context(|| format!("Failed to create avatar directory {}", root.display()))?;
+
+        Ok(Self { root })
+    }
+
+    /// Usernames are validated to `[a-z0-9_-]` before they reach the store, and tenant ids are
+    /// restricted the same way, so both are safe path components
+    fn path_for(&self, tenant: &Tenant, username: &str) -> PathBuf {
+        let directory = if tenant.is_default() {
+            self.root.clone()
+        } else {
+            self.root.join(tenant.as_str())
+        };
+        directory.join(format!("{}.png", canonical_username(username)))
+    }
+}
+
+#[async_trait]
+impl AvatarStore for DirectoryAvatarStore {
+    async fn get_avatar(&self, tenant: &Tenant, username: &str) -> Result<Option<StoredAvatar>> {
+        let path = self.path_for(tenant, username);
+
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read avatar {}", path.display())),
+        };
+
+        Ok(Some(StoredAvatar {
+            content_type: "image/png".to_string(),
+            etag: content_etag(&data),
+            updated_at: modified_at(&path).await?,
+            data,
+        }))
+    }
+
+    async fn put_avatar(&self, tenant: &Tenant, username: &str, avatar: &StoredAvatar) -> Result<()> {
+        let path = self.path_for(tenant, username);
+        let temp_path = path.with_extension("png.tmp");
+
+        if let Some(directory) = path.parent() {
+            tokio::fs::create_dir_all(directory)
+                .await
+                .with_context(|| format!("Failed to create avatar directory {}", directory.display()))?;
+        }
+
+        // Write then rename, so readers never see a partially written image
+        tokio::fs::write(&temp_path, &avatar.data)
+            .await
+            .with_context(|| format!("Failed to write avatar {}", temp_path.display()))?;
+        tokio::fs::rename(&temp_path, &path)
+            .await
+            .with_context(|| format!("Failed to move avatar into place at {}", path.display()))?;
+
+        tracing::info!("Stored avatar for user '{}' at {}", username, path.display());
+        Ok(())
+    }
+}
+
+async fn modified_at(path: &Path) -> Result<DateTime<Utc>> {
+    let modified = tokio::fs::metadata(path).await?.modified()?;
+    Ok(DateTime::<Utc>::from(modified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_directory_store_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = DirectoryAvatarStore::new(dir.path().join("avatars")).unwrap();
+        let tenant = Tenant::default();
+        assert!(store.get_avatar(&tenant, "alice").await.unwrap().is_none());
+
+        let data = vec![1, 2, 3];
+        let avatar = StoredAvatar {
+            content_type: "image/png".to_string(),
+            etag: content_etag(&data),
+            data,
+            updated_at: Utc::now(),
+        };
+        store.put_avatar(&tenant, "Alice", &avatar).await.unwrap();
+
+        let stored = store.get_avatar(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(stored.data, avatar.data);
+        assert_eq!(stored.etag, avatar.etag);
+        assert!(dir.path().join("avatars/alice.png").exists());
+
+        // Other tenants are kept in their own subdirectory
+        let brand = Tenant::new("brand-a").unwrap();
+        assert!(store.get_avatar(&brand, "alice").await.unwrap().is_none());
+        store.put_avatar(&brand, "alice", &avatar).await.unwrap();
+        assert!(dir.path().join("avatars/brand-a/alice.png").exists());
+    }
+}
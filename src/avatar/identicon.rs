@@ -0,0 +1,105 @@
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+use super::processing::content_etag;
+
+/// Foreground colors, taken from the theme in `templates/base.html`.
+///
+/// Each entry is used as the fallback of a `--identicon-N` custom property, so an identicon
+/// inlined into a page follows the page's theme while a standalone `.svg` keeps these colors.
+const PALETTE: [&str; 6] = ["#2563eb", "#1d4ed8", "#3498db", "#27ae60", "#c0392b", "#2d3748"];
+
+/// Background color (`--identicon-background`), also from `base.html`
+const BACKGROUND: &str = "#f0f4f8";
+
+/// The identicon is a GRID x GRID pattern, mirrored around the middle column
+const GRID: usize = 5;
+
+/// Deterministic SVG identicon for a (canonical) username.
+///
+/// The pattern and color come from the SHA-256 of the username only, so the same username
+/// always produces byte-for-byte the same document. The markup contains no user input and is
+/// safe to inline into templates.
+pub fn identicon_svg(username: &str) -> String {
+    let hash = Sha256::digest(username.as_bytes());
+    let color_index = usize::from(hash[0]) % PALETTE.len();
+
+    // One bit per cell of the left half (including the middle column), mirrored to the right
+    let half = GRID.div_ceil(2);
+    let mut path = String::new();
+    for row in 0..GRID {
+        for col in 0..half {
+            let bit = row * half + col;
+            if hash[1 + bit / 8] & (1 << (bit % 8)) == 0 {
+                continue;
+            }
+
+            let _ = write!(path, "M{col} {row}h1v1h-1z");
+            let mirrored = GRID - 1 - col;
+            if mirrored != col {
+                let _ = write!(path, "M{mirrored} {row}h1v1h-1z");
+            }
+        }
+    }
+
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" class="identicon" viewBox="-1 -1 {size} {size}" "#,
+            r#"width="128" height="128" shape-rendering="crispEdges" aria-hidden="true">"#,
+            r#"<rect x="-1" y="-1" width="{size}" height="{size}" style="fill:var(--identicon-background,{background})"/>"#,
+            r#"<path d="{path}" style="fill:var(--identicon-{index},{color})"/>"#,
+            "</svg>"
+        ),
+        size = GRID + 2,
+        background = BACKGROUND,
+        path = path,
+        index = color_index + 1,
+        color = PALETTE[color_index],
+    )
+}
+
+/// Strong ETag for an identicon; stable for as long as the generator is unchanged
+pub fn identicon_etag(svg: &str) -> String {
+    content_etag(svg.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identicon_is_deterministic() {
+        assert_eq!(identicon_svg("alice"), identicon_svg("alice"));
+        assert_ne!(identicon_svg("alice"), identicon_svg("bob"));
+    }
+
+    #[test]
+    fn test_identicon_is_svg_with_theme_colors() {
+        let svg = identicon_svg("alice");
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(svg.ends_with("</svg>"));
+        assert!(svg.contains("var(--identicon-background,#f0f4f8)"));
+        assert!(PALETTE.iter().any(|color| svg.contains(color)));
+        assert!(!svg.contains("alice"));
+    }
+
+    #[test]
+    fn test_identicon_is_mirrored() {
+        for username in ["alice", "bob", "carol", "dave"] {
+            let svg = identicon_svg(username);
+            let cells: Vec<(usize, usize)> = svg
+                .split('M')
+                .skip(1)
+                .filter_map(|cell| {
+                    let coords = cell.split('h').next()?;
+                    let (x, y) = coords.split_once(' ')?;
+                    Some((x.parse().ok()?, y.parse().ok()?))
+                })
+                .collect();
+
+            for &(x, y) in &cells {
+                assert!(cells.contains(&(GRID - 1 - x, y)), "{username}: ({x}, {y}) is not mirrored");
+            }
+        }
+    }
+}
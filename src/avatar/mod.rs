@@ -0,0 +1,107 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::database::{StoredAvatar, UserDatabase};
+use crate::errors::AppError;
+use crate::tenant::Tenant;
+
+pub mod directory;
+pub mod identicon;
+pub mod processing;
+
+/// Where processed avatars are kept
+#[async_trait]
+pub trait AvatarStore: Send + Sync {
+    async fn get_avatar(&self, tenant: &Tenant, username: &str) -> Result<Option<StoredAvatar>>;
+    async fn put_avatar(&self, tenant: &Tenant, username: &str, avatar: &StoredAvatar) -> Result<()>;
+}
+
+/// Stores avatars as BLOBs through the configured `UserDatabase` adapter
+pub struct DatabaseAvatarStore {
+    database: Arc<dyn UserDatabase>,
+}
+
+impl DatabaseAvatarStore {
+    pub fn new(database: Arc<dyn UserDatabase>) -> Self {
+        Self { database }
+    }
+}
+
+#[async_trait]
+impl AvatarStore for DatabaseAvatarStore {
+    async fn get_avatar(&self, tenant: &Tenant, username: &str) -> Result<Option<StoredAvatar>> {
+        self.database.get_avatar(tenant, username).await
+    }
+
+    async fn put_avatar(&self, tenant: &Tenant, username: &str, avatar: &StoredAvatar) -> Result<()> {
+        self.database.put_avatar(tenant, username, avatar).await
+    }
+}
+
+/// Upload limits and output size for avatars
+#[derive(Debug, Clone)]
+pub struct AvatarConfig {
+    /// Maximum size of the uploaded file
+    pub max_upload_bytes: usize,
+    /// Maximum width or height of the uploaded image, checked before decoding
+    pub max_dimension: u32,
+    /// Width and height of the stored (square) avatar
+    pub size: u32,
+}
+
+impl Default for AvatarConfig {
+    fn default() -> Self {
+        Self {
+            max_upload_bytes: 2 * 1024 * 1024,
+            max_dimension: 4096,
+            size: 256,
+        }
+    }
+}
+
+/// Avatar processing and storage, shared through `AppState`
+#[derive(Clone)]
+pub struct AvatarService {
+    store: Arc<dyn AvatarStore>,
+    config: AvatarConfig,
+}
+
+impl AvatarService {
+    pub fn new(store: Arc<dyn AvatarStore>, config: AvatarConfig) -> Self {
+        Self { store, config }
+    }
+
+    pub fn config(&self) -> &AvatarConfig {
+        &self.config
+    }
+
+    /// Validate and re-encode an uploaded image, then store it for the user
+    pub async fn upload(&self, tenant: &Tenant, username: &str, bytes: &[u8]) -> Result<StoredAvatar, AppError> {
+        let avatar = processing::process_avatar(bytes, &self.config)?;
+
+        self.store
+            .put_avatar(tenant, username, &avatar)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to store avatar: {}", e)))?;
+
+        Ok(avatar)
+    }
+
+    pub async fn get(&self, tenant: &Tenant, username: &str) -> Result<Option<StoredAvatar>, AppError> {
+        self.store
+            .get_avatar(tenant, username)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to get avatar: {}", e)))
+    }
+}
+
+/// Versioned avatar URL; the version changes with the image, so it can be cached indefinitely
+pub fn avatar_url(username: &str, avatar: &StoredAvatar) -> String {
+    format!("/avatar/{}?v={}", username, avatar.etag)
+}
+
+/// Generated identicon URL; its content depends only on the username
+pub fn identicon_url(username: &str) -> String {
+    format!("/avatar/{}.svg", username)
+}
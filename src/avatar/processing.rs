@@ -0,0 +1,125 @@
+use chrono::Utc;
+use image::{imageops::FilterType, ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+
+use super::AvatarConfig;
+use crate::database::StoredAvatar;
+use crate::errors::AppError;
+
+/// Formats accepted for upload; everything is re-encoded as PNG
+const ACCEPTED_FORMATS: &[ImageFormat] = &[ImageFormat::Png, ImageFormat::Jpeg, ImageFormat::Gif, ImageFormat::WebP];
+
+/// Decode an uploaded image, crop it to a square of `config.size` pixels and re-encode it as PNG.
+///
+/// Re-encoding drops metadata (EXIF, GPS, comments) and anything that is not pixel data,
+/// so only images we produced ourselves are ever served.
+pub fn process_avatar(bytes: &[u8], config: &AvatarConfig) -> Result<StoredAvatar, AppError> {
+    if bytes.is_empty() {
+        return Err(AppError::validation_failed("Avatar file is empty").localized("avatar-empty", []));
+    }
+
+    if bytes.len() > config.max_upload_bytes {
+        return Err(
+            AppError::validation_failed(format!("Avatar must be at most {} bytes", config.max_upload_bytes))
+                .localized("avatar-too-many-bytes", [("max", config.max_upload_bytes.into())]),
+        );
+    }
+
+    let reader = image_reader(bytes)?;
+    let (width, height) = reader.into_dimensions().map_err(|_| {
+        AppError::validation_failed("Avatar could not be read as an image").localized("avatar-unreadable", [])
+    })?;
+
+    if width > config.max_dimension || height > config.max_dimension {
+        return Err(AppError::validation_failed(format!(
+            "Avatar must be at most {}x{} pixels",
+            config.max_dimension, config.max_dimension
+        ))
+        .localized("avatar-too-many-pixels", [("max", config.max_dimension.into())]));
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(config.max_dimension);
+    limits.max_image_height = Some(config.max_dimension);
+
+    let mut reader = image_reader(bytes)?;
+    reader.limits(limits);
+    let image = reader.decode().map_err(|_| {
+        AppError::validation_failed("Avatar could not be read as an image").localized("avatar-unreadable", [])
+    })?;
+
+    let resized = image.resize_to_fill(config.size, config.size, FilterType::Lanczos3);
+
+    let mut data = Vec::new();
+    resized
+        .to_rgba8()
+        .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+        .map_err(|e| AppError::internal_server_error(format!("Failed to encode avatar: {}", e)))?;
+
+    Ok(StoredAvatar {
+        content_type: "image/png".to_string(),
+        etag: content_etag(&data),
+        data,
+        updated_at: Utc::now(),
+    })
+}
+
+fn image_reader(bytes: &[u8]) -> Result<ImageReader<Cursor<&[u8]>>, AppError> {
+    let reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format().map_err(|_| {
+        AppError::validation_failed("Avatar could not be read as an image").localized("avatar-unreadable", [])
+    })?;
+
+    match reader.format() {
+        Some(format) if ACCEPTED_FORMATS.contains(&format) => Ok(reader),
+        _ => Err(AppError::validation_failed("Avatar must be a PNG, JPEG, GIF or WebP image")
+            .localized("avatar-unsupported-format", [])),
+    }
+}
+
+/// Short content hash used as the avatar's `ETag`
+pub fn content_etag(data: &[u8]) -> String {
+    Sha256::digest(data)[..16].iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbImage};
+
+    fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), format).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_process_avatar_resizes_and_reencodes() {
+        let jpeg = encode(DynamicImage::ImageRgb8(RgbImage::new(400, 300)), ImageFormat::Jpeg);
+
+        let avatar = process_avatar(&jpeg, &AvatarConfig::default()).unwrap();
+        assert_eq!(avatar.content_type, "image/png");
+        assert_eq!(image::guess_format(&avatar.data).unwrap(), ImageFormat::Png);
+
+        let decoded = image::load_from_memory(&avatar.data).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (256, 256));
+        assert_eq!(avatar.etag, content_etag(&avatar.data));
+    }
+
+    #[test]
+    fn test_process_avatar_limits() {
+        let config = AvatarConfig {
+            max_upload_bytes: 1024 * 1024,
+            max_dimension: 100,
+            size: 64,
+        };
+
+        let too_large = encode(DynamicImage::ImageRgb8(RgbImage::new(200, 50)), ImageFormat::Png);
+        assert!(process_avatar(&too_large, &config).is_err());
+
+        assert!(process_avatar(&[], &config).is_err());
+        assert!(process_avatar(&vec![0; 2 * 1024 * 1024], &config).is_err());
+        assert!(process_avatar(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>", &config).is_err());
+        assert!(process_avatar(b"\x89PNG\r\n\x1a\nnot really a png", &config).is_err());
+    }
+}
@@ -82,7 +82,7 @@ async fn main() -> Result<()> {
         println!("\nTo fix missing seed data, run: just migrate");
     }
 
-    println!("");
+    println!();
 
     // Close the pool to ensure clean shutdown
     pool.close().await;
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use sqlx::MySqlPool;
+use sqlx::{MySqlPool, Row};
 use std::env;
 
 pub async fn run_migrations() -> Result<()> {
@@ -17,7 +17,11 @@ pub async fn run_migrations() -> Result<()> {
     tracing::info!("Running database migrations...");
 
     // Run migrations using sqlx migrate
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    if let Err(e) = sqlx::migrate!("./migrations").run(&pool).await {
+        report_username_collisions(&pool).await;
+        pool.close().await;
+        return Err(e.into());
+    }
 
     tracing::info!("Database migrations completed successfully");
 
@@ -27,6 +31,35 @@ pub async fn run_migrations() -> Result<()> {
     Ok(())
 }
 
+/// List usernames that differ only in case, which stop the canonical username migration
+async fn report_username_collisions(pool: &MySqlPool) {
+    let collisions = sqlx::query(
+        "SELECT LOWER(username) AS canonical_username, GROUP_CONCAT(username ORDER BY username SEPARATOR ', ') AS usernames
+         FROM users
+         GROUP BY LOWER(username)
+         HAVING COUNT(DISTINCT BINARY username) > 1
+         ORDER BY canonical_username",
+    )
+    .fetch_all(pool)
+    .await;
+
+    match collisions {
+        Ok(collisions) if !collisions.is_empty() => {
+            tracing::error!(
+                "{} usernames collide when lowercased; rename or merge these users, then run the migrations again:",
+                collisions.len()
+            );
+            for row in &collisions {
+                let canonical: String = row.get("canonical_username");
+                let usernames: String = row.get("usernames");
+                tracing::error!("  {} <- {}", canonical, usernames);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Could not check for username collisions: {}", e),
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     tracing_subscriber::fmt::init();
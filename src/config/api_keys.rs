@@ -0,0 +1,52 @@
+use anyhow::{Context, Result};
+use std::{env, sync::Arc, time::Duration};
+
+use crate::api_keys::{ApiKeyConfig, ApiKeyService, IpRange};
+use crate::database::UserDatabase;
+
+/// Load API key settings from environment variables
+///
+/// - `API_KEY_CACHE_SECONDS`: how long key lookups are cached, and so how long a deleted key keeps working on other instances (default: 30)
+/// - `API_KEY_TRUSTED_PROXIES`: comma-separated addresses or CIDR ranges of reverse proxies whose `X-Forwarded-For` header is trusted (default: none)
+pub fn load_api_key_config() -> Result<ApiKeyConfig> {
+    let defaults = ApiKeyConfig::default();
+
+    Ok(ApiKeyConfig {
+        cache_ttl: env::var("API_KEY_CACHE_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map_or(defaults.cache_ttl, Duration::from_secs),
+        trusted_proxies: parse_trusted_proxies(&env::var("API_KEY_TRUSTED_PROXIES").unwrap_or_default())?,
+    })
+}
+
+pub fn parse_trusted_proxies(value: &str) -> Result<Vec<IpRange>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse()
+                .map_err(anyhow::Error::msg)
+                .context("Invalid API_KEY_TRUSTED_PROXIES entry")
+        })
+        .collect()
+}
+
+/// Create the API key service, backed by the user database
+pub fn create_api_key_service_from_env(database: Arc<dyn UserDatabase>) -> Result<ApiKeyService> {
+    Ok(ApiKeyService::new(database, load_api_key_config()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trusted_proxies() {
+        assert!(parse_trusted_proxies("").unwrap().is_empty());
+        assert_eq!(parse_trusted_proxies("10.0.0.0/8, 2001:db8::1").unwrap().len(), 2);
+        assert!(parse_trusted_proxies("10.0.0.0/8,proxy.internal").is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use std::{collections::HashSet, env};
+
+use crate::errors::AppError;
+use crate::middleware::AuthenticatedUser;
+
+/// Roles granted by the token's roles claim (see `JWT_ROLES_CLAIM`)
+#[derive(Debug, Clone)]
+pub struct AuthorizationConfig {
+    /// Roles allowed to edit any user of their tenant
+    pub admin_roles: HashSet<String>,
+    /// Roles allowed to review pending display names of their tenant
+    pub moderator_roles: HashSet<String>,
+}
+
+impl Default for AuthorizationConfig {
+    fn default() -> Self {
+        Self {
+            admin_roles: ["admin".to_string()].into(),
+            moderator_roles: ["moderator".to_string()].into(),
+        }
+    }
+}
+
+impl AuthorizationConfig {
+    pub fn is_admin(&self, user: &AuthenticatedUser) -> bool {
+        user.roles.iter().any(|role| self.admin_roles.contains(role))
+    }
+
+    /// Reject users without an admin role
+    pub fn require_admin(&self, user: &AuthenticatedUser) -> Result<(), AppError> {
+        if self.is_admin(user) {
+            Ok(())
+        } else {
+            tracing::warn!("User '{}' attempted an admin action without an admin role", user.username);
+            Err(AppError::forbidden("Admin role required")
+                .localized("admin-role-required", [])
+                .auth_failure("admin_role_required"))
+        }
+    }
+
+    /// Admins can moderate too
+    pub fn is_moderator(&self, user: &AuthenticatedUser) -> bool {
+        self.is_admin(user) || user.roles.iter().any(|role| self.moderator_roles.contains(role))
+    }
+
+    /// Reject users without a moderator or admin role
+    pub fn require_moderator(&self, user: &AuthenticatedUser) -> Result<(), AppError> {
+        if self.is_moderator(user) {
+            Ok(())
+        } else {
+            tracing::warn!(
+                "User '{}' attempted a moderation action without a moderator role",
+                user.username
+            );
+            Err(AppError::forbidden("Moderator permission required")
+                .localized("moderator-required", [])
+                .auth_failure("moderator_required"))
+        }
+    }
+}
+
+/// Load authorization configuration from environment variables
+///
+/// - `ADMIN_ROLES`: comma-separated roles allowed to edit other users (default: `admin`)
+/// - `MODERATOR_ROLES`: comma-separated roles allowed to review pending display names (default: `moderator`)
+pub fn load_authorization_config() -> AuthorizationConfig {
+    AuthorizationConfig {
+        admin_roles: roles_from_env("ADMIN_ROLES", "admin"),
+        moderator_roles: roles_from_env("MODERATOR_ROLES", "moderator"),
+    }
+}
+
+fn roles_from_env(name: &str, default: &str) -> HashSet<String> {
+    env::var(name)
+        .unwrap_or_else(|_| default.to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|role| !role.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(roles: &[&str]) -> AuthenticatedUser {
+        AuthenticatedUser {
+            username: "carol".to_string(),
+            subject: "carol".to_string(),
+            issuer: "test-auth-service".to_string(),
+            audience: "micro-frontend-service".into(),
+            display_name: None,
+            roles: roles.iter().map(|role| role.to_string()).collect(),
+            tenant: None,
+            issued_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_require_admin() {
+        let config = AuthorizationConfig {
+            admin_roles: ["admin".to_string(), "support".to_string()].into(),
+            ..AuthorizationConfig::default()
+        };
+
+        assert!(config.require_admin(&user(&["editor", "support"])).is_ok());
+        assert!(config.require_admin(&user(&["admin"])).is_ok());
+
+        let error = config.require_admin(&user(&["editor"])).unwrap_err();
+        assert_eq!(error.auth_failure, Some(crate::errors::AuthFailure("admin_role_required")));
+        assert!(config.require_admin(&user(&[])).is_err());
+    }
+
+    #[test]
+    fn test_require_moderator() {
+        let config = AuthorizationConfig::default();
+
+        assert!(config.require_moderator(&user(&["moderator"])).is_ok());
+        assert!(config.require_moderator(&user(&["admin"])).is_ok());
+
+        let error = config.require_moderator(&user(&["editor"])).unwrap_err();
+        assert_eq!(error.auth_failure, Some(crate::errors::AuthFailure("moderator_required")));
+        assert!(config.require_moderator(&user(&[])).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use anyhow::Result;
+use std::{env, sync::Arc};
+
+use crate::avatar::{directory::DirectoryAvatarStore, AvatarConfig, AvatarService, AvatarStore, DatabaseAvatarStore};
+use crate::database::UserDatabase;
+
+/// Load avatar limits from environment variables
+pub fn load_avatar_config() -> AvatarConfig {
+    let defaults = AvatarConfig::default();
+
+    AvatarConfig {
+        max_upload_bytes: env::var("AVATAR_MAX_UPLOAD_BYTES")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(defaults.max_upload_bytes),
+        max_dimension: env::var("AVATAR_MAX_DIMENSION")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(defaults.max_dimension),
+        size: env::var("AVATAR_SIZE")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(defaults.size),
+    }
+}
+
+/// Create the avatar service, storing avatars in the database (default) or in `AVATAR_DIRECTORY`
+pub fn create_avatar_service_from_env(database: Arc<dyn UserDatabase>) -> Result<AvatarService> {
+    let storage = env::var("AVATAR_STORAGE").unwrap_or_else(|_| "database".to_string());
+
+    let store: Arc<dyn AvatarStore> = match storage.as_str() {
+        "database" => Arc::new(DatabaseAvatarStore::new(database)),
+        "directory" => {
+            let directory = env::var("AVATAR_DIRECTORY").unwrap_or_else(|_| "data/avatars".to_string());
+            tracing::info!("Avatars stored in directory {}", directory);
+            Arc::new(DirectoryAvatarStore::new(directory)?)
+        }
+        other => anyhow::bail!("AVATAR_STORAGE must be 'database' or 'directory', got: {other}"),
+    };
+
+    Ok(AvatarService::new(store, load_avatar_config()))
+}
@@ -0,0 +1,20 @@
+use std::env;
+
+/// Optional display name checks, on top of the display name policy
+#[derive(Debug, Clone, Default)]
+pub struct DisplayNameConfig {
+    /// Reject mixed-script display names and names impersonating other users
+    pub confusable_check: bool,
+}
+
+/// Load display name settings from environment variables
+///
+/// - `ENABLE_DISPLAY_NAME_CONFUSABLE_CHECK`: `true` enables the confusable check (default: false)
+pub fn load_display_name_config() -> DisplayNameConfig {
+    let confusable_check = env::var("ENABLE_DISPLAY_NAME_CONFUSABLE_CHECK")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
+
+    DisplayNameConfig { confusable_check }
+}
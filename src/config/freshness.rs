@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use axum::http::Method;
+use std::{env, time::Duration};
+
+/// Routes open to recently issued tokens only, for sensitive edits
+#[derive(Debug, Clone)]
+pub struct FreshnessConfig {
+    /// Method, route pattern (as in the router, e.g. `/api/username/{username}`) and maximum token age
+    pub routes: Vec<(Method, String, Duration)>,
+}
+
+/// Display name edits require a token issued within 10 minutes unless configured otherwise
+pub const DEFAULT_FRESHNESS_ROUTES: &str = "POST /api/username=600,POST /api/username/{username}=600";
+
+impl Default for FreshnessConfig {
+    fn default() -> Self {
+        Self {
+            routes: parse_freshness_routes(DEFAULT_FRESHNESS_ROUTES).expect("default freshness routes are valid"),
+        }
+    }
+}
+
+impl FreshnessConfig {
+    /// Maximum token age for a request to the route `path` with `method`, if it has one
+    pub fn max_age(&self, method: &Method, path: &str) -> Option<Duration> {
+        self.routes
+            .iter()
+            .find(|(route_method, route_path, _)| route_method == method && route_path == path)
+            .map(|(_, _, max_age)| *max_age)
+    }
+}
+
+/// Load freshness requirements from environment variables
+///
+/// - `TOKEN_FRESHNESS_ROUTES`: comma-separated `METHOD /route=seconds` entries
+///   (default: [`DEFAULT_FRESHNESS_ROUTES`]; empty: no requirements)
+pub fn load_freshness_config() -> Result<FreshnessConfig> {
+    let routes = env::var("TOKEN_FRESHNESS_ROUTES").unwrap_or_else(|_| DEFAULT_FRESHNESS_ROUTES.to_string());
+    Ok(FreshnessConfig { routes: parse_freshness_routes(&routes)? })
+}
+
+/// Parse `TOKEN_FRESHNESS_ROUTES` (`POST /api/username=600,POST /api/avatar=300`)
+pub fn parse_freshness_routes(value: &str) -> Result<Vec<(Method, String, Duration)>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (route, seconds) = entry
+                .rsplit_once('=')
+                .with_context(|| format!("TOKEN_FRESHNESS_ROUTES entry '{entry}' must be METHOD /route=seconds"))?;
+            let (method, path) = route
+                .trim()
+                .split_once(' ')
+                .with_context(|| format!("TOKEN_FRESHNESS_ROUTES entry '{entry}' must be METHOD /route=seconds"))?;
+            let method = Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                .with_context(|| format!("TOKEN_FRESHNESS_ROUTES entry '{entry}' has an invalid method"))?;
+            let path = path.trim();
+            if !path.starts_with('/') {
+                anyhow::bail!("TOKEN_FRESHNESS_ROUTES entry '{entry}' must name a route starting with '/'");
+            }
+            let seconds: u64 = seconds
+                .trim()
+                .parse()
+                .with_context(|| format!("TOKEN_FRESHNESS_ROUTES entry '{entry}' must end in a number of seconds"))?;
+            Ok((method, path.to_string(), Duration::from_secs(seconds)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_freshness_routes() {
+        let config = FreshnessConfig {
+            routes: parse_freshness_routes(" post /api/username=600 , PUT /api/username/overrides/{app}=60,").unwrap(),
+        };
+        assert_eq!(config.max_age(&Method::POST, "/api/username"), Some(Duration::from_secs(600)));
+        assert_eq!(
+            config.max_age(&Method::PUT, "/api/username/overrides/{app}"),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(config.max_age(&Method::DELETE, "/api/username/overrides/{app}"), None);
+        assert_eq!(config.max_age(&Method::POST, "/api/avatar"), None);
+
+        assert!(parse_freshness_routes("").unwrap().is_empty());
+        for invalid in [
+            "POST /api/username",
+            "/api/username=600",
+            "POST api/username=600",
+            "POST /api/username=soon",
+        ] {
+            assert!(parse_freshness_routes(invalid).is_err(), "{invalid}");
+        }
+        assert_eq!(
+            FreshnessConfig::default().max_age(&Method::POST, "/api/username/{username}"),
+            Some(Duration::from_secs(600))
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{error, info};
+
+use crate::middleware::jwt_auth::JwtVerifier;
+
+/// How often the key and issuer files are checked for changes
+pub const KEY_FILE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Build the JWT verifier, failing on a missing or invalid key, algorithm or issuer list.
+///
+/// JWKS are only fetched when a token needs their keys, so an unreachable JWKS URL is not
+/// detected here; `validate_environment` only checks that `JWT_JWKS_URL` is an http(s) URL.
+pub fn create_jwt_verifier_from_env() -> Result<JwtVerifier> {
+    JwtVerifier::from_env()
+}
+
+/// Reload the verifier on SIGHUP, and whenever one of the files it was read from is modified.
+///
+/// A reload that fails (such as a half-written key file) is logged and the current
+/// configuration stays in use.
+pub fn spawn_jwt_reloader(verifier: JwtVerifier, poll_interval: Duration) -> Result<()> {
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut poll = tokio::time::interval(poll_interval);
+    let mut watched = watched_files(&verifier);
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = hangup.recv() => info!("SIGHUP received, reloading JWT configuration"),
+                _ = poll.tick(), if !watched.is_empty() => {
+                    if watched_files(&verifier) == watched {
+                        continue;
+                    }
+                    info!("JWT key file changed, reloading JWT configuration");
+                }
+            }
+
+            if let Err(e) = verifier.reload() {
+                error!("Failed to reload JWT configuration, keeping the current one: {:#}", e);
+            }
+            watched = watched_files(&verifier);
+        }
+    });
+
+    Ok(())
+}
+
+/// Files of the current configuration with their modification times
+fn watched_files(verifier: &JwtVerifier) -> Vec<(PathBuf, Option<SystemTime>)> {
+    verifier
+        .config()
+        .watched_files()
+        .into_iter()
+        .map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|metadata| metadata.modified()).ok();
+            (path, modified)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::jwt_auth::{IssuerConfig, JwtConfig, VerificationKeys};
+    use crate::tests::test_keys::{generate_key_pair, RSA_KEY};
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+
+    fn sign(key: &EncodingKey) -> String {
+        let now = chrono::Utc::now().timestamp();
+        let claims = serde_json::json!({
+            "sub": "admin",
+            "iat": now,
+            "exp": now + 3600,
+            "aud": "micro-frontend-service",
+            "iss": "test-auth-service",
+        });
+        encode(&Header::new(Algorithm::RS256), &claims, key).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_key_file_rotation_reloads_verifier() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = generate_key_pair(dir.path(), "old", RSA_KEY);
+        let new = generate_key_pair(dir.path(), "new", RSA_KEY);
+        let (old_key, new_key) = (old.encoding_key(Algorithm::RS256), new.encoding_key(Algorithm::RS256));
+
+        let key_file = dir.path().join("jwt_public_key.pem");
+        std::fs::copy(&old.public_key_file, &key_file).unwrap();
+
+        let loader_key_file = key_file.clone();
+        let verifier = JwtVerifier::new(move || {
+            let mut issuer = IssuerConfig::new(
+                "test-auth-service",
+                VerificationKeys::from_pem_file(&loader_key_file, Algorithm::RS256)?,
+                Algorithm::RS256,
+                &["micro-frontend-service".to_string()],
+            )?;
+            issuer.key_file = Some(loader_key_file.clone());
+            Ok(JwtConfig::new(vec![issuer]))
+        })
+        .unwrap();
+        spawn_jwt_reloader(verifier.clone(), Duration::from_millis(20)).unwrap();
+
+        assert!(verifier.config().decode(&sign(&old_key)).await.is_ok());
+        assert!(verifier.config().decode(&sign(&new_key)).await.is_err());
+
+        // A broken key file is rejected and the current key keeps working
+        std::fs::write(&key_file, "not a key").unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(verifier.config().decode(&sign(&old_key)).await.is_ok());
+
+        std::fs::copy(&new.public_key_file, &key_file).unwrap();
+        let filetime = SystemTime::now() + Duration::from_secs(1);
+        std::fs::File::options()
+            .write(true)
+            .open(&key_file)
+            .unwrap()
+            .set_modified(filetime)
+            .unwrap();
+
+        let mut rotated = false;
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            if verifier.config().decode(&sign(&new_key)).await.is_ok() {
+                rotated = true;
+                break;
+            }
+        }
+        assert!(rotated, "the rotated key file should have been picked up");
+        assert!(verifier.config().decode(&sign(&old_key)).await.is_err());
+    }
+
+    #[test]
+    fn test_invalid_configuration_fails_to_build() {
+        let result = JwtVerifier::new(|| {
+            let keys = VerificationKeys::from_pem("not a key", Algorithm::RS256)?;
+            Ok(JwtConfig::new(vec![IssuerConfig::new(
+                "test-auth-service",
+                keys,
+                Algorithm::RS256,
+                &[],
+            )?]))
+        });
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,15 @@
+pub mod api_keys;
+pub mod authorization;
+pub mod avatar;
 pub mod database;
+pub mod display_name;
+pub mod freshness;
+pub mod jwt;
+pub mod moderation;
+pub mod rate_limit;
+pub mod revocation;
+pub mod session;
+pub mod tenancy;
 
 // Only export the modules without re-exporting specific functions
 // This avoids unused import warnings while keeping the module structure
@@ -0,0 +1,18 @@
+use std::env;
+
+/// Display name moderation settings; who may moderate is an `AuthorizationConfig` role
+#[derive(Debug, Clone, Default)]
+pub struct ModerationConfig {
+    /// When enabled, display name changes are held for review instead of published
+    pub enabled: bool,
+}
+
+/// Load moderation configuration from environment variables
+pub fn load_moderation_config() -> ModerationConfig {
+    let enabled = env::var("ENABLE_DISPLAY_NAME_MODERATION")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .unwrap_or(false);
+
+    ModerationConfig { enabled }
+}
@@ -0,0 +1,45 @@
+use anyhow::{anyhow, bail, Result};
+use std::{env, time::Duration};
+
+/// Per-client request limits of the rate limited routes
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// When disabled, no request is rejected
+    pub enabled: bool,
+    /// Requests a client may make per `window`
+    pub max_requests: usize,
+    pub window: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_requests: 60,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Load rate limits from environment variables
+///
+/// - `ENABLE_RATE_LIMITING`: reject clients over the limit with `429 Too Many Requests` (default: true)
+/// - `RATE_LIMIT_REQUESTS_PER_MINUTE`: requests per client and minute, 1-1000 (default: 60)
+pub fn load_rate_limit_config() -> Result<RateLimitConfig> {
+    let mut config = RateLimitConfig::default();
+
+    if let Ok(value) = env::var("ENABLE_RATE_LIMITING") {
+        config.enabled = value
+            .parse()
+            .map_err(|_| anyhow!("ENABLE_RATE_LIMITING must be true or false"))?;
+    }
+
+    if let Ok(value) = env::var("RATE_LIMIT_REQUESTS_PER_MINUTE") {
+        match value.parse::<usize>() {
+            Ok(max_requests) if (1..=1000).contains(&max_requests) => config.max_requests = max_requests,
+            _ => bail!("RATE_LIMIT_REQUESTS_PER_MINUTE must be a number from 1 to 1000, got: {value}"),
+        }
+    }
+
+    Ok(config)
+}
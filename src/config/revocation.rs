@@ -0,0 +1,26 @@
+use std::{env, sync::Arc, time::Duration};
+
+use crate::database::UserDatabase;
+use crate::revocation::{RevocationConfig, RevocationService};
+
+/// How often expired revocations are deleted from the database
+pub const REVOCATION_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Load revocation timings from environment variables
+///
+/// - `TOKEN_REVOCATION_RETENTION_SECONDS`: how long revocations without a known expiry are kept (default: 86400)
+/// - `TOKEN_REVOCATION_REFRESH_SECONDS`: how often revocations made on other instances are loaded (default: 30)
+pub fn load_revocation_config() -> RevocationConfig {
+    let defaults = RevocationConfig::default();
+    let seconds = |var: &str| env::var(var).ok().and_then(|value| value.parse().ok()).map(Duration::from_secs);
+
+    RevocationConfig {
+        retention: seconds("TOKEN_REVOCATION_RETENTION_SECONDS").unwrap_or(defaults.retention),
+        refresh_interval: seconds("TOKEN_REVOCATION_REFRESH_SECONDS").unwrap_or(defaults.refresh_interval),
+    }
+}
+
+/// Create the revocation service, backed by the user database
+pub fn create_revocation_service_from_env(database: Arc<dyn UserDatabase>) -> RevocationService {
+    RevocationService::new(database, load_revocation_config())
+}
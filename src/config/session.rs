@@ -0,0 +1,144 @@
+use anyhow::{bail, Result};
+use axum::http::{header, HeaderMap};
+use std::env;
+
+/// The session cookie set by `POST /auth/session`, which holds the exchanged token
+#[derive(Debug, Clone)]
+pub struct SessionConfig {
+    pub cookie_name: String,
+    /// Send the cookie over HTTPS only
+    pub secure: bool,
+    /// `SameSite` attribute: `Strict`, `Lax` or `None`
+    pub same_site: String,
+}
+
+/// Host-only (no `Domain`), `Path=/` and `Secure`, so browsers keep it to this service
+pub const DEFAULT_SESSION_COOKIE_NAME: &str = "__Host-session";
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            cookie_name: DEFAULT_SESSION_COOKIE_NAME.to_string(),
+            secure: true,
+            same_site: "Strict".to_string(),
+        }
+    }
+}
+
+impl SessionConfig {
+    /// `Set-Cookie` value storing `token` until `max_age_seconds` from now
+    pub fn session_cookie(&self, token: &str, max_age_seconds: u64) -> String {
+        format!("{}={token}; Max-Age={max_age_seconds}{}", self.cookie_name, self.attributes())
+    }
+
+    /// `Set-Cookie` value removing the session cookie
+    pub fn cleared_cookie(&self) -> String {
+        format!("{}=; Max-Age=0{}", self.cookie_name, self.attributes())
+    }
+
+    fn attributes(&self) -> String {
+        let secure = if self.secure { "; Secure" } else { "" };
+        format!("; Path=/; HttpOnly{secure}; SameSite={}", self.same_site)
+    }
+
+    /// The token held by the request's session cookie, if any
+    pub fn token<'a>(&self, headers: &'a HeaderMap) -> Option<&'a str> {
+        headers
+            .get_all(header::COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .find_map(|cookie| {
+                let (name, value) = cookie.trim().split_once('=')?;
+                (name == self.cookie_name && !value.is_empty()).then_some(value)
+            })
+    }
+}
+
+/// Load session cookie settings from environment variables
+///
+/// - `SESSION_COOKIE_NAME`: cookie name (default: [`DEFAULT_SESSION_COOKIE_NAME`])
+/// - `SESSION_COOKIE_SECURE`: `false` to allow the cookie over plain HTTP, for local development (default: `true`)
+/// - `SESSION_COOKIE_SAMESITE`: `Strict`, `Lax` or `None` (default: `Strict`)
+pub fn load_session_config() -> Result<SessionConfig> {
+    let defaults = SessionConfig::default();
+    let cookie_name = env::var("SESSION_COOKIE_NAME").unwrap_or(defaults.cookie_name);
+    let secure = env::var("SESSION_COOKIE_SECURE").map_or(defaults.secure, |value| value != "false");
+    let same_site = match env::var("SESSION_COOKIE_SAMESITE") {
+        Ok(value) => parse_same_site(&value)?,
+        Err(_) => defaults.same_site,
+    };
+
+    let config = SessionConfig { cookie_name, secure, same_site };
+    validate_session_config(&config)?;
+    Ok(config)
+}
+
+fn parse_same_site(value: &str) -> Result<String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "strict" => Ok("Strict".to_string()),
+        "lax" => Ok("Lax".to_string()),
+        "none" => Ok("None".to_string()),
+        _ => bail!("SESSION_COOKIE_SAMESITE must be Strict, Lax or None, got '{value}'"),
+    }
+}
+
+fn validate_session_config(config: &SessionConfig) -> Result<()> {
+    let name = &config.cookie_name;
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c)) {
+        bail!("SESSION_COOKIE_NAME must be non-empty and contain only letters, digits, '-', '_' and '.'");
+    }
+    if !config.secure && (name.starts_with("__Host-") || name.starts_with("__Secure-")) {
+        bail!("SESSION_COOKIE_NAME '{name}' requires SESSION_COOKIE_SECURE=true");
+    }
+    if !config.secure && config.same_site == "None" {
+        bail!("SESSION_COOKIE_SAMESITE=None requires SESSION_COOKIE_SECURE=true");
+    }
+    if name == "jwt_token" {
+        bail!("SESSION_COOKIE_NAME must not be the legacy jwt_token cookie");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_session_cookies() {
+        let config = SessionConfig::default();
+        assert_eq!(
+            config.session_cookie("abc.def.ghi", 3600),
+            "__Host-session=abc.def.ghi; Max-Age=3600; Path=/; HttpOnly; Secure; SameSite=Strict"
+        );
+        assert_eq!(
+            config.cleared_cookie(),
+            "__Host-session=; Max-Age=0; Path=/; HttpOnly; Secure; SameSite=Strict"
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.append(header::COOKIE, HeaderValue::from_static("lang=fr; jwt_token=legacy"));
+        assert_eq!(config.token(&headers), None);
+        headers.append(header::COOKIE, HeaderValue::from_static("__Host-session=abc.def.ghi"));
+        assert_eq!(config.token(&headers), Some("abc.def.ghi"));
+    }
+
+    #[test]
+    fn test_session_config_validation() {
+        assert_eq!(parse_same_site("lax").unwrap(), "Lax");
+        assert!(parse_same_site("sometimes").is_err());
+
+        let insecure = |cookie_name: &str, same_site: &str| SessionConfig {
+            cookie_name: cookie_name.to_string(),
+            secure: false,
+            same_site: same_site.to_string(),
+        };
+        assert!(validate_session_config(&SessionConfig::default()).is_ok());
+        assert!(validate_session_config(&insecure("mfe_session", "Lax")).is_ok());
+        assert!(validate_session_config(&insecure("__Host-session", "Lax")).is_err());
+        assert!(validate_session_config(&insecure("mfe_session", "None")).is_err());
+        assert!(validate_session_config(&insecure("bad name", "Lax")).is_err());
+        assert!(validate_session_config(&insecure("jwt_token", "Lax")).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use std::{collections::HashMap, env};
+
+use crate::errors::AppError;
+use crate::middleware::AuthenticatedUser;
+use crate::tenant::Tenant;
+
+/// How the tenant of a request is resolved
+#[derive(Debug, Clone, Default)]
+pub struct TenancyConfig {
+    /// JWT claim holding the tenant id on authenticated routes; `None` keeps the host's tenant
+    pub claim: Option<String>,
+    /// Lowercase host names (without port) mapped to their tenant
+    pub hosts: HashMap<String, Tenant>,
+    /// Tenant for hosts that are not mapped
+    pub default_tenant: Tenant,
+}
+
+impl TenancyConfig {
+    /// Tenant for a `Host` header value; unknown or missing hosts get the default tenant
+    pub fn tenant_for_host(&self, host: Option<&str>) -> Tenant {
+        host.map(strip_port)
+            .and_then(|host| self.hosts.get(&host.to_ascii_lowercase()))
+            .unwrap_or(&self.default_tenant)
+            .clone()
+    }
+
+    /// Tenant for an authenticated request, given the tenant of the host it arrived on.
+    ///
+    /// With `TENANT_CLAIM` configured, tokens must carry a valid tenant id in that claim, and
+    /// on a host mapped to a tenant the claim must name that same tenant, so a token issued
+    /// for one brand cannot be replayed against another.
+    pub fn tenant_for_user(&self, user: &AuthenticatedUser, host_tenant: Tenant) -> Result<Tenant, AppError> {
+        let Some(claim) = &self.claim else {
+            return Ok(host_tenant);
+        };
+
+        let tenant = user
+            .tenant
+            .as_deref()
+            .and_then(|value| Tenant::new(value).ok())
+            .ok_or_else(|| {
+                tracing::warn!("Token for '{}' has no valid '{}' tenant claim", user.username, claim);
+                AppError::forbidden("Token does not name a valid tenant")
+                    .localized("tenant-claim-invalid", [])
+                    .auth_failure("tenant_claim_invalid")
+            })?;
+
+        let host_is_mapped = self.hosts.values().any(|mapped| *mapped == host_tenant);
+        if host_is_mapped && tenant != host_tenant {
+            tracing::warn!(
+                "Token for tenant '{}' used on a host of tenant '{}' by '{}'",
+                tenant,
+                host_tenant,
+                user.username
+            );
+            return Err(AppError::forbidden("Token was issued for a different tenant")
+                .localized("tenant-mismatch", [])
+                .auth_failure("tenant_mismatch"));
+        }
+
+        Ok(tenant)
+    }
+}
+
+fn strip_port(host: &str) -> &str {
+    // Bracketed IPv6 literals contain colons of their own
+    if let Some(end) = host.strip_prefix('[').and_then(|rest| rest.find(']')) {
+        return &host[..end + 2];
+    }
+    host.split(':').next().unwrap_or(host)
+}
+
+/// Load tenancy configuration from environment variables
+///
+/// - `TENANT_CLAIM`: JWT claim carrying the tenant id (unset: tenant comes from the host only)
+/// - `TENANT_HOSTS`: comma-separated `host=tenant` pairs
+/// - `DEFAULT_TENANT`: tenant for unmapped hosts (default: `default`)
+pub fn load_tenancy_config() -> Result<TenancyConfig> {
+    let claim = env::var("TENANT_CLAIM")
+        .ok()
+        .map(|claim| claim.trim().to_string())
+        .filter(|claim| !claim.is_empty());
+
+    let default_tenant = match env::var("DEFAULT_TENANT") {
+        Ok(tenant) => Tenant::new(&tenant).map_err(|e| anyhow::anyhow!("DEFAULT_TENANT: {}", e.message))?,
+        Err(_) => Tenant::default(),
+    };
+
+    let hosts = parse_tenant_hosts(&env::var("TENANT_HOSTS").unwrap_or_default())?;
+
+    Ok(TenancyConfig { claim, hosts, default_tenant })
+}
+
+/// Parse `TENANT_HOSTS` (`brand-a.example=brand-a,brand-b.example=brand-b`)
+pub fn parse_tenant_hosts(value: &str) -> Result<HashMap<String, Tenant>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (host, tenant) = entry
+                .split_once('=')
+                .with_context(|| format!("TENANT_HOSTS entry '{entry}' must be host=tenant"))?;
+            let tenant =
+                Tenant::new(tenant).map_err(|e| anyhow::anyhow!("TENANT_HOSTS entry '{}': {}", entry, e.message))?;
+            Ok((strip_port(host.trim()).to_ascii_lowercase(), tenant))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(tenant: Option<&str>) -> AuthenticatedUser {
+        AuthenticatedUser {
+            username: "testuser".to_string(),
+            subject: "testuser".to_string(),
+            issuer: "test-auth-service".to_string(),
+            audience: "micro-frontend-service".into(),
+            display_name: None,
+            roles: Vec::new(),
+            tenant: tenant.map(str::to_string),
+            issued_at: 0,
+        }
+    }
+
+    fn config() -> TenancyConfig {
+        TenancyConfig {
+            claim: Some("tenant".to_string()),
+            hosts: parse_tenant_hosts("brand-a.example=brand-a, Brand-B.example:8080=brand-b").unwrap(),
+            default_tenant: Tenant::default(),
+        }
+    }
+
+    #[test]
+    fn test_tenant_for_host() {
+        let config = config();
+        let brand_a = Tenant::new("brand-a").unwrap();
+
+        assert_eq!(config.tenant_for_host(Some("brand-a.example")), brand_a);
+        assert_eq!(config.tenant_for_host(Some("BRAND-A.example:3000")), brand_a);
+        assert_eq!(config.tenant_for_host(Some("brand-b.example")), Tenant::new("brand-b").unwrap());
+        assert_eq!(config.tenant_for_host(Some("other.example")), Tenant::default());
+        assert_eq!(config.tenant_for_host(None), Tenant::default());
+        assert_eq!(strip_port("[::1]:8080"), "[::1]");
+    }
+
+    #[test]
+    fn test_tenant_for_user() {
+        let config = config();
+        let brand_a = Tenant::new("brand-a").unwrap();
+
+        // The claim selects the tenant on unmapped hosts, and must agree with mapped ones
+        assert_eq!(
+            config.tenant_for_user(&user(Some("Brand-A")), Tenant::default()).unwrap(),
+            brand_a
+        );
+        assert!(config.tenant_for_user(&user(Some("brand-a")), brand_a.clone()).is_ok());
+        assert!(config.tenant_for_user(&user(Some("brand-b")), brand_a.clone()).is_err());
+        assert!(config.tenant_for_user(&user(None), Tenant::default()).is_err());
+        assert!(config.tenant_for_user(&user(Some("not a tenant")), Tenant::default()).is_err());
+
+        // Without a claim configured the host decides
+        let config = TenancyConfig::default();
+        assert_eq!(
+            config.tenant_for_user(&user(Some("brand-b")), brand_a.clone()).unwrap(),
+            brand_a
+        );
+    }
+
+    #[test]
+    fn test_parse_tenant_hosts_rejects_malformed_entries() {
+        assert!(parse_tenant_hosts("").unwrap().is_empty());
+        assert!(parse_tenant_hosts("brand-a.example").is_err());
+        assert!(parse_tenant_hosts("brand-a.example=not a tenant").is_err());
+    }
+}
@@ -1,6 +1,12 @@
-use super::{User, UserDatabase};
+use super::{
+    ApiKey, PendingDisplayName, Revocations, RevokedSubject, RevokedToken, StoredAvatar, User, UserDatabase,
+    UserProfile,
+};
+use crate::tenant::Tenant;
+use crate::validation::canonical_username;
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
@@ -13,10 +19,13 @@ struct CacheEntry {
     expires_at: Instant,
 }
 
+/// Cache key: the same username in two tenants is two different users
+type CacheKey = (Tenant, String);
+
 /// Database caching layer for improved performance
 pub struct CachedUserDatabase {
     inner: Arc<dyn UserDatabase>,
-    cache: Arc<RwLock<HashMap<String, CacheEntry>>>,
+    cache: Arc<RwLock<HashMap<CacheKey, CacheEntry>>>,
     cache_ttl: Duration,
     cache_enabled: bool,
 }
@@ -38,20 +47,20 @@ impl CachedUserDatabase {
     }
 
     /// Get user from cache if available and not expired
-    fn get_cached_user(&self, username: &str) -> Option<Option<User>> {
+    fn get_cached_user(&self, tenant: &Tenant, username: &str) -> Option<Option<User>> {
         if !self.cache_enabled {
             return None;
         }
 
         let cache = self.cache.read().ok()?;
 
-        if let Some(entry) = cache.get(username) {
+        if let Some(entry) = cache.get(&(tenant.clone(), canonical_username(username))) {
             if entry.expires_at > Instant::now() {
                 debug!("Database cache hit for user: {}", username);
 
                 // Track cache hit metric
                 if let Some(metrics) = crate::router::get_metrics_instance() {
-                    crate::metrics::track_cache_hit(metrics, "user_cache");
+                    crate::metrics::track_cache_hit(metrics, tenant, "user_cache");
                 }
 
                 return Some(entry.user.clone());
@@ -60,13 +69,13 @@ impl CachedUserDatabase {
 
                 // Expired entries are effectively misses
                 if let Some(metrics) = crate::router::get_metrics_instance() {
-                    crate::metrics::track_cache_miss(metrics, "user_cache");
+                    crate::metrics::track_cache_miss(metrics, tenant, "user_cache");
                 }
             }
         } else {
             // Track cache miss metric
             if let Some(metrics) = crate::router::get_metrics_instance() {
-                crate::metrics::track_cache_miss(metrics, "user_cache");
+                crate::metrics::track_cache_miss(metrics, tenant, "user_cache");
             }
         }
 
@@ -74,7 +83,7 @@ impl CachedUserDatabase {
     }
 
     /// Store user in cache
-    fn cache_user(&self, username: &str, user: Option<User>) {
+    fn cache_user(&self, tenant: &Tenant, username: &str, user: Option<User>) {
         if !self.cache_enabled {
             return;
         }
@@ -91,19 +100,19 @@ impl CachedUserDatabase {
                 expires_at: Instant::now() + self.cache_ttl,
             };
 
-            cache.insert(username.to_string(), entry);
+            cache.insert((tenant.clone(), canonical_username(username)), entry);
             debug!("Database cache stored for user: {}", username);
         }
     }
 
     /// Invalidate cache entry for a specific user
-    fn invalidate_user_cache(&self, username: &str) {
+    fn invalidate_user_cache(&self, tenant: &Tenant, username: &str) {
         if !self.cache_enabled {
             return;
         }
 
         if let Ok(mut cache) = self.cache.write() {
-            cache.remove(username);
+            cache.remove(&(tenant.clone(), canonical_username(username)));
             debug!("Database cache invalidated for user: {}", username);
         }
     }
@@ -133,28 +142,39 @@ impl CachedUserDatabase {
 
 #[async_trait]
 impl UserDatabase for CachedUserDatabase {
-    async fn get_user(&self, username: &str) -> Result<Option<User>> {
+    async fn get_user(&self, tenant: &Tenant, username: &str) -> Result<Option<User>> {
         // Check cache first
-        if let Some(cached_user) = self.get_cached_user(username) {
+        if let Some(cached_user) = self.get_cached_user(tenant, username) {
             return Ok(cached_user);
         }
 
         // Cache miss - fetch from database
         debug!("Database cache miss for user: {}", username);
-        let user = self.inner.get_user(username).await?;
+        let user = self.inner.get_user(tenant, username).await?;
 
         // Cache the result
-        self.cache_user(username, user.clone());
+        self.cache_user(tenant, username, user.clone());
 
         Ok(user)
     }
 
-    async fn update_user_display_name(&self, username: &str, display_name: &str) -> Result<()> {
+    async fn existing_usernames(&self, tenant: &Tenant, usernames: &[String]) -> Result<Vec<String>> {
+        self.inner.existing_usernames(tenant, usernames).await
+    }
+
+    async fn update_user_display_name(&self, tenant: &Tenant, username: &str, display_name: &str) -> Result<()> {
         // Update in database
-        self.inner.update_user_display_name(username, display_name).await?;
+        self.inner.update_user_display_name(tenant, username, display_name).await?;
 
         // Invalidate cache to ensure fresh data on next read
-        self.invalidate_user_cache(username);
+        self.invalidate_user_cache(tenant, username);
+
+        Ok(())
+    }
+
+    async fn update_user_profile(&self, tenant: &Tenant, username: &str, profile: &UserProfile) -> Result<()> {
+        self.inner.update_user_profile(tenant, username, profile).await?;
+        self.invalidate_user_cache(tenant, username);
 
         Ok(())
     }
@@ -163,4 +183,130 @@ impl UserDatabase for CachedUserDatabase {
         // Health check should always go to the database
         self.inner.health_check().await
     }
+
+    async fn set_display_name_override(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        app: &str,
+        display_name: &str,
+        max_apps: usize,
+    ) -> Result<bool> {
+        let set = self
+            .inner
+            .set_display_name_override(tenant, username, app, display_name, max_apps)
+            .await?;
+        self.invalidate_user_cache(tenant, username);
+
+        Ok(set)
+    }
+
+    async fn delete_display_name_override(&self, tenant: &Tenant, username: &str, app: &str) -> Result<bool> {
+        let deleted = self.inner.delete_display_name_override(tenant, username, app).await?;
+        self.invalidate_user_cache(tenant, username);
+
+        Ok(deleted)
+    }
+
+    async fn set_localized_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        locale: &str,
+        display_name: &str,
+        max_locales: usize,
+    ) -> Result<bool> {
+        let set = self
+            .inner
+            .set_localized_display_name(tenant, username, locale, display_name, max_locales)
+            .await?;
+        self.invalidate_user_cache(tenant, username);
+
+        Ok(set)
+    }
+
+    async fn delete_localized_display_name(&self, tenant: &Tenant, username: &str, locale: &str) -> Result<bool> {
+        let deleted = self.inner.delete_localized_display_name(tenant, username, locale).await?;
+        self.invalidate_user_cache(tenant, username);
+
+        Ok(deleted)
+    }
+
+    async fn submit_pending_display_name(&self, tenant: &Tenant, username: &str, display_name: &str) -> Result<()> {
+        // Pending names are not published, so cached users stay valid
+        self.inner.submit_pending_display_name(tenant, username, display_name).await
+    }
+
+    async fn get_pending_display_name(&self, tenant: &Tenant, username: &str) -> Result<Option<PendingDisplayName>> {
+        self.inner.get_pending_display_name(tenant, username).await
+    }
+
+    async fn list_pending_display_names(&self, tenant: &Tenant) -> Result<Vec<PendingDisplayName>> {
+        self.inner.list_pending_display_names(tenant).await
+    }
+
+    async fn approve_pending_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+    ) -> Result<Option<PendingDisplayName>> {
+        let approved = self.inner.approve_pending_display_name(tenant, username).await?;
+
+        // Approval publishes the name, so the cached user is stale
+        self.invalidate_user_cache(tenant, username);
+
+        Ok(approved)
+    }
+
+    async fn reject_pending_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        reason: &str,
+    ) -> Result<Option<PendingDisplayName>> {
+        self.inner.reject_pending_display_name(tenant, username, reason).await
+    }
+
+    async fn get_avatar(&self, tenant: &Tenant, username: &str) -> Result<Option<StoredAvatar>> {
+        // Avatars are served with their own HTTP caching and would crowd out user entries
+        self.inner.get_avatar(tenant, username).await
+    }
+
+    async fn put_avatar(&self, tenant: &Tenant, username: &str, avatar: &StoredAvatar) -> Result<()> {
+        self.inner.put_avatar(tenant, username, avatar).await
+    }
+
+    // Revocations have their own cache in `RevocationService`
+    async fn revoke_token(&self, tenant: &Tenant, revoked: &RevokedToken) -> Result<()> {
+        self.inner.revoke_token(tenant, revoked).await
+    }
+
+    async fn revoke_subject(&self, tenant: &Tenant, revoked: &RevokedSubject) -> Result<()> {
+        self.inner.revoke_subject(tenant, revoked).await
+    }
+
+    async fn get_revocations(&self, now: DateTime<Utc>) -> Result<Revocations> {
+        self.inner.get_revocations(now).await
+    }
+
+    async fn delete_expired_revocations(&self, now: DateTime<Utc>) -> Result<u64> {
+        self.inner.delete_expired_revocations(now).await
+    }
+
+    // API key lookups have their own cache in `ApiKeyService`
+    async fn create_api_key(&self, tenant: &Tenant, key: &ApiKey) -> Result<()> {
+        self.inner.create_api_key(tenant, key).await
+    }
+
+    async fn list_api_keys(&self, tenant: &Tenant) -> Result<Vec<ApiKey>> {
+        self.inner.list_api_keys(tenant).await
+    }
+
+    async fn find_api_key(&self, tenant: &Tenant, key_hash: &str) -> Result<Option<ApiKey>> {
+        self.inner.find_api_key(tenant, key_hash).await
+    }
+
+    async fn delete_api_key(&self, tenant: &Tenant, id: &str) -> Result<bool> {
+        self.inner.delete_api_key(tenant, id).await
+    }
 }
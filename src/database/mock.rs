@@ -1,23 +1,46 @@
-use super::{User, UserDatabase};
+use super::{
+    ApiKey, ModerationStatus, PendingDisplayName, Revocations, RevokedSubject, RevokedToken, StoredAvatar, User,
+    UserDatabase, UserProfile,
+};
+use crate::tenant::Tenant;
+use crate::validation::canonical_username;
 use anyhow::Result;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Records of one kind, kept in a separate map per tenant
+type TenantMap<T> = Arc<RwLock<HashMap<Tenant, HashMap<String, T>>>>;
+
+/// Revocations by tenant, issuer and `jti` or subject
+type RevocationMap<T> = Arc<RwLock<HashMap<(Tenant, String, String), T>>>;
+
 pub struct MockUserDatabase {
-    users: Arc<RwLock<HashMap<String, User>>>,
+    users: TenantMap<User>,
+    pending: TenantMap<PendingDisplayName>,
+    avatars: TenantMap<StoredAvatar>,
+    revoked_tokens: RevocationMap<RevokedToken>,
+    revoked_subjects: RevocationMap<RevokedSubject>,
+    api_keys: TenantMap<ApiKey>,
 }
 
 impl MockUserDatabase {
     pub fn new() -> Self {
         let mut users = HashMap::new();
+        let now = Utc::now();
 
         users.insert(
             "admin".to_string(),
             User {
                 username: "admin".to_string(),
                 display_name: "Administrator".to_string(),
+                profile: UserProfile::default(),
+                display_name_overrides: BTreeMap::new(),
+                localized_display_names: BTreeMap::new(),
+                created_at: now,
+                updated_at: now,
             },
         );
 
@@ -26,6 +49,11 @@ impl MockUserDatabase {
             User {
                 username: "johndoe".to_string(),
                 display_name: "John Doe".to_string(),
+                profile: UserProfile::default(),
+                display_name_overrides: BTreeMap::new(),
+                localized_display_names: BTreeMap::new(),
+                created_at: now,
+                updated_at: now,
             },
         );
 
@@ -34,6 +62,11 @@ impl MockUserDatabase {
             User {
                 username: "alice".to_string(),
                 display_name: "Alice Smith".to_string(),
+                profile: UserProfile::default(),
+                display_name_overrides: BTreeMap::new(),
+                localized_display_names: BTreeMap::new(),
+                created_at: now,
+                updated_at: now,
             },
         );
 
@@ -42,54 +75,101 @@ impl MockUserDatabase {
             User {
                 username: "testuser".to_string(),
                 display_name: "testuser".to_string(),
+                profile: UserProfile::default(),
+                display_name_overrides: BTreeMap::new(),
+                localized_display_names: BTreeMap::new(),
+                created_at: now,
+                updated_at: now,
             },
         );
 
-        tracing::info!("Mock database initialized with {} sample users", users.len());
+        tracing::info!(
+            "Mock database initialized with {} sample users in the default tenant",
+            users.len()
+        );
 
-        Self { users: Arc::new(RwLock::new(users)) }
+        Self {
+            users: Arc::new(RwLock::new(HashMap::from([(Tenant::default(), users)]))),
+            pending: Arc::new(RwLock::new(HashMap::new())),
+            avatars: Arc::new(RwLock::new(HashMap::new())),
+            revoked_tokens: Arc::new(RwLock::new(HashMap::new())),
+            revoked_subjects: Arc::new(RwLock::new(HashMap::new())),
+            api_keys: Arc::new(RwLock::new(HashMap::new())),
+        }
     }
 
     #[allow(dead_code)]
     pub fn new_empty() -> Self {
         Self {
             users: Arc::new(RwLock::new(HashMap::new())),
+            pending: Arc::new(RwLock::new(HashMap::new())),
+            avatars: Arc::new(RwLock::new(HashMap::new())),
+            revoked_tokens: Arc::new(RwLock::new(HashMap::new())),
+            revoked_subjects: Arc::new(RwLock::new(HashMap::new())),
+            api_keys: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Number of users across all tenants
     pub async fn user_count(&self) -> usize {
         let users = self.users.read().await;
-        users.len()
+        users.values().map(HashMap::len).sum()
     }
 
     #[allow(dead_code)]
-    async fn user_exists(&self, username: &str) -> Result<bool> {
+    async fn user_exists(&self, tenant: &Tenant, username: &str) -> Result<bool> {
         let users = self.users.read().await;
-        Ok(users.contains_key(username))
+        Ok(users
+            .get(tenant)
+            .is_some_and(|users| users.contains_key(&canonical_username(username))))
     }
 }
 
 #[async_trait]
 impl UserDatabase for MockUserDatabase {
-    async fn get_user(&self, username: &str) -> Result<Option<User>> {
+    async fn get_user(&self, tenant: &Tenant, username: &str) -> Result<Option<User>> {
         let users = self.users.read().await;
-        Ok(users.get(username).cloned())
+        Ok(users
+            .get(tenant)
+            .and_then(|users| users.get(&canonical_username(username)))
+            .cloned())
     }
 
-    async fn update_user_display_name(&self, username: &str, display_name: &str) -> Result<()> {
+    async fn existing_usernames(&self, tenant: &Tenant, usernames: &[String]) -> Result<Vec<String>> {
+        let users = self.users.read().await;
+        let Some(users) = users.get(tenant) else {
+            return Ok(Vec::new());
+        };
+        Ok(usernames
+            .iter()
+            .map(|username| canonical_username(username))
+            .filter(|username| users.contains_key(username))
+            .collect())
+    }
+
+    async fn update_user_display_name(&self, tenant: &Tenant, username: &str, display_name: &str) -> Result<()> {
+        let username = canonical_username(username);
         let mut users = self.users.write().await;
+        let users = users.entry(tenant.clone()).or_default();
+        let now = Utc::now();
 
-        match users.get_mut(username) {
+        match users.get_mut(&username) {
             Some(user) => {
                 user.display_name = display_name.to_string();
+                user.updated_at = now;
                 tracing::info!("📝 Updated display name for user '{}': '{}'", username, display_name);
             }
             None => {
                 let user = User {
-                    username: username.to_string(),
+                    username: username.clone(),
                     display_name: display_name.to_string(),
+                    profile: UserProfile::default(),
+                    display_name_overrides: BTreeMap::new(),
+                    localized_display_names: BTreeMap::new(),
+                    created_at: now,
+                    updated_at: now,
                 };
-                users.insert(username.to_string(), user);
+                users.insert(username.clone(), user);
                 tracing::info!("➕ Created new user '{}' with display name: '{}'", username, display_name);
             }
         }
@@ -97,10 +177,318 @@ impl UserDatabase for MockUserDatabase {
         Ok(())
     }
 
+    async fn update_user_profile(&self, tenant: &Tenant, username: &str, profile: &UserProfile) -> Result<()> {
+        let username = canonical_username(username);
+        let mut users = self.users.write().await;
+        let users = users.entry(tenant.clone()).or_default();
+        let now = Utc::now();
+
+        let user = users.entry(username.clone()).or_insert_with(|| User {
+            username: username.clone(),
+            display_name: username.clone(),
+            profile: UserProfile::default(),
+            display_name_overrides: BTreeMap::new(),
+            localized_display_names: BTreeMap::new(),
+            created_at: now,
+            updated_at: now,
+        });
+        user.profile = profile.clone();
+        user.updated_at = now;
+        tracing::info!("📝 Updated profile for user '{}'", username);
+
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<String> {
         let user_count = self.user_count().await;
         Ok(format!("mock_db_healthy_with_{user_count}_users"))
     }
+
+    async fn set_display_name_override(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        app: &str,
+        display_name: &str,
+        max_apps: usize,
+    ) -> Result<bool> {
+        let username = canonical_username(username);
+        let mut users = self.users.write().await;
+        let users = users.entry(tenant.clone()).or_default();
+
+        let Some(user) = users.get_mut(&username) else {
+            anyhow::bail!("User '{}' not found", username);
+        };
+        if !user.display_name_overrides.contains_key(app) && user.display_name_overrides.len() >= max_apps {
+            return Ok(false);
+        }
+        user.display_name_overrides.insert(app.to_string(), display_name.to_string());
+        user.updated_at = Utc::now();
+        tracing::info!("📝 Set display name for user '{}' in app '{}'", username, app);
+
+        Ok(true)
+    }
+
+    async fn delete_display_name_override(&self, tenant: &Tenant, username: &str, app: &str) -> Result<bool> {
+        let username = canonical_username(username);
+        let mut users = self.users.write().await;
+        let users = users.entry(tenant.clone()).or_default();
+
+        let Some(user) = users.get_mut(&username) else {
+            return Ok(false);
+        };
+        if user.display_name_overrides.remove(app).is_none() {
+            return Ok(false);
+        }
+        user.updated_at = Utc::now();
+        tracing::info!("🗑️ Removed display name for user '{}' in app '{}'", username, app);
+
+        Ok(true)
+    }
+
+    async fn set_localized_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        locale: &str,
+        display_name: &str,
+        max_locales: usize,
+    ) -> Result<bool> {
+        let username = canonical_username(username);
+        let mut users = self.users.write().await;
+        let users = users.entry(tenant.clone()).or_default();
+
+        let Some(user) = users.get_mut(&username) else {
+            anyhow::bail!("User '{}' not found", username);
+        };
+        if !user.localized_display_names.contains_key(locale) && user.localized_display_names.len() >= max_locales {
+            return Ok(false);
+        }
+        user.localized_display_names
+            .insert(locale.to_string(), display_name.to_string());
+        user.updated_at = Utc::now();
+        tracing::info!("📝 Set '{}' display name for user '{}'", locale, username);
+
+        Ok(true)
+    }
+
+    async fn delete_localized_display_name(&self, tenant: &Tenant, username: &str, locale: &str) -> Result<bool> {
+        let username = canonical_username(username);
+        let mut users = self.users.write().await;
+        let users = users.entry(tenant.clone()).or_default();
+
+        let Some(user) = users.get_mut(&username) else {
+            return Ok(false);
+        };
+        if user.localized_display_names.remove(locale).is_none() {
+            return Ok(false);
+        }
+        user.updated_at = Utc::now();
+        tracing::info!("🗑️ Removed '{}' display name for user '{}'", locale, username);
+
+        Ok(true)
+    }
+
+    async fn submit_pending_display_name(&self, tenant: &Tenant, username: &str, display_name: &str) -> Result<()> {
+        let username = canonical_username(username);
+        let mut pending = self.pending.write().await;
+        let pending = pending.entry(tenant.clone()).or_default();
+
+        pending.insert(
+            username.clone(),
+            PendingDisplayName {
+                username: username.clone(),
+                display_name: display_name.to_string(),
+                status: ModerationStatus::Pending,
+                rejection_reason: None,
+                submitted_at: Utc::now(),
+            },
+        );
+        tracing::info!(
+            "🕒 Submitted display name for '{}' for moderation: '{}'",
+            username,
+            display_name
+        );
+
+        Ok(())
+    }
+
+    async fn get_pending_display_name(&self, tenant: &Tenant, username: &str) -> Result<Option<PendingDisplayName>> {
+        let pending = self.pending.read().await;
+        Ok(pending
+            .get(tenant)
+            .and_then(|pending| pending.get(&canonical_username(username)))
+            .cloned())
+    }
+
+    async fn list_pending_display_names(&self, tenant: &Tenant) -> Result<Vec<PendingDisplayName>> {
+        let pending = self.pending.read().await;
+        let mut submissions: Vec<PendingDisplayName> = pending
+            .get(tenant)
+            .into_iter()
+            .flat_map(HashMap::values)
+            .filter(|submission| submission.status == ModerationStatus::Pending)
+            .cloned()
+            .collect();
+
+        submissions.sort_by_key(|submission| submission.submitted_at);
+        Ok(submissions)
+    }
+
+    async fn approve_pending_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+    ) -> Result<Option<PendingDisplayName>> {
+        let username = canonical_username(username);
+
+        let approved = {
+            let mut pending = self.pending.write().await;
+            let pending = pending.entry(tenant.clone()).or_default();
+            match pending.get(&username) {
+                Some(submission) if submission.status == ModerationStatus::Pending => pending.remove(&username),
+                _ => None,
+            }
+        };
+
+        if let Some(submission) = &approved {
+            self.update_user_display_name(tenant, &username, &submission.display_name)
+                .await?;
+        }
+
+        Ok(approved)
+    }
+
+    async fn reject_pending_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        reason: &str,
+    ) -> Result<Option<PendingDisplayName>> {
+        let mut pending = self.pending.write().await;
+        let pending = pending.entry(tenant.clone()).or_default();
+
+        match pending.get_mut(&canonical_username(username)) {
+            Some(submission) if submission.status == ModerationStatus::Pending => {
+                submission.status = ModerationStatus::Rejected;
+                submission.rejection_reason = Some(reason.to_string());
+                Ok(Some(submission.clone()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn get_avatar(&self, tenant: &Tenant, username: &str) -> Result<Option<StoredAvatar>> {
+        let avatars = self.avatars.read().await;
+        Ok(avatars
+            .get(tenant)
+            .and_then(|avatars| avatars.get(&canonical_username(username)))
+            .cloned())
+    }
+
+    async fn put_avatar(&self, tenant: &Tenant, username: &str, avatar: &StoredAvatar) -> Result<()> {
+        let username = canonical_username(username);
+        let mut avatars = self.avatars.write().await;
+        let avatars = avatars.entry(tenant.clone()).or_default();
+
+        avatars.insert(username.clone(), avatar.clone());
+        tracing::info!("🖼️ Stored avatar for user '{}' ({} bytes)", username, avatar.data.len());
+
+        Ok(())
+    }
+
+    async fn revoke_token(&self, tenant: &Tenant, revoked: &RevokedToken) -> Result<()> {
+        let mut tokens = self.revoked_tokens.write().await;
+        let key = (tenant.clone(), revoked.issuer.clone(), revoked.jti.clone());
+        let entry = tokens.entry(key).or_insert_with(|| revoked.clone());
+        entry.expires_at = entry.expires_at.max(revoked.expires_at);
+        tracing::info!(
+            "🚫 Revoked token '{}' of '{}' in tenant '{}' until {}",
+            revoked.jti,
+            revoked.issuer,
+            tenant,
+            revoked.expires_at
+        );
+        Ok(())
+    }
+
+    async fn revoke_subject(&self, tenant: &Tenant, revoked: &RevokedSubject) -> Result<()> {
+        let mut subjects = self.revoked_subjects.write().await;
+        let key = (tenant.clone(), revoked.issuer.clone(), revoked.subject.clone());
+        let entry = subjects.entry(key).or_insert_with(|| revoked.clone());
+        entry.issued_before = entry.issued_before.max(revoked.issued_before);
+        entry.expires_at = entry.expires_at.max(revoked.expires_at);
+        tracing::info!(
+            "🚫 Revoked tokens of '{}' from '{}' in tenant '{}' issued before {}",
+            revoked.subject,
+            revoked.issuer,
+            tenant,
+            revoked.issued_before
+        );
+        Ok(())
+    }
+
+    async fn get_revocations(&self, now: DateTime<Utc>) -> Result<Revocations> {
+        let tokens = self.revoked_tokens.read().await;
+        let subjects = self.revoked_subjects.read().await;
+        Ok(Revocations {
+            tokens: tokens
+                .iter()
+                .filter(|(_, revoked)| revoked.expires_at > now)
+                .map(|((tenant, _, _), revoked)| (tenant.clone(), revoked.clone()))
+                .collect(),
+            subjects: subjects
+                .iter()
+                .filter(|(_, revoked)| revoked.expires_at > now)
+                .map(|((tenant, _, _), revoked)| (tenant.clone(), revoked.clone()))
+                .collect(),
+        })
+    }
+
+    async fn delete_expired_revocations(&self, now: DateTime<Utc>) -> Result<u64> {
+        let mut tokens = self.revoked_tokens.write().await;
+        let mut subjects = self.revoked_subjects.write().await;
+        let before = tokens.len() + subjects.len();
+        tokens.retain(|_, revoked| revoked.expires_at > now);
+        subjects.retain(|_, revoked| revoked.expires_at > now);
+        Ok((before - tokens.len() - subjects.len()) as u64)
+    }
+
+    async fn create_api_key(&self, tenant: &Tenant, key: &ApiKey) -> Result<()> {
+        let mut api_keys = self.api_keys.write().await;
+        let tenant_keys = api_keys.entry(tenant.clone()).or_default();
+        if tenant_keys.values().any(|existing| existing.key_hash == key.key_hash) {
+            anyhow::bail!("API key hash already exists");
+        }
+        tenant_keys.insert(key.id.clone(), key.clone());
+        tracing::info!("🔑 Created API key '{}' ({}) in tenant '{}'", key.name, key.id, tenant);
+        Ok(())
+    }
+
+    async fn list_api_keys(&self, tenant: &Tenant) -> Result<Vec<ApiKey>> {
+        let api_keys = self.api_keys.read().await;
+        let mut keys: Vec<ApiKey> = api_keys
+            .get(tenant)
+            .map(|tenant_keys| tenant_keys.values().cloned().collect())
+            .unwrap_or_default();
+        keys.sort_by_key(|key| key.created_at);
+        Ok(keys)
+    }
+
+    async fn find_api_key(&self, tenant: &Tenant, key_hash: &str) -> Result<Option<ApiKey>> {
+        let api_keys = self.api_keys.read().await;
+        Ok(api_keys
+            .get(tenant)
+            .and_then(|tenant_keys| tenant_keys.values().find(|key| key.key_hash == key_hash))
+            .cloned())
+    }
+
+    async fn delete_api_key(&self, tenant: &Tenant, id: &str) -> Result<bool> {
+        let mut api_keys = self.api_keys.write().await;
+        Ok(api_keys
+            .get_mut(tenant)
+            .is_some_and(|tenant_keys| tenant_keys.remove(id).is_some()))
+    }
 }
 
 #[cfg(test)]
@@ -110,9 +498,10 @@ mod tests {
     #[tokio::test]
     async fn test_mock_database_new() {
         let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
         assert_eq!(db.user_count().await, 4); // Updated to match the actual count
 
-        let admin = db.get_user("admin").await.unwrap();
+        let admin = db.get_user(&tenant, "admin").await.unwrap();
         assert!(admin.is_some());
         assert_eq!(admin.unwrap().display_name, "Administrator");
     }
@@ -120,19 +509,21 @@ mod tests {
     #[tokio::test]
     async fn test_mock_database_empty() {
         let db = MockUserDatabase::new_empty();
+        let tenant = Tenant::default();
         assert_eq!(db.user_count().await, 0);
 
-        let user = db.get_user("nonexistent").await.unwrap();
+        let user = db.get_user(&tenant, "nonexistent").await.unwrap();
         assert!(user.is_none());
     }
 
     #[tokio::test]
     async fn test_update_existing_user() {
         let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
 
-        db.update_user_display_name("admin", "Super Admin").await.unwrap();
+        db.update_user_display_name(&tenant, "admin", "Super Admin").await.unwrap();
 
-        let user = db.get_user("admin").await.unwrap().unwrap();
+        let user = db.get_user(&tenant, "admin").await.unwrap().unwrap();
         assert_eq!(user.display_name, "Super Admin");
         assert_eq!(db.user_count().await, 4); // Should still have 4 users
     }
@@ -140,22 +531,195 @@ mod tests {
     #[tokio::test]
     async fn test_create_new_user() {
         let db = MockUserDatabase::new_empty();
+        let tenant = Tenant::default();
 
         // Create new user
-        db.update_user_display_name("newuser", "New User").await.unwrap();
+        db.update_user_display_name(&tenant, "newuser", "New User").await.unwrap();
 
-        let user = db.get_user("newuser").await.unwrap().unwrap();
+        let user = db.get_user(&tenant, "newuser").await.unwrap().unwrap();
         assert_eq!(user.username, "newuser");
         assert_eq!(user.display_name, "New User");
         assert_eq!(db.user_count().await, 1);
     }
 
+    #[tokio::test]
+    async fn test_lookups_are_case_insensitive() {
+        let db = MockUserDatabase::new_empty();
+        let tenant = Tenant::default();
+
+        db.update_user_display_name(&tenant, "NewUser", "New User").await.unwrap();
+        db.update_user_display_name(&tenant, "newuser", "Renamed").await.unwrap();
+
+        let user = db.get_user(&tenant, "NEWUSER").await.unwrap().unwrap();
+        assert_eq!(user.username, "newuser");
+        assert_eq!(user.display_name, "Renamed");
+        assert_eq!(db.user_count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pending_display_name_approval() {
+        let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
+
+        db.submit_pending_display_name(&tenant, "alice", "Alice Cooper").await.unwrap();
+
+        // The approved name is unchanged until a moderator approves
+        let user = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(user.display_name, "Alice Smith");
+        assert_eq!(db.list_pending_display_names(&tenant).await.unwrap().len(), 1);
+
+        let approved = db.approve_pending_display_name(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(approved.display_name, "Alice Cooper");
+
+        let user = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(user.display_name, "Alice Cooper");
+        assert!(db.get_pending_display_name(&tenant, "alice").await.unwrap().is_none());
+        assert!(db.approve_pending_display_name(&tenant, "alice").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pending_display_name_rejection() {
+        let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
+
+        db.submit_pending_display_name(&tenant, "alice", "Spam Name").await.unwrap();
+        let rejected = db
+            .reject_pending_display_name(&tenant, "alice", "Looks like spam")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(rejected.status, ModerationStatus::Rejected);
+
+        // Rejected submissions stay visible to the user but leave the queue
+        let pending = db.get_pending_display_name(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(pending.rejection_reason.as_deref(), Some("Looks like spam"));
+        assert!(db.list_pending_display_names(&tenant).await.unwrap().is_empty());
+        assert!(db.approve_pending_display_name(&tenant, "alice").await.unwrap().is_none());
+
+        let user = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(user.display_name, "Alice Smith");
+    }
+
+    #[tokio::test]
+    async fn test_updates_touch_updated_at() {
+        let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
+        let before = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+
+        db.update_user_display_name(&tenant, "alice", "Alice Cooper").await.unwrap();
+        let after = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(after.created_at, before.created_at);
+        assert!(after.updated_at >= before.updated_at);
+
+        db.update_user_profile(&tenant, "newbie", &UserProfile::default())
+            .await
+            .unwrap();
+        let created = db.get_user(&tenant, "newbie").await.unwrap().unwrap();
+        assert_eq!(created.created_at, created.updated_at);
+    }
+
+    #[tokio::test]
+    async fn test_display_name_overrides() {
+        let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
+
+        assert!(db
+            .set_display_name_override(&tenant, "Alice", "billing", "Alice M. Smith", 1)
+            .await
+            .unwrap());
+        let user = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(user.display_name_override(Some("billing")), Some("Alice M. Smith"));
+        assert_eq!(user.display_name_override(Some("chat")), None);
+        assert_eq!(user.display_name_override(None), None);
+
+        // At the limit, no override for another application is added
+        assert!(!db.set_display_name_override(&tenant, "alice", "chat", "Al", 1).await.unwrap());
+        let user = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(user.display_name_override(Some("chat")), None);
+
+        assert!(db.delete_display_name_override(&tenant, "alice", "billing").await.unwrap());
+        assert!(!db.delete_display_name_override(&tenant, "alice", "billing").await.unwrap());
+        assert!(db
+            .get_user(&tenant, "alice")
+            .await
+            .unwrap()
+            .unwrap()
+            .display_name_overrides
+            .is_empty());
+
+        // Overrides need an existing user
+        assert!(db
+            .set_display_name_override(&tenant, "nobody", "billing", "Nobody", 1)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_localized_display_names() {
+        let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
+
+        assert!(db
+            .set_localized_display_name(&tenant, "alice", "ja", "アリス", 1)
+            .await
+            .unwrap());
+        let user = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        let languages = ["ja-jp".to_string()];
+        let resolved = user.display_name_for(None, &languages);
+        assert_eq!(resolved.display_name, "アリス");
+        assert_eq!(resolved.locale, Some("ja"));
+        assert_eq!(user.display_name_for(None, &[]).display_name, "Alice Smith");
+
+        // An application override takes precedence over the reader's language
+        db.set_display_name_override(&tenant, "alice", "chat", "Al", 1).await.unwrap();
+        let user = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        assert_eq!(user.display_name_for(Some("chat"), &languages).display_name, "Al");
+
+        // At the limit, existing variants can still change but no new language is added
+        assert!(db
+            .set_localized_display_name(&tenant, "alice", "ja", "ありす", 1)
+            .await
+            .unwrap());
+        assert!(!db.set_localized_display_name(&tenant, "alice", "fr", "Alice", 1).await.unwrap());
+        let user = db.get_user(&tenant, "alice").await.unwrap().unwrap();
+        assert!(!user.localized_display_names.contains_key("fr"));
+
+        assert!(db.delete_localized_display_name(&tenant, "alice", "ja").await.unwrap());
+        assert!(!db.delete_localized_display_name(&tenant, "alice", "ja").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_tenants_are_isolated() {
+        let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
+        let brand = Tenant::new("brand-a").unwrap();
+
+        // Seed users live in the default tenant only
+        assert!(db.get_user(&brand, "alice").await.unwrap().is_none());
+
+        // The same username can belong to a different person in each tenant
+        db.update_user_display_name(&brand, "alice", "Alice Brand").await.unwrap();
+        assert_eq!(db.get_user(&brand, "alice").await.unwrap().unwrap().display_name, "Alice Brand");
+        assert_eq!(
+            db.get_user(&tenant, "alice").await.unwrap().unwrap().display_name,
+            "Alice Smith"
+        );
+        assert_eq!(db.user_count().await, 5);
+
+        db.submit_pending_display_name(&brand, "alice", "Pending").await.unwrap();
+        assert!(db.list_pending_display_names(&tenant).await.unwrap().is_empty());
+        assert!(db.get_pending_display_name(&tenant, "alice").await.unwrap().is_none());
+        assert!(db.approve_pending_display_name(&tenant, "alice").await.unwrap().is_none());
+        assert_eq!(db.list_pending_display_names(&brand).await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_user_exists() {
         let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
 
-        assert!(db.user_exists("admin").await.unwrap());
-        assert!(!db.user_exists("nonexistent").await.unwrap());
+        assert!(db.user_exists(&tenant, "admin").await.unwrap());
+        assert!(!db.user_exists(&tenant, "nonexistent").await.unwrap());
     }
 
     #[tokio::test]
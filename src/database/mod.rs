@@ -1,9 +1,14 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::api_keys::IpRange;
+use crate::tenant::Tenant;
+
 pub mod cache;
 pub mod mock;
 pub mod mysql;
@@ -13,13 +18,267 @@ pub mod seeding;
 pub struct User {
     pub username: String,
     pub display_name: String,
+    #[serde(default)]
+    pub profile: UserProfile,
+    /// Display names shown to specific applications, keyed by app; others see `display_name`
+    #[serde(default)]
+    pub display_name_overrides: BTreeMap<String, String>,
+    /// Display name variants keyed by BCP 47 language tag (e.g. `ja`, `en-Latn`)
+    #[serde(default)]
+    pub localized_display_names: BTreeMap<String, String>,
+    pub created_at: DateTime<Utc>,
+    /// Last change to the display name, overrides or profile
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The display name a particular reader sees, see [`User::display_name_for`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedDisplayName<'a> {
+    pub display_name: &'a str,
+    /// Set when an application override was used
+    pub app: Option<&'a str>,
+    /// Set when a localized variant was used
+    pub locale: Option<&'a str>,
+}
+
+impl User {
+    /// The display name override for `app`, if the user has one
+    pub fn display_name_override(&self, app: Option<&str>) -> Option<&str> {
+        app.and_then(|app| self.display_name_overrides.get(app)).map(String::as_str)
+    }
+
+    /// The display name for a reader in `app` who prefers `languages` (most preferred first).
+    ///
+    /// An application override is an explicit choice and wins; otherwise the best matching
+    /// localized variant is used, and the default display name when nothing matches.
+    pub fn display_name_for<'a>(&'a self, app: Option<&'a str>, languages: &[String]) -> ResolvedDisplayName<'a> {
+        if let Some(display_name) = self.display_name_override(app) {
+            return ResolvedDisplayName { display_name, app, locale: None };
+        }
+
+        let locale = crate::language::negotiate(languages, self.localized_display_names.keys().map(String::as_str));
+        match locale {
+            Some(locale) => ResolvedDisplayName {
+                display_name: &self.localized_display_names[locale],
+                app: None,
+                locale: Some(locale),
+            },
+            None => ResolvedDisplayName {
+                display_name: &self.display_name,
+                app: None,
+                locale: None,
+            },
+        }
+    }
+}
+
+/// Optional public profile fields; `None` means the field is not set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserProfile {
+    pub pronouns: Option<String>,
+    pub bio: Option<String>,
+    /// BCP 47 language tag, e.g. `en-US`
+    pub locale: Option<String>,
+    /// IANA time zone name, e.g. `Europe/Berlin`
+    pub timezone: Option<String>,
+    /// Absolute `http` or `https` URL
+    pub website: Option<String>,
+}
+
+/// A processed avatar image, ready to be served as-is
+#[derive(Debug, Clone)]
+pub struct StoredAvatar {
+    pub content_type: String,
+    pub data: Vec<u8>,
+    /// Content hash, used as the `ETag` and as the cache-busting version in avatar URLs
+    pub etag: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Moderation state of a submitted display name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationStatus {
+    Pending,
+    Rejected,
+}
+
+impl ModerationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ModerationStatus::Pending => "pending",
+            ModerationStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// A display name change awaiting (or refused by) moderation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingDisplayName {
+    pub username: String,
+    pub display_name: String,
+    pub status: ModerationStatus,
+    pub rejection_reason: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+}
+
+/// Revoked tokens and subjects that have not expired yet, with the tenant they were revoked
+/// in, see [`UserDatabase::get_revocations`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Revocations {
+    pub tokens: Vec<(Tenant, RevokedToken)>,
+    pub subjects: Vec<(Tenant, RevokedSubject)>,
+}
+
+/// A token revoked by its issuer and `jti` claim
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevokedToken {
+    pub issuer: String,
+    pub jti: String,
+    /// Time the entry can be dropped, once the token has expired
+    pub expires_at: DateTime<Utc>,
+}
+
+/// All tokens of an issuer's subject issued before `issued_before` are revoked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RevokedSubject {
+    pub issuer: String,
+    pub subject: String,
+    pub issued_before: DateTime<Utc>,
+    /// Time the entry can be dropped, once every token it covers has expired
+    pub expires_at: DateTime<Utc>,
 }
 
+/// A backend service's API key, see `crate::api_keys`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    /// SHA-256 of the key, hex-encoded; the key itself is never stored
+    #[serde(skip)]
+    pub key_hash: String,
+    pub scope: ApiKeyScope,
+    /// Addresses the key may be used from; empty allows any address
+    pub allowed_ips: Vec<IpRange>,
+    /// Requests allowed per minute, per instance
+    pub quota_per_minute: Option<u32>,
+    /// Username of the admin who created the key
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// What an API key may do: `read` allows `GET` requests, `write` allows any request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyScope {
+    Read,
+    Write,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::Read => "read",
+            ApiKeyScope::Write => "write",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "read" => Some(ApiKeyScope::Read),
+            "write" => Some(ApiKeyScope::Write),
+            _ => None,
+        }
+    }
+
+    /// Whether a key with this scope may make a request needing `required`
+    pub fn allows(&self, required: ApiKeyScope) -> bool {
+        *self == ApiKeyScope::Write || required == ApiKeyScope::Read
+    }
+}
+
+/// User storage.
+///
+/// Every call except `health_check` is scoped to one tenant: usernames are unique per tenant,
+/// and no call reads or writes another tenant's data.
 #[async_trait]
 pub trait UserDatabase: Send + Sync {
-    async fn get_user(&self, username: &str) -> Result<Option<User>>;
-    async fn update_user_display_name(&self, username: &str, display_name: &str) -> Result<()>;
+    async fn get_user(&self, tenant: &Tenant, username: &str) -> Result<Option<User>>;
+    /// Which of `usernames` belong to existing users, as canonical usernames, in one lookup
+    async fn existing_usernames(&self, tenant: &Tenant, usernames: &[String]) -> Result<Vec<String>>;
+    async fn update_user_display_name(&self, tenant: &Tenant, username: &str, display_name: &str) -> Result<()>;
+    /// Replace the user's profile fields, creating the user (named after the username) if needed
+    async fn update_user_profile(&self, tenant: &Tenant, username: &str, profile: &UserProfile) -> Result<()>;
     async fn health_check(&self) -> Result<String>;
+
+    /// Set the display name shown to one application; the user must already exist.
+    ///
+    /// Returns `false`, changing nothing, if the user already has `max_apps` other overrides.
+    /// The check and the write are atomic, so concurrent requests cannot exceed the limit.
+    async fn set_display_name_override(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        app: &str,
+        display_name: &str,
+        max_apps: usize,
+    ) -> Result<bool>;
+    /// Remove an application's display name override; returns `false` if there was none
+    async fn delete_display_name_override(&self, tenant: &Tenant, username: &str, app: &str) -> Result<bool>;
+    /// Set the display name variant for a language tag; the user must already exist.
+    ///
+    /// Returns `false`, changing nothing, if the user already has `max_locales` other variants.
+    /// The check and the write are atomic, so concurrent requests cannot exceed the limit.
+    async fn set_localized_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        locale: &str,
+        display_name: &str,
+        max_locales: usize,
+    ) -> Result<bool>;
+    /// Remove a localized display name; returns `false` if there was none
+    async fn delete_localized_display_name(&self, tenant: &Tenant, username: &str, locale: &str) -> Result<bool>;
+
+    /// Store a display name for moderation, replacing any earlier submission
+    async fn submit_pending_display_name(&self, tenant: &Tenant, username: &str, display_name: &str) -> Result<()>;
+    async fn get_pending_display_name(&self, tenant: &Tenant, username: &str) -> Result<Option<PendingDisplayName>>;
+    /// Submissions awaiting review, oldest first
+    async fn list_pending_display_names(&self, tenant: &Tenant) -> Result<Vec<PendingDisplayName>>;
+    /// Publish a pending display name; returns `None` if nothing is pending for the user
+    async fn approve_pending_display_name(&self, tenant: &Tenant, username: &str)
+        -> Result<Option<PendingDisplayName>>;
+    /// Mark a pending display name as rejected; returns `None` if nothing is pending for the user
+    async fn reject_pending_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        reason: &str,
+    ) -> Result<Option<PendingDisplayName>>;
+
+    async fn get_avatar(&self, tenant: &Tenant, username: &str) -> Result<Option<StoredAvatar>>;
+    /// Store the user's avatar, replacing any previous one
+    async fn put_avatar(&self, tenant: &Tenant, username: &str, avatar: &StoredAvatar) -> Result<()>;
+
+    // Token revocation; token ids and subjects are only unique per issuer
+    /// Revoke a token; revoking it again keeps the latest `expires_at`
+    async fn revoke_token(&self, tenant: &Tenant, revoked: &RevokedToken) -> Result<()>;
+    /// Revoke a subject's tokens; revoking the same subject again keeps the latest `issued_before` and `expires_at`
+    async fn revoke_subject(&self, tenant: &Tenant, revoked: &RevokedSubject) -> Result<()>;
+    /// Revocations of every tenant that have not expired at `now`
+    async fn get_revocations(&self, now: DateTime<Utc>) -> Result<Revocations>;
+    /// Drop revocations that expired before `now`, returning how many were removed
+    async fn delete_expired_revocations(&self, now: DateTime<Utc>) -> Result<u64>;
+
+    /// Store a new API key
+    async fn create_api_key(&self, tenant: &Tenant, key: &ApiKey) -> Result<()>;
+    /// The tenant's API keys, oldest first
+    async fn list_api_keys(&self, tenant: &Tenant) -> Result<Vec<ApiKey>>;
+    /// The API key with this hash, if any
+    async fn find_api_key(&self, tenant: &Tenant, key_hash: &str) -> Result<Option<ApiKey>>;
+    /// Delete an API key; returns `false` if there was none
+    async fn delete_api_key(&self, tenant: &Tenant, id: &str) -> Result<bool>;
 }
 
 pub struct DatabaseConfig {
@@ -1,12 +1,26 @@
-use super::{User, UserDatabase};
+use super::{
+    ApiKey, ApiKeyScope, ModerationStatus, PendingDisplayName, Revocations, RevokedSubject, RevokedToken, StoredAvatar,
+    User, UserDatabase, UserProfile,
+};
+use crate::tenant::Tenant;
+use crate::validation::canonical_username;
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::mysql::MySqlRow;
 use sqlx::{MySqlPool, Row};
+use std::collections::BTreeMap;
+
+/// The `tenant` column of a row read across tenants
+fn row_tenant(row: &MySqlRow) -> Result<Tenant> {
+    let tenant: String = row.get("tenant");
+    Tenant::new(&tenant).map_err(|e| anyhow::anyhow!("Invalid tenant '{}' in database: {}", tenant, e))
+}
 
 // Helper function to try to get metrics from the global metrics instance
 fn try_get_metrics() -> Option<&'static crate::metrics::AppMetrics> {
     // Get metrics without risking panics
-    match std::panic::catch_unwind(|| crate::router::get_metrics_instance()) {
+    match std::panic::catch_unwind(crate::router::get_metrics_instance) {
         Ok(metrics) => metrics,
         Err(_) => {
             tracing::warn!("Failed to access metrics instance, metrics tracking will be skipped");
@@ -15,6 +29,86 @@ fn try_get_metrics() -> Option<&'static crate::metrics::AppMetrics> {
     }
 }
 
+// Record the duration and outcome of a query, if metrics are available
+fn track_query(tenant: &Tenant, operation: &str, start: std::time::Instant, success: bool) {
+    if let Some(metrics) = try_get_metrics() {
+        let status = if success { "success" } else { "error" };
+        crate::metrics::track_database_query(metrics, tenant, operation, status, start.elapsed().as_secs_f64());
+    }
+}
+
+const API_KEY_COLUMNS: &str = "id, name, key_hash, scope, allowed_ips, quota_per_minute, created_by, created_at";
+
+fn api_key_from_row(row: &MySqlRow) -> ApiKey {
+    let scope: String = row.get("scope");
+    let allowed_ips: String = row.get("allowed_ips");
+
+    ApiKey {
+        id: row.get("id"),
+        name: row.get("name"),
+        key_hash: row.get("key_hash"),
+        // An unknown scope grants the least
+        scope: ApiKeyScope::parse(&scope).unwrap_or(ApiKeyScope::Read),
+        allowed_ips: allowed_ips
+            .split(',')
+            .filter(|range| !range.is_empty())
+            .filter_map(|range| match range.parse() {
+                Ok(range) => Some(range),
+                Err(e) => {
+                    tracing::warn!("Ignoring invalid address range of API key: {}", e);
+                    None
+                }
+            })
+            .collect(),
+        quota_per_minute: row.get("quota_per_minute"),
+        created_by: row.get("created_by"),
+        created_at: row.get("created_at"),
+    }
+}
+
+const PENDING_COLUMNS: &str = "username, display_name, status, rejection_reason, submitted_at";
+
+fn pending_from_row(row: &MySqlRow) -> PendingDisplayName {
+    let status: String = row.get("status");
+
+    PendingDisplayName {
+        username: row.get("username"),
+        display_name: row.get("display_name"),
+        status: if status == ModerationStatus::Rejected.as_str() {
+            ModerationStatus::Rejected
+        } else {
+            ModerationStatus::Pending
+        },
+        rejection_reason: row.get("rejection_reason"),
+        submitted_at: row.get("submitted_at"),
+    }
+}
+
+/// Tables of extra display names, keyed by something in addition to the username
+#[derive(Debug, Clone, Copy)]
+enum DisplayNameVariants {
+    /// Per-application overrides
+    Overrides,
+    /// Per-language variants
+    Localized,
+}
+
+impl DisplayNameVariants {
+    fn table(self) -> &'static str {
+        match self {
+            DisplayNameVariants::Overrides => "display_name_overrides",
+            DisplayNameVariants::Localized => "localized_display_names",
+        }
+    }
+
+    fn key_column(self) -> &'static str {
+        match self {
+            DisplayNameVariants::Overrides => "app",
+            DisplayNameVariants::Localized => "locale",
+        }
+    }
+}
+
 pub struct MySqlUserDatabase {
     pool: MySqlPool,
 }
@@ -60,34 +154,195 @@ impl MySqlUserDatabase {
 
     // Removed legacy 'new' function that has been replaced by new_with_config
     // All code should now use new_with_config instead
+
+    async fn get_display_name_variants(
+        &self,
+        tenant: &Tenant,
+        variants: DisplayNameVariants,
+        username: &str,
+    ) -> Result<BTreeMap<String, String>> {
+        let start = std::time::Instant::now();
+        let (table, key) = (variants.table(), variants.key_column());
+
+        let result = sqlx::query(&format!(
+            "SELECT {key}, display_name FROM {table} WHERE tenant = ? AND username = ?"
+        ))
+        .bind(tenant.as_str())
+        .bind(username)
+        .fetch_all(&self.pool)
+        .await;
+
+        track_query(tenant, &format!("get_{table}"), start, result.is_ok());
+        Ok(result?.iter().map(|row| (row.get(key), row.get("display_name"))).collect())
+    }
+
+    /// Set a variant, unless the user already has `max_variants` others; returns whether it was set
+    async fn set_display_name_variant(
+        &self,
+        tenant: &Tenant,
+        variants: DisplayNameVariants,
+        username: &str,
+        key: &str,
+        display_name: &str,
+        max_variants: usize,
+    ) -> Result<bool> {
+        let start = std::time::Instant::now();
+        let username = canonical_username(username);
+        let (table, key_column) = (variants.table(), variants.key_column());
+
+        let result: Result<bool> = async {
+            let mut tx = self.pool.begin().await?;
+
+            // Variants change what readers see, so they count as an update of the user. The
+            // update also locks the user's row, so concurrent writers count variants in turn.
+            let updated =
+                sqlx::query("UPDATE users SET updated_at = CURRENT_TIMESTAMP WHERE tenant = ? AND username = ?")
+                    .bind(tenant.as_str())
+                    .bind(&username)
+                    .execute(&mut *tx)
+                    .await?;
+            if updated.rows_affected() == 0 {
+                anyhow::bail!("User '{}' not found", username);
+            }
+
+            let others: i64 = sqlx::query_scalar(&format!(
+                "SELECT COUNT(*) FROM {table} WHERE tenant = ? AND username = ? AND {key_column} <> ?"
+            ))
+            .bind(tenant.as_str())
+            .bind(&username)
+            .bind(key)
+            .fetch_one(&mut *tx)
+            .await?;
+            if others >= max_variants as i64 {
+                // Dropping the transaction rolls back the updated_at change
+                return Ok(false);
+            }
+
+            sqlx::query(&format!(
+                "INSERT INTO {table} (tenant, username, {key_column}, display_name) VALUES (?, ?, ?, ?)
+                 ON DUPLICATE KEY UPDATE display_name = VALUES(display_name)"
+            ))
+            .bind(tenant.as_str())
+            .bind(&username)
+            .bind(key)
+            .bind(display_name)
+            .execute(&mut *tx)
+            .await?;
+
+            tx.commit().await?;
+            Ok(true)
+        }
+        .await;
+
+        track_query(tenant, &format!("set_{table}"), start, result.is_ok());
+        if !result? {
+            return Ok(false);
+        }
+
+        tracing::info!(
+            "Set {} '{}' for user '{}' in tenant '{}' in MySQL",
+            key_column,
+            key,
+            username,
+            tenant
+        );
+        Ok(true)
+    }
+
+    async fn delete_display_name_variant(
+        &self,
+        tenant: &Tenant,
+        variants: DisplayNameVariants,
+        username: &str,
+        key: &str,
+    ) -> Result<bool> {
+        let start = std::time::Instant::now();
+        let username = canonical_username(username);
+        let (table, key_column) = (variants.table(), variants.key_column());
+
+        let result: Result<bool> = async {
+            let mut tx = self.pool.begin().await?;
+
+            let deleted = sqlx::query(&format!(
+                "DELETE FROM {table} WHERE tenant = ? AND username = ? AND {key_column} = ?"
+            ))
+            .bind(tenant.as_str())
+            .bind(&username)
+            .bind(key)
+            .execute(&mut *tx)
+            .await?;
+            if deleted.rows_affected() == 0 {
+                return Ok(false);
+            }
+
+            sqlx::query("UPDATE users SET updated_at = CURRENT_TIMESTAMP WHERE tenant = ? AND username = ?")
+                .bind(tenant.as_str())
+                .bind(&username)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await?;
+            Ok(true)
+        }
+        .await;
+
+        track_query(tenant, &format!("delete_{table}"), start, result.is_ok());
+
+        if let Ok(true) = &result {
+            tracing::info!("Removed {} '{}' for user '{}' in MySQL", key_column, key, username);
+        }
+        result
+    }
 }
 
 #[async_trait]
 impl UserDatabase for MySqlUserDatabase {
-    async fn get_user(&self, username: &str) -> Result<Option<User>> {
+    async fn get_user(&self, tenant: &Tenant, username: &str) -> Result<Option<User>> {
         let start = std::time::Instant::now();
         let operation = "get_user";
 
-        let result = sqlx::query("SELECT username, display_name FROM users WHERE username = ?")
-            .bind(username)
-            .fetch_optional(&self.pool)
-            .await;
+        let result = sqlx::query(
+            "SELECT username, display_name, pronouns, bio, locale, timezone, website, created_at, updated_at
+             FROM users WHERE tenant = ? AND username = ?",
+        )
+        .bind(tenant.as_str())
+        .bind(canonical_username(username))
+        .fetch_optional(&self.pool)
+        .await;
 
         let duration = start.elapsed().as_secs_f64();
 
         // Track database operation metrics
         if let Some(metrics) = try_get_metrics() {
             let status = if result.is_ok() { "success" } else { "error" };
-            crate::metrics::track_database_query(metrics, operation, status, duration);
+            crate::metrics::track_database_query(metrics, tenant, operation, status, duration);
         }
 
         // Process the result
         match result {
             Ok(row) => match row {
                 Some(row) => {
+                    let username: String = row.get("username");
+                    let display_name_overrides = self
+                        .get_display_name_variants(tenant, DisplayNameVariants::Overrides, &username)
+                        .await?;
+                    let localized_display_names = self
+                        .get_display_name_variants(tenant, DisplayNameVariants::Localized, &username)
+                        .await?;
                     let user = User {
-                        username: row.get("username"),
+                        username,
                         display_name: row.get("display_name"),
+                        profile: UserProfile {
+                            pronouns: row.get("pronouns"),
+                            bio: row.get("bio"),
+                            locale: row.get("locale"),
+                            timezone: row.get("timezone"),
+                            website: row.get("website"),
+                        },
+                        display_name_overrides,
+                        localized_display_names,
+                        created_at: row.get("created_at"),
+                        updated_at: row.get("updated_at"),
                     };
                     Ok(Some(user))
                 }
@@ -97,15 +352,43 @@ impl UserDatabase for MySqlUserDatabase {
         }
     }
 
-    async fn update_user_display_name(&self, username: &str, display_name: &str) -> Result<()> {
+    async fn existing_usernames(&self, tenant: &Tenant, usernames: &[String]) -> Result<Vec<String>> {
+        if usernames.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let start = std::time::Instant::now();
+        let operation = "existing_usernames";
+
+        let placeholders = vec!["?"; usernames.len()].join(", ");
+        let sql = format!("SELECT username FROM users WHERE tenant = ? AND username IN ({placeholders})");
+        let query = usernames
+            .iter()
+            .fold(sqlx::query(&sql).bind(tenant.as_str()), |query, username| {
+                query.bind(canonical_username(username))
+            });
+        let result = query.fetch_all(&self.pool).await;
+
+        let duration = start.elapsed().as_secs_f64();
+
+        if let Some(metrics) = try_get_metrics() {
+            let status = if result.is_ok() { "success" } else { "error" };
+            crate::metrics::track_database_query(metrics, tenant, operation, status, duration);
+        }
+
+        Ok(result?.iter().map(|row| row.get("username")).collect())
+    }
+
+    async fn update_user_display_name(&self, tenant: &Tenant, username: &str, display_name: &str) -> Result<()> {
         let start = std::time::Instant::now();
         let operation = "update_user_display_name";
 
         let result = sqlx::query(
-            "INSERT INTO users (username, display_name) VALUES (?, ?) 
+            "INSERT INTO users (tenant, username, display_name) VALUES (?, ?, ?)
              ON DUPLICATE KEY UPDATE display_name = VALUES(display_name)",
         )
-        .bind(username)
+        .bind(tenant.as_str())
+        .bind(canonical_username(username))
         .bind(display_name)
         .execute(&self.pool)
         .await;
@@ -115,7 +398,7 @@ impl UserDatabase for MySqlUserDatabase {
         // Track database operation metrics
         if let Some(metrics) = try_get_metrics() {
             let status = if result.is_ok() { "success" } else { "error" };
-            crate::metrics::track_database_query(metrics, operation, status, duration);
+            crate::metrics::track_database_query(metrics, tenant, operation, status, duration);
         }
 
         match result {
@@ -127,9 +410,40 @@ impl UserDatabase for MySqlUserDatabase {
         }
     }
 
+    async fn update_user_profile(&self, tenant: &Tenant, username: &str, profile: &UserProfile) -> Result<()> {
+        let start = std::time::Instant::now();
+        let username = canonical_username(username);
+
+        // New users start with their username as display name, like the edit form does
+        let result = sqlx::query(
+            "INSERT INTO users (tenant, username, display_name, pronouns, bio, locale, timezone, website)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON DUPLICATE KEY UPDATE pronouns = VALUES(pronouns), bio = VALUES(bio), locale = VALUES(locale),
+                timezone = VALUES(timezone), website = VALUES(website)",
+        )
+        .bind(tenant.as_str())
+        .bind(&username)
+        .bind(&username)
+        .bind(&profile.pronouns)
+        .bind(&profile.bio)
+        .bind(&profile.locale)
+        .bind(&profile.timezone)
+        .bind(&profile.website)
+        .execute(&self.pool)
+        .await;
+
+        track_query(tenant, "update_user_profile", start, result.is_ok());
+        result?;
+
+        tracing::info!("Updated profile for user '{}' in MySQL", username);
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<String> {
         let start = std::time::Instant::now();
         let operation = "health_check";
+        // Not tenant-scoped: the count covers every tenant
+        let tenant = &Tenant::default();
 
         let result = sqlx::query("SELECT COUNT(*) as user_count FROM users")
             .fetch_one(&self.pool)
@@ -140,7 +454,7 @@ impl UserDatabase for MySqlUserDatabase {
         // Track database operation metrics
         if let Some(metrics) = try_get_metrics() {
             let status = if result.is_ok() { "success" } else { "error" };
-            crate::metrics::track_database_query(metrics, operation, status, duration);
+            crate::metrics::track_database_query(metrics, tenant, operation, status, duration);
         }
 
         match result {
@@ -151,4 +465,419 @@ impl UserDatabase for MySqlUserDatabase {
             Err(e) => Err(e.into()),
         }
     }
+
+    async fn set_display_name_override(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        app: &str,
+        display_name: &str,
+        max_apps: usize,
+    ) -> Result<bool> {
+        self.set_display_name_variant(tenant, DisplayNameVariants::Overrides, username, app, display_name, max_apps)
+            .await
+    }
+
+    async fn delete_display_name_override(&self, tenant: &Tenant, username: &str, app: &str) -> Result<bool> {
+        self.delete_display_name_variant(tenant, DisplayNameVariants::Overrides, username, app)
+            .await
+    }
+
+    async fn set_localized_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        locale: &str,
+        display_name: &str,
+        max_locales: usize,
+    ) -> Result<bool> {
+        self.set_display_name_variant(
+            tenant,
+            DisplayNameVariants::Localized,
+            username,
+            locale,
+            display_name,
+            max_locales,
+        )
+        .await
+    }
+
+    async fn delete_localized_display_name(&self, tenant: &Tenant, username: &str, locale: &str) -> Result<bool> {
+        self.delete_display_name_variant(tenant, DisplayNameVariants::Localized, username, locale)
+            .await
+    }
+
+    async fn submit_pending_display_name(&self, tenant: &Tenant, username: &str, display_name: &str) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        // A new submission replaces any earlier one, including a rejected one
+        let result = sqlx::query(
+            "INSERT INTO pending_display_names
+                (tenant, username, display_name, status, rejection_reason, submitted_at, reviewed_at)
+             VALUES (?, ?, ?, 'pending', NULL, CURRENT_TIMESTAMP, NULL)
+             ON DUPLICATE KEY UPDATE display_name = VALUES(display_name), status = 'pending',
+                rejection_reason = NULL, submitted_at = CURRENT_TIMESTAMP, reviewed_at = NULL",
+        )
+        .bind(tenant.as_str())
+        .bind(canonical_username(username))
+        .bind(display_name)
+        .execute(&self.pool)
+        .await;
+
+        track_query(tenant, "submit_pending_display_name", start, result.is_ok());
+        result?;
+
+        tracing::info!("Submitted display name for user '{}' for moderation", username);
+        Ok(())
+    }
+
+    async fn get_pending_display_name(&self, tenant: &Tenant, username: &str) -> Result<Option<PendingDisplayName>> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(&format!(
+            "SELECT {PENDING_COLUMNS} FROM pending_display_names WHERE tenant = ? AND username = ?"
+        ))
+        .bind(tenant.as_str())
+        .bind(canonical_username(username))
+        .fetch_optional(&self.pool)
+        .await;
+
+        track_query(tenant, "get_pending_display_name", start, result.is_ok());
+        Ok(result?.as_ref().map(pending_from_row))
+    }
+
+    async fn list_pending_display_names(&self, tenant: &Tenant) -> Result<Vec<PendingDisplayName>> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(&format!(
+            "SELECT {PENDING_COLUMNS} FROM pending_display_names
+             WHERE tenant = ? AND status = 'pending' ORDER BY submitted_at"
+        ))
+        .bind(tenant.as_str())
+        .fetch_all(&self.pool)
+        .await;
+
+        track_query(tenant, "list_pending_display_names", start, result.is_ok());
+        Ok(result?.iter().map(pending_from_row).collect())
+    }
+
+    async fn approve_pending_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+    ) -> Result<Option<PendingDisplayName>> {
+        let start = std::time::Instant::now();
+        let username = canonical_username(username);
+
+        let result: Result<Option<PendingDisplayName>> = async {
+            let mut tx = self.pool.begin().await?;
+
+            // Lock the submission so a concurrent resubmission cannot be approved unseen
+            let row = sqlx::query(&format!(
+                "SELECT {PENDING_COLUMNS} FROM pending_display_names
+                 WHERE tenant = ? AND username = ? AND status = 'pending' FOR UPDATE"
+            ))
+            .bind(tenant.as_str())
+            .bind(&username)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            let Some(submission) = row.as_ref().map(pending_from_row) else {
+                return Ok(None);
+            };
+
+            sqlx::query(
+                "INSERT INTO users (tenant, username, display_name) VALUES (?, ?, ?)
+                 ON DUPLICATE KEY UPDATE display_name = VALUES(display_name)",
+            )
+            .bind(tenant.as_str())
+            .bind(&username)
+            .bind(&submission.display_name)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("DELETE FROM pending_display_names WHERE tenant = ? AND username = ?")
+                .bind(tenant.as_str())
+                .bind(&username)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await?;
+            Ok(Some(submission))
+        }
+        .await;
+
+        track_query(tenant, "approve_pending_display_name", start, result.is_ok());
+
+        if let Ok(Some(_)) = &result {
+            tracing::info!("Approved pending display name for user '{}'", username);
+        }
+        result
+    }
+
+    async fn reject_pending_display_name(
+        &self,
+        tenant: &Tenant,
+        username: &str,
+        reason: &str,
+    ) -> Result<Option<PendingDisplayName>> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "UPDATE pending_display_names
+             SET status = 'rejected', rejection_reason = ?, reviewed_at = CURRENT_TIMESTAMP
+             WHERE tenant = ? AND username = ? AND status = 'pending'",
+        )
+        .bind(reason)
+        .bind(tenant.as_str())
+        .bind(canonical_username(username))
+        .execute(&self.pool)
+        .await;
+
+        track_query(tenant, "reject_pending_display_name", start, result.is_ok());
+
+        if result?.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        tracing::info!("Rejected pending display name for user '{}'", username);
+        self.get_pending_display_name(tenant, username).await
+    }
+
+    async fn get_avatar(&self, tenant: &Tenant, username: &str) -> Result<Option<StoredAvatar>> {
+        let start = std::time::Instant::now();
+
+        let result =
+            sqlx::query("SELECT content_type, data, etag, updated_at FROM avatars WHERE tenant = ? AND username = ?")
+                .bind(tenant.as_str())
+                .bind(canonical_username(username))
+                .fetch_optional(&self.pool)
+                .await;
+
+        track_query(tenant, "get_avatar", start, result.is_ok());
+
+        Ok(result?.map(|row| StoredAvatar {
+            content_type: row.get("content_type"),
+            data: row.get("data"),
+            etag: row.get("etag"),
+            updated_at: row.get("updated_at"),
+        }))
+    }
+
+    async fn put_avatar(&self, tenant: &Tenant, username: &str, avatar: &StoredAvatar) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT INTO avatars (tenant, username, content_type, data, etag, updated_at) VALUES (?, ?, ?, ?, ?, ?)
+             ON DUPLICATE KEY UPDATE content_type = VALUES(content_type), data = VALUES(data),
+                etag = VALUES(etag), updated_at = VALUES(updated_at)",
+        )
+        .bind(tenant.as_str())
+        .bind(canonical_username(username))
+        .bind(&avatar.content_type)
+        .bind(&avatar.data)
+        .bind(&avatar.etag)
+        .bind(avatar.updated_at)
+        .execute(&self.pool)
+        .await;
+
+        track_query(tenant, "put_avatar", start, result.is_ok());
+        result?;
+
+        tracing::info!("Stored avatar for user '{}' in MySQL", username);
+        Ok(())
+    }
+
+    async fn revoke_token(&self, tenant: &Tenant, revoked: &RevokedToken) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT INTO revoked_tokens (tenant, issuer, jti, expires_at) VALUES (?, ?, ?, ?)
+             ON DUPLICATE KEY UPDATE expires_at = GREATEST(expires_at, VALUES(expires_at))",
+        )
+        .bind(tenant.as_str())
+        .bind(&revoked.issuer)
+        .bind(&revoked.jti)
+        .bind(revoked.expires_at)
+        .execute(&self.pool)
+        .await;
+
+        track_query(tenant, "revoke_token", start, result.is_ok());
+        result?;
+
+        tracing::info!(
+            "Revoked token '{}' of '{}' in tenant '{}' until {} in MySQL",
+            revoked.jti,
+            revoked.issuer,
+            tenant,
+            revoked.expires_at
+        );
+        Ok(())
+    }
+
+    async fn revoke_subject(&self, tenant: &Tenant, revoked: &RevokedSubject) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(
+            "INSERT INTO revoked_subjects (tenant, issuer, subject, issued_before, expires_at) VALUES (?, ?, ?, ?, ?)
+             ON DUPLICATE KEY UPDATE issued_before = GREATEST(issued_before, VALUES(issued_before)),
+                 expires_at = GREATEST(expires_at, VALUES(expires_at))",
+        )
+        .bind(tenant.as_str())
+        .bind(&revoked.issuer)
+        .bind(&revoked.subject)
+        .bind(revoked.issued_before)
+        .bind(revoked.expires_at)
+        .execute(&self.pool)
+        .await;
+
+        track_query(tenant, "revoke_subject", start, result.is_ok());
+        result?;
+
+        tracing::info!(
+            "Revoked tokens of '{}' from '{}' in tenant '{}' issued before {} in MySQL",
+            revoked.subject,
+            revoked.issuer,
+            tenant,
+            revoked.issued_before
+        );
+        Ok(())
+    }
+
+    async fn get_revocations(&self, now: DateTime<Utc>) -> Result<Revocations> {
+        let start = std::time::Instant::now();
+
+        let tokens = sqlx::query("SELECT tenant, issuer, jti, expires_at FROM revoked_tokens WHERE expires_at > ?")
+            .bind(now)
+            .fetch_all(&self.pool)
+            .await;
+        let subjects = sqlx::query(
+            "SELECT tenant, issuer, subject, issued_before, expires_at FROM revoked_subjects WHERE expires_at > ?",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await;
+
+        track_query(&Tenant::default(), "get_revocations", start, tokens.is_ok() && subjects.is_ok());
+
+        Ok(Revocations {
+            tokens: tokens?
+                .iter()
+                .map(|row| {
+                    let revoked = RevokedToken {
+                        issuer: row.get("issuer"),
+                        jti: row.get("jti"),
+                        expires_at: row.get("expires_at"),
+                    };
+                    Ok((row_tenant(row)?, revoked))
+                })
+                .collect::<Result<_>>()?,
+            subjects: subjects?
+                .iter()
+                .map(|row| {
+                    let revoked = RevokedSubject {
+                        issuer: row.get("issuer"),
+                        subject: row.get("subject"),
+                        issued_before: row.get("issued_before"),
+                        expires_at: row.get("expires_at"),
+                    };
+                    Ok((row_tenant(row)?, revoked))
+                })
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    async fn delete_expired_revocations(&self, now: DateTime<Utc>) -> Result<u64> {
+        let start = std::time::Instant::now();
+
+        let tokens = sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await;
+        let subjects = sqlx::query("DELETE FROM revoked_subjects WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&self.pool)
+            .await;
+
+        track_query(
+            &Tenant::default(),
+            "delete_expired_revocations",
+            start,
+            tokens.is_ok() && subjects.is_ok(),
+        );
+
+        Ok(tokens?.rows_affected() + subjects?.rows_affected())
+    }
+
+    async fn create_api_key(&self, tenant: &Tenant, key: &ApiKey) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        let allowed_ips: Vec<String> = key.allowed_ips.iter().map(ToString::to_string).collect();
+        let result = sqlx::query(
+            "INSERT INTO api_keys (tenant, id, name, key_hash, scope, allowed_ips, quota_per_minute, created_by, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(tenant.as_str())
+        .bind(&key.id)
+        .bind(&key.name)
+        .bind(&key.key_hash)
+        .bind(key.scope.as_str())
+        .bind(allowed_ips.join(","))
+        .bind(key.quota_per_minute)
+        .bind(&key.created_by)
+        .bind(key.created_at)
+        .execute(&self.pool)
+        .await;
+
+        track_query(tenant, "create_api_key", start, result.is_ok());
+        result?;
+
+        tracing::info!("Created API key '{}' ({}) in MySQL", key.name, key.id);
+        Ok(())
+    }
+
+    async fn list_api_keys(&self, tenant: &Tenant) -> Result<Vec<ApiKey>> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(&format!(
+            "SELECT {API_KEY_COLUMNS} FROM api_keys WHERE tenant = ? ORDER BY created_at"
+        ))
+        .bind(tenant.as_str())
+        .fetch_all(&self.pool)
+        .await;
+
+        track_query(tenant, "list_api_keys", start, result.is_ok());
+
+        Ok(result?.iter().map(api_key_from_row).collect())
+    }
+
+    async fn find_api_key(&self, tenant: &Tenant, key_hash: &str) -> Result<Option<ApiKey>> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query(&format!(
+            "SELECT {API_KEY_COLUMNS} FROM api_keys WHERE tenant = ? AND key_hash = ?"
+        ))
+        .bind(tenant.as_str())
+        .bind(key_hash)
+        .fetch_optional(&self.pool)
+        .await;
+
+        track_query(tenant, "find_api_key", start, result.is_ok());
+
+        Ok(result?.as_ref().map(api_key_from_row))
+    }
+
+    async fn delete_api_key(&self, tenant: &Tenant, id: &str) -> Result<bool> {
+        let start = std::time::Instant::now();
+
+        let result = sqlx::query("DELETE FROM api_keys WHERE tenant = ? AND id = ?")
+            .bind(tenant.as_str())
+            .bind(id)
+            .execute(&self.pool)
+            .await;
+
+        track_query(tenant, "delete_api_key", start, result.is_ok());
+
+        Ok(result?.rows_affected() > 0)
+    }
 }
@@ -2,6 +2,7 @@ use anyhow::Result;
 use std::sync::Arc;
 
 use crate::database::UserDatabase;
+use crate::tenant::Tenant;
 
 /// Marker struct to represent database seeding status
 #[derive(Debug, Clone)]
@@ -23,13 +24,15 @@ pub struct SeedStatus {
 pub const EXPECTED_SEED_USERS: [&str; 3] = ["admin", "testuser", "demo"];
 
 /// Check if the database contains the expected seed data
+///
+/// Migrations seed the default tenant only.
 pub async fn check_database_seeding(db: Arc<dyn UserDatabase>) -> Result<SeedStatus> {
     let mut found_users = Vec::new();
     let mut missing_users = Vec::new();
 
     // Check for each expected seed user
     for &username in EXPECTED_SEED_USERS.iter() {
-        match db.get_user(username).await? {
+        match db.get_user(&Tenant::default(), username).await? {
             Some(_) => found_users.push(username.to_string()),
             None => missing_users.push(username.to_string()),
         }
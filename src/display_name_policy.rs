@@ -0,0 +1,422 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
+use unicode_script::{Script, UnicodeScript};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::errors::AppError;
+use crate::tenant::Tenant;
+use crate::validation::DISPLAY_NAME_MAX_GRAPHEMES;
+
+/// Deployment-specific content rules for display names.
+///
+/// Implementations run after the built-in `validate_display_name` checks, so they only
+/// need to enforce what differs between deployments.
+pub trait DisplayNamePolicy: Send + Sync {
+    /// Check a sanitized display name, returning a validation error if it is not allowed
+    fn check(&self, display_name: &str) -> Result<(), AppError>;
+}
+
+/// Policy settings as read from `DISPLAY_NAME_POLICY_FILE` (JSON)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DisplayNamePolicyConfig {
+    /// Minimum length in user-perceived characters
+    pub min_length: usize,
+    /// Maximum length in user-perceived characters (capped at the global limit)
+    pub max_length: usize,
+    /// Script names (`Latin`, `Latn`, `Han`, ...); empty allows every script
+    pub allowed_scripts: Vec<String>,
+    /// Words (or phrases) rejected when they appear as whole words, after leetspeak normalization
+    pub denied_words: Vec<String>,
+    /// Regular expressions matched against both the raw and the normalized name, for substring rules
+    pub denied_patterns: Vec<String>,
+}
+
+impl Default for DisplayNamePolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 1,
+            max_length: DISPLAY_NAME_MAX_GRAPHEMES,
+            allowed_scripts: Vec::new(),
+            denied_words: Vec::new(),
+            denied_patterns: Vec::new(),
+        }
+    }
+}
+
+/// Default policy: length bounds, allowed scripts and a leetspeak-aware denylist
+pub struct DefaultDisplayNamePolicy {
+    min_length: usize,
+    max_length: usize,
+    allowed_scripts: Vec<String>,
+    /// Each denied word or phrase as normalized tokens
+    denied_words: Vec<Vec<String>>,
+    denied_patterns: Vec<Regex>,
+}
+
+impl DefaultDisplayNamePolicy {
+    pub fn from_config(config: DisplayNamePolicyConfig) -> Result<Self> {
+        if config.min_length == 0 || config.min_length > config.max_length {
+            anyhow::bail!(
+                "Display name policy min_length must be between 1 and max_length, got {}",
+                config.min_length
+            );
+        }
+
+        if config.max_length > DISPLAY_NAME_MAX_GRAPHEMES {
+            anyhow::bail!(
+                "Display name policy max_length cannot exceed {}, got {}",
+                DISPLAY_NAME_MAX_GRAPHEMES,
+                config.max_length
+            );
+        }
+
+        let denied_patterns = config
+            .denied_patterns
+            .iter()
+            .map(|pattern| Regex::new(pattern).with_context(|| format!("Invalid denied pattern: {pattern}")))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            min_length: config.min_length,
+            max_length: config.max_length,
+            allowed_scripts: config.allowed_scripts.iter().map(|s| s.to_lowercase()).collect(),
+            denied_words: config
+                .denied_words
+                .iter()
+                .map(|word| tokenize(word))
+                .filter(|tokens| !tokens.is_empty())
+                .collect(),
+            denied_patterns,
+        })
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let contents =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read display name policy from {path}"))?;
+        let config: DisplayNamePolicyConfig =
+            serde_json::from_str(&contents).with_context(|| format!("Invalid display name policy in {path}"))?;
+
+        Self::from_config(config)
+    }
+
+    fn is_script_allowed(&self, script: Script) -> bool {
+        if self.allowed_scripts.is_empty() || matches!(script, Script::Common | Script::Inherited | Script::Unknown) {
+            return true;
+        }
+
+        self.allowed_scripts.iter().any(|allowed| {
+            allowed == &script.full_name().to_lowercase() || allowed == &script.short_name().to_lowercase()
+        })
+    }
+}
+
+impl Default for DefaultDisplayNamePolicy {
+    fn default() -> Self {
+        Self::from_config(DisplayNamePolicyConfig::default()).expect("Default display name policy is valid")
+    }
+}
+
+impl DisplayNamePolicy for DefaultDisplayNamePolicy {
+    fn check(&self, display_name: &str) -> Result<(), AppError> {
+        let length = display_name.graphemes(true).count();
+
+        if length < self.min_length {
+            return Err(AppError::validation_failed(format!(
+                "Display name must be at least {} characters long",
+                self.min_length
+            ))
+            .localized("display-name-too-short", [("min", self.min_length.into())]));
+        }
+
+        if length > self.max_length {
+            return Err(AppError::validation_failed(format!(
+                "Display name must be at most {} characters long",
+                self.max_length
+            ))
+            .localized("display-name-too-long", [("max", self.max_length.into())]));
+        }
+
+        if let Some(script) = display_name.chars().map(|c| c.script()).find(|s| !self.is_script_allowed(*s)) {
+            return Err(AppError::validation_failed(format!(
+                "Display name cannot contain {} characters",
+                script.full_name()
+            ))
+            .localized("display-name-script-not-allowed", [("script", script.full_name().into())]));
+        }
+
+        let tokens = tokenize(display_name);
+        let normalized = normalize_leetspeak(display_name);
+
+        let denied = self
+            .denied_words
+            .iter()
+            .any(|word| tokens.windows(word.len()).any(|window| window == word.as_slice()))
+            || self
+                .denied_patterns
+                .iter()
+                .any(|pattern| pattern.is_match(display_name) || pattern.is_match(&normalized));
+
+        if denied {
+            tracing::warn!("Display name '{}' rejected by denylist", display_name);
+            return Err(AppError::validation_failed("Display name contains a word that is not allowed")
+                .localized("display-name-denied-word", []));
+        }
+
+        Ok(())
+    }
+}
+
+/// Lowercase, fold compatibility forms and undo common leetspeak substitutions (`4dm1n` → `admin`)
+pub fn normalize_leetspeak(text: &str) -> String {
+    text.nfkc()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            '0' => 'o',
+            '1' | '!' => 'i',
+            '3' => 'e',
+            '4' | '@' => 'a',
+            '5' | '$' => 's',
+            '7' | '+' => 't',
+            '8' => 'b',
+            '9' => 'g',
+            '|' => 'l',
+            c => c,
+        })
+        .collect()
+}
+
+/// Normalized words of `text`, so `B4D` matches `bad` but `Badger` does not
+///
+/// Runs of single characters are joined into one word, so a word spelled out as `b.a.d` is still caught.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut spelled = String::new();
+
+    for token in normalize_leetspeak(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+    {
+        if token.chars().count() == 1 {
+            spelled.push_str(token);
+            continue;
+        }
+        if !spelled.is_empty() {
+            tokens.push(std::mem::take(&mut spelled));
+        }
+        tokens.push(token.to_string());
+    }
+
+    if !spelled.is_empty() {
+        tokens.push(spelled);
+    }
+    tokens
+}
+
+/// Display name policy of each tenant, falling back to a default policy
+#[derive(Clone)]
+pub struct DisplayNamePolicies {
+    default: Arc<dyn DisplayNamePolicy>,
+    tenants: HashMap<Tenant, Arc<dyn DisplayNamePolicy>>,
+}
+
+impl DisplayNamePolicies {
+    pub fn new(default: Arc<dyn DisplayNamePolicy>, tenants: HashMap<Tenant, Arc<dyn DisplayNamePolicy>>) -> Self {
+        Self { default, tenants }
+    }
+
+    /// The tenant's own policy, or the default one
+    pub fn for_tenant(&self, tenant: &Tenant) -> &dyn DisplayNamePolicy {
+        self.tenants.get(tenant).unwrap_or(&self.default).as_ref()
+    }
+
+    /// Number of tenants with their own policy
+    pub fn tenant_count(&self) -> usize {
+        self.tenants.len()
+    }
+}
+
+impl Default for DisplayNamePolicies {
+    fn default() -> Self {
+        Self::new(Arc::new(DefaultDisplayNamePolicy::default()), HashMap::new())
+    }
+}
+
+/// Load `<tenant>.json` policies from `dir`; other files are ignored
+fn load_tenant_policies(dir: &Path) -> Result<HashMap<Tenant, Arc<dyn DisplayNamePolicy>>> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read display name policy directory {}", dir.display()))?;
+
+    let mut policies: HashMap<Tenant, Arc<dyn DisplayNamePolicy>> = HashMap::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+
+        let stem = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+        let tenant = Tenant::new(stem)
+            .map_err(|e| anyhow::anyhow!("Invalid tenant in display name policy file {}: {}", path.display(), e))?;
+        let policy = DefaultDisplayNamePolicy::from_file(&path.to_string_lossy())?;
+        policies.insert(tenant, Arc::new(policy));
+    }
+
+    Ok(policies)
+}
+
+/// Create the display name policies
+///
+/// - `DISPLAY_NAME_POLICY_FILE`: policy for tenants without their own (default: built-in policy)
+/// - `DISPLAY_NAME_POLICY_DIR`: directory of `<tenant>.json` policies, replacing the default for those tenants
+pub fn create_display_name_policy_from_env() -> Result<DisplayNamePolicies> {
+    let default: Arc<dyn DisplayNamePolicy> = match std::env::var("DISPLAY_NAME_POLICY_FILE") {
+        Ok(path) => {
+            let policy = DefaultDisplayNamePolicy::from_file(&path)?;
+            tracing::info!("Display name policy loaded from {}", path);
+            Arc::new(policy)
+        }
+        Err(_) => Arc::new(DefaultDisplayNamePolicy::default()),
+    };
+
+    let tenants = match std::env::var("DISPLAY_NAME_POLICY_DIR") {
+        Ok(dir) => {
+            let tenants = load_tenant_policies(Path::new(&dir))?;
+            tracing::info!("Loaded {} tenant display name policies from {}", tenants.len(), dir);
+            tenants
+        }
+        Err(_) => HashMap::new(),
+    };
+
+    Ok(DisplayNamePolicies::new(default, tenants))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(json: &str) -> DefaultDisplayNamePolicy {
+        DefaultDisplayNamePolicy::from_config(serde_json::from_str(json).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_default_policy_allows_valid_names() {
+        let policy = DefaultDisplayNamePolicy::default();
+
+        assert!(policy.check("John Doe").is_ok());
+        assert!(policy.check("山田 太郎").is_ok());
+        assert!(policy.check("A").is_ok());
+    }
+
+    #[test]
+    fn test_length_bounds() {
+        let policy = policy(r#"{"min_length": 3, "max_length": 10}"#);
+
+        assert!(policy.check("Al").is_err());
+        assert!(policy.check("Ali").is_ok());
+        assert!(policy.check("山田太郎").is_ok()); // counted in characters, not bytes
+        assert!(policy.check("Alexandria Smith").is_err());
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let config = |json: &str| serde_json::from_str::<DisplayNamePolicyConfig>(json).unwrap();
+
+        assert!(DefaultDisplayNamePolicy::from_config(config(r#"{"min_length": 0}"#)).is_err());
+        assert!(DefaultDisplayNamePolicy::from_config(config(r#"{"min_length": 20, "max_length": 10}"#)).is_err());
+        assert!(DefaultDisplayNamePolicy::from_config(config(r#"{"max_length": 500}"#)).is_err());
+        assert!(DefaultDisplayNamePolicy::from_config(config(r#"{"denied_patterns": ["("]}"#)).is_err());
+    }
+
+    #[test]
+    fn test_allowed_scripts() {
+        let policy = policy(r#"{"allowed_scripts": ["Latin", "Hira"]}"#);
+
+        assert!(policy.check("John Doe 123!").is_ok());
+        assert!(policy.check("José").is_ok()); // combining marks are inherited
+        assert!(policy.check("ひらがな").is_ok());
+        assert!(policy.check("Иван").is_err());
+    }
+
+    #[test]
+    fn test_denied_words_with_leetspeak() {
+        let policy = policy(r#"{"denied_words": ["badword"]}"#);
+
+        assert!(policy.check("My Badword").is_err());
+        assert!(policy.check("b4dw0rd").is_err());
+        assert!(policy.check("B.A.D W-O-R-D").is_err());
+        assert!(policy.check("ｂａｄｗｏｒｄ").is_err()); // fullwidth
+        assert!(policy.check("Big Bad Wolf").is_ok());
+    }
+
+    #[test]
+    fn test_denied_words_match_whole_words() {
+        let policy = policy(r#"{"denied_words": ["ass", "john", "big bad"]}"#);
+
+        assert!(policy.check("Ass").is_err());
+        assert!(policy.check("Smart A$$").is_err());
+        assert!(policy.check("A.S.S").is_err());
+        assert!(policy.check("The BIG-BAD wolf").is_err()); // phrases match consecutive words
+
+        assert!(policy.check("Cassandra").is_ok());
+        assert!(policy.check("Glass Shop").is_ok());
+        assert!(policy.check("Bob Assen").is_ok()); // no match across or inside words
+        assert!(policy.check("Jo Hnson").is_ok());
+        assert!(policy.check("Johnny Big").is_ok());
+        assert!(policy.check("Bad Big").is_ok());
+    }
+
+    #[test]
+    fn test_denied_patterns_match_substrings() {
+        let policy = policy(r#"{"denied_patterns": ["(?i)admin"]}"#);
+
+        assert!(policy.check("SuperAdmin").is_err());
+        assert!(policy.check("4dm1nistrator").is_err());
+        assert!(policy.check("Ad Min").is_ok());
+    }
+
+    #[test]
+    fn test_denied_patterns() {
+        let policy = policy(r#"{"denied_patterns": ["(?i)^official\\b"]}"#);
+
+        assert!(policy.check("Official Support").is_err());
+        assert!(policy.check("0ff1c1al support").is_err()); // matched after normalization
+        assert!(policy.check("Unofficial fan").is_ok());
+    }
+
+    #[test]
+    fn test_normalize_leetspeak() {
+        assert_eq!(normalize_leetspeak("4DM1N"), "admin");
+        assert_eq!(normalize_leetspeak("$3cr3t"), "secret");
+        assert_eq!(tokenize("B.A.D w0rd"), ["bad", "word"]);
+        assert_eq!(tokenize("J. R. R. Tolkien"), ["jrr", "tolkien"]);
+    }
+
+    #[test]
+    fn test_tenant_policies() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("brand-a.json"), r#"{"denied_words": ["rival"]}"#).unwrap();
+        std::fs::write(dir.path().join("README.txt"), "not a policy").unwrap();
+
+        let policies = DisplayNamePolicies::new(
+            Arc::new(policy(r#"{"min_length": 3}"#)),
+            load_tenant_policies(dir.path()).unwrap(),
+        );
+        assert_eq!(policies.tenant_count(), 1);
+
+        let brand = Tenant::new("brand-a").unwrap();
+        assert!(policies.for_tenant(&brand).check("Rival Fan").is_err());
+        assert!(policies.for_tenant(&brand).check("Al").is_ok()); // replaces the default, not merged with it
+
+        let other = Tenant::new("brand-b").unwrap();
+        assert!(policies.for_tenant(&other).check("Rival Fan").is_ok());
+        assert!(policies.for_tenant(&other).check("Al").is_err());
+
+        std::fs::write(dir.path().join("not a tenant.json"), "{}").unwrap();
+        assert!(load_tenant_policies(dir.path()).is_err());
+    }
+}
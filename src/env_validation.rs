@@ -5,7 +5,7 @@ use tracing::info;
 pub fn validate_environment() -> Result<()> {
     info!("Validating environment variables...");
 
-    let required_vars = vec!["DATABASE_ADAPTER", "JWT_PUBLIC_KEY"];
+    let required_vars = vec!["DATABASE_ADAPTER"];
 
     let mut missing_vars = Vec::new();
     let mut validation_errors = Vec::new();
@@ -16,6 +16,15 @@ pub fn validate_environment() -> Result<()> {
         }
     }
 
+    // Tokens are verified either with a fixed key, with the keys of a JWKS endpoint,
+    // or with the keys of each issuer listed in an issuers file
+    if ["JWT_PUBLIC_KEY", "JWT_PUBLIC_KEY_FILE", "JWT_JWKS_URL", "JWT_ISSUERS_FILE"]
+        .iter()
+        .all(|var| env::var(var).is_err())
+    {
+        missing_vars.push("JWT_PUBLIC_KEY (or JWT_PUBLIC_KEY_FILE, JWT_JWKS_URL or JWT_ISSUERS_FILE)");
+    }
+
     if !missing_vars.is_empty() {
         anyhow::bail!("Missing required environment variables: {}", missing_vars.join(", "));
     }
@@ -46,6 +55,8 @@ pub fn validate_environment() -> Result<()> {
         "ENABLE_DATABASE_QUERY_CACHING",
         "ENABLE_GZIP_COMPRESSION",
         "ENABLE_BROTLI_COMPRESSION",
+        "ENABLE_DISPLAY_NAME_CONFUSABLE_CHECK",
+        "ENABLE_DISPLAY_NAME_MODERATION",
     ];
 
     for flag in boolean_flags {
@@ -56,6 +67,74 @@ pub fn validate_environment() -> Result<()> {
         }
     }
 
+    if let Ok(storage) = env::var("AVATAR_STORAGE") {
+        if !["database", "directory"].contains(&storage.as_str()) {
+            validation_errors.push(format!("AVATAR_STORAGE must be 'database' or 'directory', got: {storage}"));
+        }
+    }
+
+    if let Ok(max_bytes) = env::var("AVATAR_MAX_UPLOAD_BYTES") {
+        match max_bytes.parse::<usize>() {
+            Ok(bytes) if (1024..=16 * 1024 * 1024).contains(&bytes) => {}
+            _ => validation_errors.push(format!(
+                "AVATAR_MAX_UPLOAD_BYTES must be between 1024 and 16777216, got: {max_bytes}"
+            )),
+        }
+    }
+
+    for var in ["AVATAR_MAX_DIMENSION", "AVATAR_SIZE"] {
+        if let Ok(value) = env::var(var) {
+            match value.parse::<u32>() {
+                Ok(pixels) if (16..=8192).contains(&pixels) => {}
+                _ => validation_errors.push(format!("{var} must be between 16 and 8192 pixels, got: {value}")),
+            }
+        }
+    }
+
+    if let Ok(tenant) = env::var("DEFAULT_TENANT") {
+        if let Err(e) = crate::tenant::Tenant::new(&tenant) {
+            validation_errors.push(format!("DEFAULT_TENANT is invalid: {}", e.message));
+        }
+    }
+
+    if let Ok(hosts) = env::var("TENANT_HOSTS") {
+        if let Err(e) = crate::config::tenancy::parse_tenant_hosts(&hosts) {
+            validation_errors.push(e.to_string());
+        }
+    }
+
+    if let Ok(algorithm) = env::var("JWT_ALGORITHM") {
+        if let Err(e) = crate::middleware::jwt_auth::parse_algorithm(&algorithm) {
+            validation_errors.push(e.to_string());
+        }
+    }
+
+    if let Ok(jwks_url) = env::var("JWT_JWKS_URL") {
+        match url::Url::parse(&jwks_url) {
+            Ok(url) if ["http", "https"].contains(&url.scheme()) => info!("JWT keys from JWKS: {}", url),
+            _ => validation_errors.push(format!("JWT_JWKS_URL must be an http(s) URL, got: {jwks_url}")),
+        }
+    }
+
+    for var in [
+        "JWT_JWKS_CACHE_SECONDS",
+        "JWT_JWKS_MIN_REFRESH_SECONDS",
+        "JWT_MAX_AGE_SECONDS",
+        "JWT_CLOCK_SKEW_SECONDS",
+    ] {
+        if let Ok(value) = env::var(var) {
+            if value.parse::<u64>().is_err() {
+                validation_errors.push(format!("{var} must be a number of seconds, got: {value}"));
+            }
+        }
+    }
+
+    if let Ok(locale) = env::var("DEFAULT_LOCALE") {
+        if crate::language::language_range(&locale).is_none() {
+            validation_errors.push(format!("DEFAULT_LOCALE must be a language tag such as 'en', got: {locale}"));
+        }
+    }
+
     if let Ok(port_str) = env::var("DATABASE_PORT") {
         match port_str.parse::<u16>() {
             Ok(port) if port > 0 => {
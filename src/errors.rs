@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header::WWW_AUTHENTICATE, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -12,17 +12,91 @@ pub struct AppError {
     pub code: ErrorCode,
     pub message: String,
     pub details: Option<String>,
+    /// Catalog message `message` was written from, so responses can be localized
+    pub localized: Option<LocalizedMessage>,
+    /// Why an authenticated request was refused, for the auth failure metric
+    pub auth_failure: Option<AuthFailure>,
 }
 
-#[derive(Debug)]
+/// Reason label of a refused authenticated request, left in the response extensions
+/// for `auth_metrics_middleware`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthFailure(pub &'static str);
+
+/// A message id in the locale catalogs (`locales/*.ftl`) with its arguments.
+///
+/// The id is part of the API: clients can key their own translations on it.
+#[derive(Debug, Clone)]
+pub struct LocalizedMessage {
+    pub id: &'static str,
+    pub args: Vec<(&'static str, MessageArg)>,
+}
+
+/// Argument of a catalog message; numbers drive plural selection
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageArg {
+    Text(String),
+    Number(f64),
+}
+
+impl From<String> for MessageArg {
+    fn from(value: String) -> Self {
+        MessageArg::Text(value)
+    }
+}
+
+impl From<&str> for MessageArg {
+    fn from(value: &str) -> Self {
+        MessageArg::Text(value.to_string())
+    }
+}
+
+impl From<usize> for MessageArg {
+    fn from(value: usize) -> Self {
+        MessageArg::Number(value as f64)
+    }
+}
+
+impl From<i64> for MessageArg {
+    fn from(value: i64) -> Self {
+        MessageArg::Number(value as f64)
+    }
+}
+
+impl From<u32> for MessageArg {
+    fn from(value: u32) -> Self {
+        MessageArg::Number(value.into())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ErrorCode {
     ValidationFailed,
     UserNotFound,
     DatabaseError,
     InvalidInput,
+    Unauthorized,
+    Forbidden,
+    PayloadTooLarge,
     InternalServerError,
 }
 
+impl ErrorCode {
+    /// Stable code sent to clients in the `code` field of error responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::ValidationFailed => "ValidationFailed",
+            ErrorCode::UserNotFound => "UserNotFound",
+            ErrorCode::DatabaseError => "DatabaseError",
+            ErrorCode::InvalidInput => "InvalidInput",
+            ErrorCode::Unauthorized => "Unauthorized",
+            ErrorCode::Forbidden => "Forbidden",
+            ErrorCode::PayloadTooLarge => "PayloadTooLarge",
+            ErrorCode::InternalServerError => "InternalServerError",
+        }
+    }
+}
+
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}: {}", self.code, self.message)
@@ -37,15 +111,34 @@ impl AppError {
             code,
             message: message.into(),
             details: None,
+            localized: None,
+            auth_failure: None,
         }
     }
 
+    /// Attach the catalog message this error's (English) text corresponds to.
+    ///
+    /// The response is rendered from the catalog in the reader's locale; `message` stays
+    /// the log text and the fallback when no catalog applies.
+    pub fn localized<const N: usize>(mut self, id: &'static str, args: [(&'static str, MessageArg); N]) -> Self {
+        self.localized = Some(LocalizedMessage { id, args: args.into() });
+        self
+    }
+
+    /// Record why an authenticated request was refused (`admin_role_required`, `tenant_mismatch`, ...)
+    pub fn auth_failure(mut self, reason: &'static str) -> Self {
+        self.auth_failure = Some(AuthFailure(reason));
+        self
+    }
+
     pub fn validation_failed(message: impl Into<String>) -> Self {
         Self::new(ErrorCode::ValidationFailed, message)
     }
 
     pub fn user_not_found(username: impl Into<String>) -> Self {
-        Self::new(ErrorCode::UserNotFound, format!("User '{}' not found", username.into()))
+        let username = username.into();
+        Self::new(ErrorCode::UserNotFound, format!("User '{}' not found", username))
+            .localized("user-not-found", [("username", username.into())])
     }
 
     pub fn database_error(message: impl Into<String>) -> Self {
@@ -56,6 +149,14 @@ impl AppError {
         Self::new(ErrorCode::InvalidInput, message)
     }
 
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Forbidden, message)
+    }
+
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::PayloadTooLarge, message)
+    }
+
     pub fn internal_server_error(message: impl Into<String>) -> Self {
         Self::new(ErrorCode::InternalServerError, message)
     }
@@ -70,6 +171,9 @@ impl IntoResponse for AppError {
             ErrorCode::UserNotFound => (StatusCode::NOT_FOUND, "User not found"),
             ErrorCode::DatabaseError => (StatusCode::INTERNAL_SERVER_ERROR, "Database error"),
             ErrorCode::InvalidInput => (StatusCode::BAD_REQUEST, "Invalid input"),
+            ErrorCode::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
+            ErrorCode::Forbidden => (StatusCode::FORBIDDEN, "Forbidden"),
+            ErrorCode::PayloadTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, "Payload too large"),
             ErrorCode::InternalServerError => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error"),
         };
 
@@ -78,26 +182,164 @@ impl IntoResponse for AppError {
             error!("Error details: {}", details);
         }
 
-        let body = json!({
-            "error": {
-                "code": format!("{:?}", self.code),
-                "message": self.message,
-                "details": self.details
-            }
-        });
+        let body = error_body(self.code, &self.message, self.localized.as_ref(), self.details.as_deref());
+        let mut response = (status, Json(body)).into_response();
+        if let Some(auth_failure) = self.auth_failure {
+            response.extensions_mut().insert(auth_failure);
+        }
+
+        // The locale middleware re-renders the body with the message in the reader's language
+        if let Some(localized) = self.localized {
+            response.extensions_mut().insert(LocalizedError {
+                code: self.code,
+                message: localized,
+                details: self.details,
+            });
+        }
 
-        (status, Json(body)).into_response()
+        response
+    }
+}
+
+/// Why a request's token was refused.
+///
+/// Responses are `401 Unauthorized` with an RFC 6750 `WWW-Authenticate: Bearer` challenge and
+/// the usual JSON error body, and carry the reason for the `auth_failure_total` metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthError {
+    /// No token in the session cookie, `Authorization` header or legacy cookie
+    MissingToken,
+    /// Not a JWT: wrong number of segments, or segments that are not base64url JSON
+    MalformedToken,
+    /// Past its `exp` claim
+    TokenExpired,
+    /// The signature does not verify, or the signing key or algorithm is not the issuer's
+    InvalidSignature,
+    /// The `aud` claim names none of the issuer's audiences
+    InvalidAudience,
+    /// The `iss` claim is missing or not a trusted issuer
+    InvalidIssuer,
+    /// Revoked by its `jti` or subject (see `POST /api/revocations`)
+    TokenRevoked,
+    /// Issued longer ago than the maximum token age
+    TokenTooOld,
+    /// Issued in the future, beyond the clock skew leeway
+    TokenIssuedInFuture,
+    /// Issued longer ago than the route's freshness limit, in seconds (RFC 9470 step-up)
+    TokenNotFresh { max_age: u64 },
+    /// Refused for another reason, such as a `nbf` claim in the future
+    InvalidToken,
+    /// The `X-API-Key` header names no key of the host's tenant
+    InvalidApiKey,
+}
+
+impl AuthError {
+    /// Label of the `auth_failure_total` metric
+    pub fn reason(&self) -> &'static str {
+        match self {
+            AuthError::MissingToken => "missing_token",
+            AuthError::MalformedToken => "malformed_token",
+            AuthError::TokenExpired => "token_expired",
+            AuthError::InvalidSignature => "invalid_signature",
+            AuthError::InvalidAudience => "invalid_audience",
+            AuthError::InvalidIssuer => "invalid_issuer",
+            AuthError::TokenRevoked => "token_revoked",
+            AuthError::TokenTooOld => "token_too_old",
+            AuthError::TokenIssuedInFuture => "token_issued_in_future",
+            AuthError::TokenNotFresh { .. } => "token_not_fresh",
+            AuthError::InvalidToken => "invalid_token",
+            AuthError::InvalidApiKey => "invalid_api_key",
+        }
     }
+
+    /// Catalog message id and its English text, also sent as the challenge's `error_description`
+    fn message(&self) -> (&'static str, &'static str) {
+        match self {
+            AuthError::MissingToken => ("token-missing", "Authentication required"),
+            AuthError::MalformedToken => ("token-malformed", "The token is malformed"),
+            AuthError::TokenExpired => ("token-expired", "The token has expired"),
+            AuthError::InvalidSignature => ("token-signature-invalid", "The token signature is invalid"),
+            AuthError::InvalidAudience => ("token-audience-invalid", "The token is not intended for this service"),
+            AuthError::InvalidIssuer => ("token-issuer-invalid", "The token issuer is not trusted"),
+            AuthError::TokenRevoked => ("token-revoked", "The token has been revoked"),
+            AuthError::TokenTooOld => ("token-too-old", "The token is too old"),
+            AuthError::TokenIssuedInFuture => ("token-issued-in-future", "The token is issued in the future"),
+            AuthError::TokenNotFresh { .. } => ("token-not-fresh", "A more recent authentication is required"),
+            AuthError::InvalidToken => ("token-invalid", "The token is invalid"),
+            AuthError::InvalidApiKey => ("api-key-invalid", "The API key is invalid"),
+        }
+    }
+
+    /// `WWW-Authenticate` value; a request without a token gets a bare challenge (RFC 6750, section 3.1)
+    pub fn challenge(&self) -> String {
+        let (_, description) = self.message();
+        match self {
+            AuthError::MissingToken => "Bearer".to_string(),
+            AuthError::TokenNotFresh { max_age } => format!(
+                r#"Bearer error="insufficient_user_authentication", error_description="{description}", max_age="{max_age}""#
+            ),
+            _ => format!(r#"Bearer error="invalid_token", error_description="{description}""#),
+        }
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message().1, self.reason())
+    }
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        let (id, message) = self.message();
+        let localized = LocalizedMessage { id, args: Vec::new() };
+        let body = error_body(ErrorCode::Unauthorized, message, Some(&localized), None);
+
+        let mut response =
+            (StatusCode::UNAUTHORIZED, [(WWW_AUTHENTICATE, self.challenge())], Json(body)).into_response();
+        response.extensions_mut().insert(AuthFailure(self.reason()));
+        response.extensions_mut().insert(LocalizedError {
+            code: ErrorCode::Unauthorized,
+            message: localized,
+            details: None,
+        });
+        response
+    }
+}
+
+/// A localizable error response, left in the response extensions for the locale middleware
+#[derive(Debug, Clone)]
+pub struct LocalizedError {
+    pub code: ErrorCode,
+    pub message: LocalizedMessage,
+    pub details: Option<String>,
+}
+
+/// JSON body of error responses
+pub fn error_body(
+    code: ErrorCode,
+    message: &str,
+    localized: Option<&LocalizedMessage>,
+    details: Option<&str>,
+) -> serde_json::Value {
+    json!({
+        "error": {
+            "code": code.as_str(),
+            "message_id": localized.map(|localized| localized.id),
+            "message": message,
+            "details": details
+        }
+    })
 }
 
 // Helper macro for creating errors
 #[macro_export]
 macro_rules! app_error {
     ($code:expr, $msg:expr) => {
-        crate::errors::AppError::new($code, $msg)
+        $crate::errors::AppError::new($code, $msg)
     };
     ($code:expr, $msg:expr, $details:expr) => {
-        crate::errors::AppError::new($code, $msg).with_details($details)
+        $crate::errors::AppError::new($code, $msg).with_details($details)
     };
 }
 
@@ -0,0 +1,27 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use std::sync::Arc;
+
+use crate::errors::{AppError, ErrorCode};
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+
+/// DELETE /api/keys/{id} - Delete an API key; other instances stop accepting it within `API_KEY_CACHE_SECONDS`
+pub async fn delete_api_key(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    if app_state.api_keys.delete(&tenant, &id).await? {
+        tracing::info!("Admin '{}' deleted API key {}", auth.username, id);
+        Ok(StatusCode::NO_CONTENT)
+    } else {
+        Err(AppError::new(ErrorCode::UserNotFound, format!("No API key '{}'", id))
+            .localized("api-key-not-found", [("id", id.into())]))
+    }
+}
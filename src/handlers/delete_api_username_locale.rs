@@ -0,0 +1,58 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use std::sync::Arc;
+
+use crate::errors::{AppError, ErrorCode};
+use crate::middleware::AuthenticatedUser;
+use crate::profile::validate_locale;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::ValidatedUsername;
+
+/// DELETE /api/username/locales/{locale} - Remove the display name for one language
+pub async fn delete_api_username_locale(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Path(locale): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let validated_username = ValidatedUsername::existing(auth.username.clone())?;
+    let locale = validate_locale(locale.trim())?;
+
+    match app_state
+        .database
+        .delete_localized_display_name(&tenant, validated_username.as_str(), &locale)
+        .await
+    {
+        Ok(true) => {
+            tracing::info!("Removed '{}' display name for '{}'", locale, validated_username);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(AppError::new(
+            ErrorCode::UserNotFound,
+            format!("No '{}' display name for '{}'", locale, validated_username),
+        )
+        .localized(
+            "localized-name-not-found",
+            [
+                ("locale", locale.as_str().into()),
+                ("username", validated_username.to_string().into()),
+            ],
+        )),
+        Err(e) => {
+            tracing::error!(
+                "Database error removing '{}' display name for '{}': {}",
+                locale,
+                validated_username,
+                e
+            );
+            Err(AppError::database_error(format!(
+                "Failed to remove localized display name: {}",
+                e
+            )))
+        }
+    }
+}
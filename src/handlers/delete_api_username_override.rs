@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+};
+use std::sync::Arc;
+
+use crate::errors::{AppError, ErrorCode};
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::{ValidatedAppKey, ValidatedUsername};
+
+/// DELETE /api/username/overrides/{app} - Go back to the default display name for one application
+pub async fn delete_api_username_override(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Path(app): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let validated_username = ValidatedUsername::existing(auth.username.clone())?;
+    let app = ValidatedAppKey::new(&app)?;
+
+    match app_state
+        .database
+        .delete_display_name_override(&tenant, validated_username.as_str(), app.as_str())
+        .await
+    {
+        Ok(true) => {
+            tracing::info!("Removed display name for '{}' in app '{}'", validated_username, app);
+            Ok(StatusCode::NO_CONTENT)
+        }
+        Ok(false) => Err(AppError::new(
+            ErrorCode::UserNotFound,
+            format!("No display name override for '{}' in '{}'", validated_username, app),
+        )
+        .localized(
+            "override-not-found",
+            [
+                ("username", validated_username.to_string().into()),
+                ("app", app.to_string().into()),
+            ],
+        )),
+        Err(e) => {
+            tracing::error!(
+                "Database error removing display name for '{}' in '{}': {}",
+                validated_username,
+                app,
+                e
+            );
+            Err(AppError::database_error(format!(
+                "Failed to remove display name override: {}",
+                e
+            )))
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use serde::Deserialize;
+use std::fmt;
+
+use crate::database::User;
+use crate::errors::AppError;
+use crate::handlers::post_api_username::validate_new_display_name;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::{ValidatedAppKey, ValidatedUsername};
+
+#[derive(Debug, Deserialize)]
+pub struct DisplayNameVariantRequest {
+    pub display_name: String,
+}
+
+/// What a display name variant is shown for
+#[derive(Debug, Clone, Copy)]
+pub enum VariantKey<'a> {
+    /// One application (`PUT /api/username/overrides/{app}`)
+    App(&'a ValidatedAppKey),
+    /// Readers of one language (`PUT /api/username/locales/{locale}`)
+    Locale(&'a str),
+}
+
+impl VariantKey<'_> {
+    /// Catalog id and English text of the error refusing variants while moderation is enabled
+    fn moderation_error(&self) -> (&'static str, &'static str) {
+        match self {
+            VariantKey::App(_) => (
+                "overrides-unavailable",
+                "Display name overrides are unavailable while display name moderation is enabled",
+            ),
+            VariantKey::Locale(_) => (
+                "localized-names-unavailable",
+                "Localized display names are unavailable while display name moderation is enabled",
+            ),
+        }
+    }
+
+    /// Catalog id and plural noun of the error refusing variants over the limit
+    fn limit_error(&self) -> (&'static str, &'static str) {
+        match self {
+            VariantKey::App(_) => ("too-many-overrides", "display name overrides"),
+            VariantKey::Locale(_) => ("too-many-localized-names", "localized display names"),
+        }
+    }
+}
+
+impl fmt::Display for VariantKey<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariantKey::App(app) => write!(f, "app '{app}'"),
+            VariantKey::Locale(locale) => write!(f, "locale '{locale}'"),
+        }
+    }
+}
+
+/// Validate and store a user's display name variant, keeping at most `max` variants of the same kind.
+///
+/// Returns the updated user.
+pub(crate) async fn set_display_name_variant(
+    app_state: &AppState,
+    tenant: &Tenant,
+    username: &ValidatedUsername,
+    key: VariantKey<'_>,
+    display_name: &str,
+    max: usize,
+) -> Result<User, AppError> {
+    // Variants are published immediately, which would bypass the review queue
+    if app_state.moderation.enabled {
+        let (id, message) = key.moderation_error();
+        return Err(AppError::forbidden(message).localized(id, []));
+    }
+
+    let validated_display_name = validate_new_display_name(app_state, tenant, username, display_name).await?;
+
+    match app_state.database.get_user(tenant, username.as_str()).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(AppError::user_not_found(username.as_str())),
+        Err(e) => return Err(AppError::database_error(format!("Failed to get user: {}", e))),
+    }
+
+    // The limit is checked in the same transaction as the write, so concurrent requests cannot exceed it
+    let stored = match key {
+        VariantKey::App(app) => {
+            app_state
+                .database
+                .set_display_name_override(
+                    tenant,
+                    username.as_str(),
+                    app.as_str(),
+                    validated_display_name.as_str(),
+                    max,
+                )
+                .await
+        }
+        VariantKey::Locale(locale) => {
+            app_state
+                .database
+                .set_localized_display_name(tenant, username.as_str(), locale, validated_display_name.as_str(), max)
+                .await
+        }
+    };
+
+    match stored {
+        Ok(true) => {}
+        Ok(false) => {
+            let (id, noun) = key.limit_error();
+            return Err(AppError::validation_failed(format!("At most {} {} are allowed", max, noun))
+                .localized(id, [("max", max.into())]));
+        }
+        Err(e) => {
+            tracing::error!("Database error setting {} display name for '{}': {}", key, username, e);
+            return Err(AppError::database_error(format!("Failed to update display name: {}", e)));
+        }
+    }
+
+    tracing::info!("Set {} display name for '{}' to '{}'", key, username, validated_display_name);
+
+    match app_state.database.get_user(tenant, username.as_str()).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(AppError::user_not_found(username.as_str())),
+        Err(e) => Err(AppError::database_error(format!("Failed to get user: {}", e))),
+    }
+}
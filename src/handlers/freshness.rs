@@ -0,0 +1,130 @@
+use axum::http::{header, HeaderMap, HeaderValue};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::i18n::{Catalog, Locale};
+
+/// Format a timestamp as an HTTP date (`Sun, 06 Nov 1994 08:49:37 GMT`)
+pub fn http_date(timestamp: DateTime<Utc>) -> String {
+    timestamp.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// `Last-Modified` header value for a resource last changed at `updated_at`
+pub fn last_modified(updated_at: DateTime<Utc>) -> (header::HeaderName, HeaderValue) {
+    let value = HeaderValue::from_str(&http_date(updated_at)).expect("HTTP dates are valid header values");
+    (header::LAST_MODIFIED, value)
+}
+
+/// Whether the request's `If-Modified-Since` shows the client already has this version.
+///
+/// HTTP dates only have second precision, so the comparison ignores sub-second changes.
+pub fn is_not_modified(headers: &HeaderMap, updated_at: DateTime<Utc>) -> bool {
+    let Some(since) = headers
+        .get(header::IF_MODIFIED_SINCE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+    else {
+        return false;
+    };
+
+    updated_at.timestamp() <= since.timestamp()
+}
+
+/// Whether the request's `If-None-Match` lists the current `etag` (or `*`)
+pub fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim().trim_start_matches("W/").trim_matches('"'))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+/// Human-readable age of a timestamp in the reader's language, e.g. `3 days ago`
+pub fn time_ago(catalog: &Catalog, locale: &Locale, timestamp: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(timestamp);
+
+    let (count, unit) = if elapsed < Duration::minutes(1) {
+        (0, "now")
+    } else if elapsed < Duration::hours(1) {
+        (elapsed.num_minutes(), "minute")
+    } else if elapsed < Duration::days(1) {
+        (elapsed.num_hours(), "hour")
+    } else if elapsed < Duration::days(30) {
+        (elapsed.num_days(), "day")
+    } else if elapsed < Duration::days(365) {
+        (elapsed.num_days() / 30, "month")
+    } else {
+        (elapsed.num_days() / 365, "year")
+    };
+
+    catalog.message(locale, "time-ago", &[("unit", unit.into()), ("count", count.into())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{DEFAULT_LOCALE, DEFAULT_LOCALES_DIR};
+    use chrono::TimeZone;
+    use std::path::Path;
+
+    #[test]
+    fn test_http_date() {
+        let timestamp = Utc.with_ymd_and_hms(1994, 11, 6, 8, 49, 37).unwrap();
+        assert_eq!(http_date(timestamp), "Sun, 06 Nov 1994 08:49:37 GMT");
+    }
+
+    #[test]
+    fn test_is_not_modified() {
+        let updated_at = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap() + Duration::milliseconds(500);
+        let mut headers = HeaderMap::new();
+        assert!(!is_not_modified(&headers, updated_at));
+
+        headers.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Mon, 01 Jan 2024 12:00:00 GMT"),
+        );
+        assert!(is_not_modified(&headers, updated_at));
+
+        headers.insert(
+            header::IF_MODIFIED_SINCE,
+            HeaderValue::from_static("Mon, 01 Jan 2024 11:59:59 GMT"),
+        );
+        assert!(!is_not_modified(&headers, updated_at));
+
+        headers.insert(header::IF_MODIFIED_SINCE, HeaderValue::from_static("yesterday"));
+        assert!(!is_not_modified(&headers, updated_at));
+    }
+
+    #[test]
+    fn test_etag_matches() {
+        let mut headers = HeaderMap::new();
+        assert!(!etag_matches(&headers, "abc"));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static(r#""xyz", W/"abc""#));
+        assert!(etag_matches(&headers, "abc"));
+        assert!(!etag_matches(&headers, "ab"));
+
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static("*"));
+        assert!(etag_matches(&headers, "anything"));
+    }
+
+    #[test]
+    fn test_time_ago() {
+        let now = Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+        let catalog = Catalog::load(Path::new(DEFAULT_LOCALES_DIR), DEFAULT_LOCALE).unwrap();
+        let time_ago = |timestamp, locale: &Locale| super::time_ago(&catalog, locale, timestamp, now);
+        let english = Locale::default();
+
+        assert_eq!(time_ago(now - Duration::seconds(30), &english), "just now");
+        assert_eq!(time_ago(now - Duration::minutes(1), &english), "1 minute ago");
+        assert_eq!(time_ago(now - Duration::hours(5), &english), "5 hours ago");
+        assert_eq!(time_ago(now - Duration::days(3), &english), "3 days ago");
+        assert_eq!(time_ago(now - Duration::days(65), &english), "2 months ago");
+        assert_eq!(time_ago(now - Duration::days(800), &english), "2 years ago");
+
+        let french = catalog.negotiate(&["fr".to_string()]);
+        assert_eq!(time_ago(now - Duration::seconds(30), &french), "à l’instant");
+        assert_eq!(time_ago(now - Duration::days(3), &french), "il y a 3 jours");
+    }
+}
@@ -0,0 +1,22 @@
+use axum::{extract::State, response::Json};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::database::ApiKey;
+use crate::errors::AppError;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeysResponse {
+    pub keys: Vec<ApiKey>,
+}
+
+/// GET /api/keys - The tenant's API keys, oldest first, without the keys themselves
+pub async fn get_api_keys(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+) -> Result<Json<ApiKeysResponse>, AppError> {
+    let keys = app_state.api_keys.list(&tenant).await?;
+    Ok(Json(ApiKeysResponse { keys }))
+}
@@ -0,0 +1,34 @@
+use axum::{extract::State, response::Json, Extension};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::database::PendingDisplayName;
+use crate::errors::AppError;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+
+#[derive(Debug, Serialize)]
+pub struct PendingDisplayNamesResponse {
+    pub pending: Vec<PendingDisplayName>,
+}
+
+/// GET /api/moderation/pending - Display name changes awaiting review, oldest first
+pub async fn get_api_moderation_pending(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+) -> Result<Json<PendingDisplayNamesResponse>, AppError> {
+    app_state.authorization.require_moderator(&auth)?;
+
+    match app_state.database.list_pending_display_names(&tenant).await {
+        Ok(pending) => {
+            tracing::info!("Moderator '{}' listed {} pending display names", auth.username, pending.len());
+            Ok(Json(PendingDisplayNamesResponse { pending }))
+        }
+        Err(e) => {
+            tracing::error!("Database error listing pending display names: {}", e);
+            Err(AppError::database_error(format!("Failed to list pending display names: {}", e)))
+        }
+    }
+}
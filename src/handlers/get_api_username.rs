@@ -1,33 +1,116 @@
 use axum::{
-    extract::{Path, State},
-    response::Json,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
-use serde::Serialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+use crate::database::{User, UserProfile};
 use crate::errors::AppError;
+use crate::handlers::freshness::{is_not_modified, last_modified};
+use crate::i18n::RequestedLanguages;
 use crate::router::AppState;
-use crate::validation::ValidatedUsername;
+use crate::tenant::Tenant;
+use crate::validation::{ValidatedAppKey, ValidatedUsername};
+
+/// `?app=` selects the application whose display name override should be used
+#[derive(Debug, Default, Deserialize)]
+pub struct AppQuery {
+    pub app: Option<String>,
+}
+
+impl AppQuery {
+    /// The requested application, if any; an empty `?app=` means the default display name
+    pub fn validated(&self) -> Result<Option<ValidatedAppKey>, AppError> {
+        match self.app.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(app) => ValidatedAppKey::new(app).map(Some),
+        }
+    }
+}
 
 #[derive(Debug, Serialize)]
 pub struct UsernameResponse {
     pub username: String,
     pub display_name: String,
+    /// Application whose display name override was used; absent when `display_name` is the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    /// Language tag of the localized variant used for `display_name`, chosen from the reader's requested languages
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name_locale: Option<String>,
+    /// Display name awaiting moderation, when it differs from the published one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending_display_name: Option<String>,
+    #[serde(flatten)]
+    pub profile: UserProfile,
+    /// Absent only for a user whose first display name is still awaiting moderation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<User> for UsernameResponse {
+    fn from(user: User) -> Self {
+        Self {
+            username: user.username,
+            display_name: user.display_name,
+            app: None,
+            display_name_locale: None,
+            pending_display_name: None,
+            profile: user.profile,
+            created_at: Some(user.created_at),
+            updated_at: Some(user.updated_at),
+        }
+    }
+}
+
+impl UsernameResponse {
+    /// Response as seen by a reader in `app` who prefers `languages`, see [`User::display_name_for`]
+    pub fn for_reader(user: User, app: Option<&ValidatedAppKey>, languages: &[String]) -> Self {
+        let resolved = user.display_name_for(app.map(ValidatedAppKey::as_str), languages);
+        let display_name = resolved.display_name.to_string();
+        let app = resolved.app.map(str::to_string);
+        let display_name_locale = resolved.locale.map(str::to_string);
+
+        Self {
+            display_name,
+            app,
+            display_name_locale,
+            ..Self::from(user)
+        }
+    }
 }
 
 pub async fn get_api_username(
     State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    RequestedLanguages(languages): RequestedLanguages,
     Path(username): Path<String>,
-) -> Result<Json<UsernameResponse>, AppError> {
-    let validated_username = ValidatedUsername::new(username)?;
+    Query(query): Query<AppQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let validated_username = ValidatedUsername::existing(username)?;
+    let app = query.validated()?;
 
-    match app_state.database.get_user(validated_username.as_str()).await {
+    match app_state.database.get_user(&tenant, validated_username.as_str()).await {
         Ok(Some(user)) => {
+            let last_modified = last_modified(user.updated_at);
+
+            if is_not_modified(&headers, user.updated_at) {
+                tracing::debug!("User data for '{}' not modified", validated_username);
+                return Ok((StatusCode::NOT_MODIFIED, [last_modified]).into_response());
+            }
+
             tracing::info!("Retrieved user data for '{}'", validated_username);
-            Ok(Json(UsernameResponse {
-                username: user.username,
-                display_name: user.display_name,
-            }))
+            Ok((
+                [last_modified],
+                Json(UsernameResponse::for_reader(user, app.as_ref(), &languages)),
+            )
+                .into_response())
         }
         Ok(None) => {
             tracing::info!("User '{}' not found", validated_username);
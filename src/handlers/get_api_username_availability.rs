@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use chrono::Datelike;
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::errors::AppError;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::{
+    canonical_username, impersonation_candidate, is_reserved_username, resembles_reserved_username, validate_username,
+};
+
+/// Number of alternative handles suggested when a username cannot be claimed
+const SUGGESTION_COUNT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AvailabilityStatus {
+    Available,
+    Invalid,
+    Reserved,
+    Taken,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AvailabilityResponse {
+    pub username: String,
+    pub available: bool,
+    pub status: AvailabilityStatus,
+    pub reason: Option<String>,
+    pub suggestions: Vec<String>,
+}
+
+/// GET /api/username/{username}/availability - Report whether a handle is valid, reserved or taken
+pub async fn get_api_username_availability(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Path(username): Path<String>,
+) -> Result<Json<AvailabilityResponse>, AppError> {
+    let (status, reason) = match validate_username(&username) {
+        Err(e) => (AvailabilityStatus::Invalid, Some(e.message)),
+        Ok(()) => match username_status(&app_state, &tenant, &canonical_username(&username)).await? {
+            AvailabilityStatus::Reserved => (AvailabilityStatus::Reserved, Some("Username is reserved".to_string())),
+            AvailabilityStatus::Taken => (AvailabilityStatus::Taken, Some("Username is already taken".to_string())),
+            status => (status, None),
+        },
+    };
+
+    let suggestions = if status == AvailabilityStatus::Available {
+        Vec::new()
+    } else {
+        suggest_usernames(&app_state, &tenant, &username).await?
+    };
+
+    tracing::info!("Availability check for '{}': {:?}", username, status);
+
+    Ok(Json(AvailabilityResponse {
+        username: if status == AvailabilityStatus::Invalid {
+            username
+        } else {
+            canonical_username(&username)
+        },
+        available: status == AvailabilityStatus::Available,
+        status,
+        reason,
+        suggestions,
+    }))
+}
+
+/// Status of a syntactically valid, canonical username
+async fn username_status(
+    app_state: &AppState,
+    tenant: &Tenant,
+    username: &str,
+) -> Result<AvailabilityStatus, AppError> {
+    if is_reserved_username(username) {
+        return Ok(AvailabilityStatus::Reserved);
+    }
+
+    match app_state.database.get_user(tenant, username).await {
+        Ok(Some(_)) => Ok(AvailabilityStatus::Taken),
+        Ok(None) => Ok(AvailabilityStatus::Available),
+        Err(e) => Err(AppError::database_error(format!("Failed to get user: {}", e))),
+    }
+}
+
+/// Generate a few available handles derived from the requested one
+///
+/// Nothing is suggested for a name resembling a reserved one, or one whose lookalike
+/// characters spell it, so the service never proposes handles such as `admin1` or `the_admin`.
+async fn suggest_usernames(app_state: &AppState, tenant: &Tenant, username: &str) -> Result<Vec<String>, AppError> {
+    let base: String = canonical_username(username)
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .take(40)
+        .collect();
+
+    let impersonates_reserved =
+        impersonation_candidate(username).is_some_and(|candidate| resembles_reserved_username(&candidate));
+    if base.is_empty() || impersonates_reserved || resembles_reserved_username(&base) {
+        return Ok(Vec::new());
+    }
+
+    let year = chrono::Utc::now().year() % 100;
+    let candidates: Vec<String> = [
+        format!("{base}1"),
+        format!("{base}_"),
+        format!("the_{base}"),
+        format!("{base}2"),
+        format!("{base}_{year}"),
+        format!("{base}3"),
+        format!("{base}123"),
+    ]
+    .into_iter()
+    .filter(|candidate| validate_username(candidate).is_ok() && !resembles_reserved_username(candidate))
+    .collect();
+
+    let taken = app_state
+        .database
+        .existing_usernames(tenant, &candidates)
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to look up usernames: {}", e)))?;
+
+    Ok(candidates
+        .into_iter()
+        .filter(|candidate| !taken.contains(candidate))
+        .take(SUGGESTION_COUNT)
+        .collect())
+}
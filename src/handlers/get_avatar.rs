@@ -0,0 +1,101 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::avatar::identicon::{identicon_etag, identicon_svg};
+use crate::errors::{AppError, ErrorCode};
+use crate::handlers::freshness::etag_matches;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::ValidatedUsername;
+
+/// Versioned URLs (`?v={etag}`) never change content, so they can be cached for a year
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Unversioned URLs must pick up a new avatar reasonably quickly
+const REVALIDATE_CACHE_CONTROL: &str = "public, max-age=300, must-revalidate";
+
+#[derive(Debug, Deserialize)]
+pub struct AvatarQuery {
+    pub v: Option<String>,
+}
+
+/// GET /avatar/{username} - Serve a user's avatar image
+///
+/// GET /avatar/{username}.svg is served here as well, since the router cannot match a
+/// parameter followed by a suffix.
+pub async fn get_avatar(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Path(username): Path<String>,
+    Query(query): Query<AvatarQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    if let Some(username) = username.strip_suffix(".svg") {
+        return get_identicon(username, &headers);
+    }
+
+    let validated_username = ValidatedUsername::existing(username)?;
+
+    let Some(avatar) = app_state.avatars.get(&tenant, validated_username.as_str()).await? else {
+        return Err(
+            AppError::new(ErrorCode::UserNotFound, format!("No avatar for '{}'", validated_username))
+                .localized("avatar-not-found", [("username", validated_username.to_string().into())]),
+        );
+    };
+
+    let cache_control = if query.v.as_deref() == Some(avatar.etag.as_str()) {
+        IMMUTABLE_CACHE_CONTROL
+    } else {
+        REVALIDATE_CACHE_CONTROL
+    };
+
+    let etag = HeaderValue::from_str(&format!("\"{}\"", avatar.etag))
+        .map_err(|e| AppError::internal_server_error(format!("Invalid avatar ETag: {}", e)))?;
+    let cache_headers = [
+        (header::ETAG, etag),
+        (header::CACHE_CONTROL, HeaderValue::from_static(cache_control)),
+    ];
+
+    if etag_matches(&headers, &avatar.etag) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    let content_type = HeaderValue::from_str(&avatar.content_type)
+        .map_err(|e| AppError::internal_server_error(format!("Invalid avatar content type: {}", e)))?;
+
+    Ok((cache_headers, [(header::CONTENT_TYPE, content_type)], avatar.data).into_response())
+}
+
+/// GET /avatar/{username}.svg - Generated identicon, available whether or not the user has an avatar
+///
+/// The image depends only on the canonical username, so it never needs a database lookup
+/// and can be cached forever.
+fn get_identicon(username: &str, headers: &HeaderMap) -> Result<Response, AppError> {
+    let validated_username = ValidatedUsername::existing(username.to_string())?;
+
+    let svg = identicon_svg(validated_username.as_str());
+    let etag = identicon_etag(&svg);
+    let cache_headers = [
+        (
+            header::ETAG,
+            HeaderValue::from_str(&format!("\"{}\"", etag)).expect("hex ETag is a valid header"),
+        ),
+        (header::CACHE_CONTROL, HeaderValue::from_static(IMMUTABLE_CACHE_CONTROL)),
+    ];
+
+    if etag_matches(headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    Ok((
+        cache_headers,
+        [(header::CONTENT_TYPE, HeaderValue::from_static("image/svg+xml"))],
+        svg,
+    )
+        .into_response())
+}
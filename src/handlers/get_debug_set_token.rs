@@ -13,9 +13,9 @@ use tracing::info;
 use crate::middleware::jwt_auth::Claims;
 use crate::router::AppState;
 
-/// GET /debug/set-token/{username} - Debug utility to set JWT token in browser
+/// GET /debug/set-token/{username} - Debug utility to set the session cookie in the browser
 pub async fn get_debug_set_token(
-    State(_app_state): State<Arc<AppState>>,
+    State(app_state): State<Arc<AppState>>,
     Path(username): Path<String>,
     Query(params): Query<HashMap<String, String>>,
 ) -> Result<Response, StatusCode> {
@@ -36,7 +36,7 @@ pub async fn get_debug_set_token(
         (token, exp)
     } else {
         info!("No token provided, generating new debug JWT");
-        let (new_token, exp) = generate_debug_jwt(&username)?;
+        let (new_token, exp) = generate_debug_jwt(&username, params.get("tenant").map(String::as_str))?;
         (new_token, exp)
     };
 
@@ -117,7 +117,7 @@ pub async fn get_debug_set_token(
         <div class="success">
             ✅ JWT token has been set for user: <strong>{username}</strong>
         </div>
-        <p>The JWT token has been stored in your browser cookies and is ready to use.</p>
+        <p>The JWT token has been stored in an HttpOnly session cookie and is ready to use.</p>
         <div>
             <a href="/edit" class="btn">Test Protected Page (/edit)</a>
             <a href="/api/username" class="btn">Test API Endpoint</a>
@@ -131,11 +131,10 @@ pub async fn get_debug_set_token(
         token_len = token.len(),
         expiration_text = expiration_text
     ); // Build response with a single Set-Cookie header and HTML content
-       // Include Max-Age to ensure the cookie expires when the token expires
-       // Use HttpOnly=false to allow JavaScript access
-    let cookie = format!("jwt_token={}; Path=/; SameSite=Lax; Max-Age={}; HttpOnly=false", token, max_age);
+       // The session cookie expires when the token expires, like one set by POST /auth/session
+    let cookie = app_state.session.session_cookie(&token, max_age as u64);
 
-    info!("Setting JWT cookie for user: {} with Max-Age={}", username, max_age);
+    info!("Setting session cookie for user: {} with Max-Age={}", username, max_age);
 
     let response = Response::builder()
         .status(StatusCode::OK)
@@ -147,7 +146,7 @@ pub async fn get_debug_set_token(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    info!("Set jwt_token cookie with proper expiration");
+    info!("Set session cookie with proper expiration");
 
     Ok(response)
 }
@@ -186,7 +185,8 @@ fn extract_token_expiration(token: &str) -> Result<usize, StatusCode> {
     Ok(exp)
 }
 
-fn generate_debug_jwt(username: &str) -> Result<(String, usize), StatusCode> {
+/// Sign a one-hour token for `username`; `tenant` fills the claim named by `TENANT_CLAIM`
+fn generate_debug_jwt(username: &str, tenant: Option<&str>) -> Result<(String, usize), StatusCode> {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     // Read private key from environment
@@ -209,8 +209,15 @@ fn generate_debug_jwt(username: &str) -> Result<(String, usize), StatusCode> {
         sub: username.to_string(),
         iat: now,
         exp: expiration, // 1 hour expiry
-        aud: "micro-frontend-service".to_string(),
+        aud: "micro-frontend-service".into(),
         iss: "test-auth-service".to_string(),
+        jti: Some(uuid::Uuid::new_v4().to_string()),
+        extra: std::env::var("TENANT_CLAIM")
+            .ok()
+            .zip(tenant)
+            .map(|(claim, tenant)| (claim, serde_json::Value::from(tenant)))
+            .into_iter()
+            .collect(),
     };
 
     let header = Header::new(Algorithm::RS256);
@@ -1,58 +1,49 @@
 use anyhow::Result;
-use axum::{extract::Path, http::StatusCode, response::Html};
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
-use std::env;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Html,
+};
+use std::collections::HashSet;
+use std::sync::Arc;
 
-use crate::middleware::jwt_auth::Claims;
+use crate::router::AppState;
 
-pub async fn get_debug_validate_token(Path(token): Path<String>) -> Result<Html<String>, StatusCode> {
-    // Read JWT configuration from environment
-    let mut public_key_pem = env::var("JWT_PUBLIC_KEY").map_err(|_| {
-        tracing::error!("JWT_PUBLIC_KEY environment variable not set");
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+pub async fn get_debug_validate_token(
+    State(app_state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Html<String>, StatusCode> {
+    // Verify with the same configuration as the authentication middleware
+    let jwt_config = app_state.jwt.config();
 
-    // Remove surrounding single or double quotes if present
-    if (public_key_pem.starts_with('"') && public_key_pem.ends_with('"'))
-        || (public_key_pem.starts_with('\'') && public_key_pem.ends_with('\''))
-    {
-        public_key_pem = public_key_pem[1..public_key_pem.len() - 1].to_string();
-    }
-
-    // Handle escaped newlines in the public key (for .env with \n)
-    if public_key_pem.contains("\\n") {
-        public_key_pem = public_key_pem.replace("\\n", "\n");
-    }
-
-    tracing::debug!("Public key PEM loaded ({} chars): {}", public_key_pem.len(), public_key_pem);
-
-    let algorithm = env::var("JWT_ALGORITHM").unwrap_or_else(|_| "RS256".to_string());
-    let audience = env::var("JWT_AUDIENCE").unwrap_or_else(|_| "micro-frontend-service".to_string());
-    let issuer = env::var("JWT_ISSUER").unwrap_or_else(|_| "test-auth-service".to_string());
-
-    // Set up JWT validation
-    let public_key = DecodingKey::from_rsa_pem(public_key_pem.as_bytes()).map_err(|e| {
-        tracing::error!("Failed to parse JWT public key: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
-
-    let mut validation = Validation::new(match algorithm.as_str() {
-        "RS256" => Algorithm::RS256,
-        "ES256" => Algorithm::ES256,
-        _ => {
-            tracing::error!("Unsupported JWT algorithm: {}", algorithm);
-            return Err(StatusCode::INTERNAL_SERVER_ERROR);
-        }
-    });
-
-    validation.set_audience(&[audience.clone()]);
-    validation.set_issuer(&[issuer.clone()]);
-    validation.leeway = 60; // 1 minute clock skew
+    let expected = |values: &Option<HashSet<String>>| {
+        let mut values: Vec<_> = values.iter().flatten().map(String::as_str).collect();
+        values.sort_unstable();
+        values.join(", ")
+    };
+    let algorithm = jwt_config
+        .issuers
+        .iter()
+        .map(|issuer| format!("{:?}", issuer.validation.algorithms))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let audience = jwt_config
+        .issuers
+        .iter()
+        .map(|issuer| format!("{} ({})", expected(&issuer.validation.aud), issuer.issuer))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let issuer = jwt_config
+        .issuers
+        .iter()
+        .map(|issuer| issuer.issuer.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
 
-    tracing::debug!("Token validation configuration: {:?}", validation);
+    tracing::debug!("Token validation configuration: {:?}", jwt_config);
 
     // Try to validate the token
-    let validation_result = decode::<Claims>(&token, &public_key, &validation);
+    let validation_result = jwt_config.decode(&token).await;
 
     tracing::debug!("Validation result: {:?}", validation_result);
 
@@ -100,7 +91,7 @@ Token algorithm: {header_alg}</pre>
                 issuer = claims.iss,
                 token = token,
                 header_type = token_data.header.typ.unwrap_or_default(),
-                header_alg = format!("{:?}", token_data.header.alg)
+                header_alg = format_args!("{:?}", token_data.header.alg)
             )
         }
         Err(err) => {
@@ -147,7 +138,7 @@ Token algorithm: {header_alg}</pre>
                 audience = audience,
                 issuer = issuer,
                 token = token,
-                header = token.split('.').nth(0).unwrap_or("invalid"),
+                header = token.split('.').next().unwrap_or("invalid"),
                 payload = token.split('.').nth(1).unwrap_or("invalid"),
                 signature = token.split('.').nth(2).unwrap_or("invalid")
             )
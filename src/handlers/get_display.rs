@@ -1,83 +1,139 @@
 use axum::{
-    extract::{Path, State},
-    response::Html,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
 };
 use minijinja::context;
 use std::sync::Arc;
 use tracing::info;
 
+use crate::avatar::avatar_url;
+use crate::avatar::identicon::identicon_svg;
 use crate::errors::AppError;
+use crate::handlers::freshness::{is_not_modified, last_modified, time_ago};
+use crate::handlers::get_api_username::AppQuery;
+use crate::i18n::{Locale, RequestedLanguages};
 use crate::router::AppState;
-use crate::validation::ValidatedUsername;
+use crate::tenant::Tenant;
+use crate::validation::{ValidatedAppKey, ValidatedUsername};
 
 /// GET /display/username/{username} - Display component shows username and display name
 pub async fn get_display_username(
     State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    locale: Locale,
+    RequestedLanguages(languages): RequestedLanguages,
     Path(username): Path<String>,
-) -> Result<Html<String>, AppError> {
+    Query(query): Query<AppQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
     info!("Display request for username: {}", username);
 
-    // Validate username
-    let validated_username = ValidatedUsername::new(username)?;
+    // Validate username and the optional application context
+    let validated_username = ValidatedUsername::existing(username.clone())?;
+    let app = query.validated()?;
+
+    // Non-canonical spellings (e.g. /display/username/Admin) permanently redirect to the canonical URL
+    if validated_username.as_str() != username {
+        let location = match &app {
+            Some(app) => format!("/display/username/{}?app={}", validated_username, app),
+            None => format!("/display/username/{}", validated_username),
+        };
+        return Ok((StatusCode::MOVED_PERMANENTLY, [(header::LOCATION, location)]).into_response());
+    }
+
+    let catalog = app_state.template_service.catalog();
 
     // Get user data from database
-    let user_data = match app_state.database.get_user(validated_username.as_str()).await {
+    let user_data = match app_state.database.get_user(&tenant, validated_username.as_str()).await {
         Ok(Some(user)) => user,
         Ok(None) => {
             // User not found - still render template but show error
             let html = app_state.template_service.render(
+                &tenant,
                 "display.html",
                 context! {
+                    locale => locale.as_str(),
                     username => validated_username.as_str(),
-                    error => "User not found"
+                    error => catalog.message(&locale, "display-user-not-found", &[])
                 },
             )?;
 
-            return Ok(Html(html));
+            return Ok(Html(html).into_response());
         }
         Err(e) => {
             return Err(AppError::database_error(format!("Failed to get user: {}", e)));
         }
     };
 
+    // A new avatar changes the page too, so it counts towards Last-Modified
+    let avatar = app_state.avatars.get(&tenant, validated_username.as_str()).await?;
+    let updated_at = avatar
+        .as_ref()
+        .map_or(user_data.updated_at, |avatar| avatar.updated_at.max(user_data.updated_at));
+
+    let last_modified = last_modified(updated_at);
+    if is_not_modified(&headers, updated_at) {
+        return Ok((StatusCode::NOT_MODIFIED, [last_modified]).into_response());
+    }
+
+    let resolved = user_data.display_name_for(app.as_ref().map(ValidatedAppKey::as_str), &languages);
+
     // Render the display template
     let html = app_state.template_service.render(
+        &tenant,
         "display.html",
         context! {
+            locale => locale.as_str(),
             username => user_data.username,
-            display_name => user_data.display_name,
-            title => format!("Display - {}", user_data.username),
-            description => format!("View the display name for user {}", user_data.username),
-            keywords => "user, display, profile, username"
+            display_name => resolved.display_name,
+            display_name_lang => resolved.locale,
+            profile => user_data.profile,
+            avatar_url => avatar.as_ref().map(|avatar| avatar_url(&user_data.username, avatar)),
+            // Users without an uploaded avatar get their identicon inlined, styled by the page theme
+            identicon => avatar.is_none().then(|| identicon_svg(&user_data.username)),
+            updated_at => updated_at.to_rfc3339(),
+            updated_ago => time_ago(catalog, &locale, updated_at, chrono::Utc::now()),
+            title => catalog.message(&locale, "display-page-title", &[("username", user_data.username.as_str().into())]),
+            description => catalog.message(&locale, "display-page-description", &[("username", user_data.username.as_str().into())]),
+            keywords => catalog.message(&locale, "display-page-keywords", &[])
         },
     )?;
 
-    Ok(Html(html))
+    Ok(([last_modified], Html(html)).into_response())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::database::mock::MockUserDatabase;
-    use crate::metrics::AppMetrics;
+
+    async fn response_body(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
 
     #[tokio::test]
     async fn test_get_display_username_success() {
         // Set up test dependencies
         let db = Arc::new(MockUserDatabase::new());
-        let template_service = crate::template::TemplateService::new(false, false).unwrap();
-        let app_state = Arc::new(AppState {
-            database: db,
-            template_service,
-            metrics: AppMetrics::new_for_tests(),
-        });
+        let app_state = Arc::new(AppState::for_tests(db));
 
         // Call the handler with admin username
-        let result = get_display_username(State(app_state), Path("admin".to_string())).await;
+        let result = get_display_username(
+            State(app_state),
+            Tenant::default(),
+            Locale::default(),
+            RequestedLanguages::default(),
+            Path("admin".to_string()),
+            Query(AppQuery::default()),
+            HeaderMap::new(),
+        )
+        .await;
 
         // Check that it returns OK and contains the expected content
         assert!(result.is_ok());
-        let html = result.unwrap().0;
+        let html = response_body(result.unwrap()).await;
         assert!(html.contains("Administrator"));
         assert!(html.contains("admin"));
     }
@@ -86,19 +142,23 @@ mod tests {
     async fn test_get_display_username_not_found() {
         // Set up test dependencies
         let db = Arc::new(MockUserDatabase::new());
-        let template_service = crate::template::TemplateService::new(false, false).unwrap();
-        let app_state = Arc::new(AppState {
-            database: db,
-            template_service,
-            metrics: AppMetrics::new_for_tests(),
-        });
+        let app_state = Arc::new(AppState::for_tests(db));
 
         // Call the handler with a non-existent username
-        let result = get_display_username(State(app_state), Path("nonexistent".to_string())).await;
+        let result = get_display_username(
+            State(app_state),
+            Tenant::default(),
+            Locale::default(),
+            RequestedLanguages::default(),
+            Path("nonexistent".to_string()),
+            Query(AppQuery::default()),
+            HeaderMap::new(),
+        )
+        .await;
 
         // Check that it returns OK (we still render the template, but with an error)
         assert!(result.is_ok());
-        let html = result.unwrap().0;
+        let html = response_body(result.unwrap()).await;
         assert!(html.contains("User not found"));
     }
 
@@ -106,19 +166,83 @@ mod tests {
     async fn test_get_display_username_invalid() {
         // Set up test dependencies
         let db = Arc::new(MockUserDatabase::new());
-        let template_service = crate::template::TemplateService::new(false, false).unwrap();
-        let app_state = Arc::new(AppState {
-            database: db,
-            template_service,
-            metrics: AppMetrics::new_for_tests(),
-        });
+        let app_state = Arc::new(AppState::for_tests(db));
 
         // Call the handler with an invalid username
-        let result = get_display_username(State(app_state), Path("a".to_string())).await;
+        let result = get_display_username(
+            State(app_state),
+            Tenant::default(),
+            Locale::default(),
+            RequestedLanguages::default(),
+            Path("a".to_string()),
+            Query(AppQuery::default()),
+            HeaderMap::new(),
+        )
+        .await;
 
         // Check that it returns an error
         assert!(result.is_err());
         let err = result.unwrap_err();
         assert!(err.to_string().contains("Username must be at least"));
     }
+
+    #[tokio::test]
+    async fn test_get_display_username_redirects_to_canonical() {
+        // Set up test dependencies
+        let db = Arc::new(MockUserDatabase::new());
+        let app_state = Arc::new(AppState::for_tests(db));
+
+        // Call the handler with a mixed-case username
+        let response = get_display_username(
+            State(app_state),
+            Tenant::default(),
+            Locale::default(),
+            RequestedLanguages::default(),
+            Path("Admin".to_string()),
+            Query(AppQuery::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(response.headers().get(header::LOCATION).unwrap(), "/display/username/admin");
+    }
+
+    #[tokio::test]
+    async fn test_get_display_username_last_modified() {
+        // Set up test dependencies
+        let db = Arc::new(MockUserDatabase::new());
+        let app_state = Arc::new(AppState::for_tests(db));
+
+        let response = get_display_username(
+            State(app_state.clone()),
+            Tenant::default(),
+            Locale::default(),
+            RequestedLanguages::default(),
+            Path("admin".to_string()),
+            Query(AppQuery::default()),
+            HeaderMap::new(),
+        )
+        .await
+        .unwrap();
+        let last_modified = response.headers().get(header::LAST_MODIFIED).unwrap().clone();
+        assert!(response_body(response).await.contains("Last updated"));
+
+        // A client with the current version gets 304 Not Modified
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MODIFIED_SINCE, last_modified);
+        let response = get_display_username(
+            State(app_state),
+            Tenant::default(),
+            Locale::default(),
+            RequestedLanguages::default(),
+            Path("admin".to_string()),
+            Query(AppQuery::default()),
+            headers,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    }
 }
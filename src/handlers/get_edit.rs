@@ -1,51 +1,169 @@
-use axum::{extract::State, response::Html, Extension};
+use axum::{
+    extract::{Path, State},
+    response::Html,
+    Extension,
+};
 use minijinja::context;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use tracing::info;
 
+use crate::avatar::{avatar_url, identicon_url};
+use crate::database::UserProfile;
 use crate::errors::AppError;
-use crate::middleware::jwt_auth::Claims;
+use crate::i18n::Locale;
+use crate::middleware::{AuthenticatedUser, TokenSource};
 use crate::router::AppState;
-use crate::validation::ValidatedUsername;
+use crate::tenant::Tenant;
+use crate::validation::{ValidatedAppKey, ValidatedUsername};
 
 /// GET /edit - CMS component for editing display names
 pub async fn get_edit(
     State(app_state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
+    tenant: Tenant,
+    locale: Locale,
+    Extension(auth): Extension<AuthenticatedUser>,
+    source: Option<Extension<TokenSource>>,
 ) -> Result<Html<String>, AppError> {
-    let username = &claims.sub;
+    let username = &auth.username;
     info!("CMS request for username: {}", username);
 
     // Validate username from JWT token
-    let validated_username = ValidatedUsername::new(username.clone())?;
-
-    // Get current user data to pre-populate form
-    let current_display_name = match app_state.database.get_user(validated_username.as_str()).await {
-        Ok(Some(user)) => {
-            // If user has a display name, use it; otherwise fall back to username
-            if user.display_name.is_empty() {
-                user.username
-            } else {
-                user.display_name
-            }
-        }
-        Ok(None) => {
-            // If user doesn't exist in database yet, use username as default
-            validated_username.as_str().to_string()
-        }
-        Err(e) => {
-            return Err(AppError::database_error(format!("Failed to get user: {}", e)));
-        }
+    let validated_username = ValidatedUsername::existing(username.clone())?;
+
+    // The token's audience is the app the user most likely wants to customize
+    let default_app = auth.audience.iter().find_map(|audience| ValidatedAppKey::new(audience).ok());
+
+    let page = EditPage {
+        suggested_display_name: auth.display_name.as_deref(),
+        default_app: default_app.as_ref(),
+        form_action: "/api/username".to_string(),
+        editing_other_user: false,
+        csrf_token: csrf_token(source),
+    };
+    render_edit_page(&app_state, &tenant, &locale, &validated_username, page).await
+}
+
+/// GET /edit/{username} - an admin edits another user of the tenant
+pub async fn get_edit_for_user(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    locale: Locale,
+    Path(username): Path<String>,
+    Extension(auth): Extension<AuthenticatedUser>,
+    source: Option<Extension<TokenSource>>,
+) -> Result<Html<String>, AppError> {
+    let validated_username = ValidatedUsername::existing(username)?;
+    info!("Admin '{}' editing username: {}", auth.username, validated_username);
+
+    let user = app_state
+        .database
+        .get_user(&tenant, validated_username.as_str())
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to get user: {}", e)))?;
+    if user.is_none() {
+        return Err(AppError::user_not_found(validated_username.into_string()));
+    }
+
+    let page = EditPage {
+        suggested_display_name: None,
+        default_app: None,
+        form_action: format!("/api/username/{}", validated_username),
+        editing_other_user: true,
+        csrf_token: csrf_token(source),
+    };
+    render_edit_page(&app_state, &tenant, &locale, &validated_username, page).await
+}
+
+/// How the edit page differs between users editing themselves and admins editing them
+struct EditPage<'a> {
+    /// Display name offered when the user has none yet
+    suggested_display_name: Option<&'a str>,
+    default_app: Option<&'a ValidatedAppKey>,
+    /// Endpoint the display name form posts to
+    form_action: String,
+    /// Hides the sections that only act on the signed-in user (avatar and variants)
+    editing_other_user: bool,
+    /// Sent with the page's writes when it was loaded with a session cookie
+    csrf_token: Option<String>,
+}
+
+fn csrf_token(source: Option<Extension<TokenSource>>) -> Option<String> {
+    match source {
+        Some(Extension(TokenSource::Cookie { csrf_token })) => Some(csrf_token),
+        _ => None,
+    }
+}
+
+async fn render_edit_page(
+    app_state: &AppState,
+    tenant: &Tenant,
+    locale: &Locale,
+    validated_username: &ValidatedUsername,
+    page: EditPage<'_>,
+) -> Result<Html<String>, AppError> {
+    // Get current user data to pre-populate form; a user not in the database yet starts from defaults
+    let user = app_state
+        .database
+        .get_user(tenant, validated_username.as_str())
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to get user: {}", e)))?;
+
+    // If user has a display name, use it; otherwise suggest the token's, or fall back to username
+    let current_display_name = match &user {
+        Some(user) if !user.display_name.is_empty() => user.display_name.clone(),
+        _ => page
+            .suggested_display_name
+            .map(str::to_string)
+            .unwrap_or_else(|| validated_username.as_str().to_string()),
+    };
+    let (profile, display_name_overrides, localized_display_names) = match user {
+        Some(user) => (user.profile, user.display_name_overrides, user.localized_display_names),
+        None => (UserProfile::default(), BTreeMap::new(), BTreeMap::new()),
     };
 
+    // Show the user any display name still awaiting (or refused by) moderation
+    let pending = if app_state.moderation.enabled {
+        app_state
+            .database
+            .get_pending_display_name(tenant, validated_username.as_str())
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to get pending display name: {}", e)))?
+    } else {
+        None
+    };
+
+    let avatar = app_state.avatars.get(tenant, validated_username.as_str()).await?;
+
     // Render the edit template
-    let html = app_state.template_service.render("edit.html", context! {
-        username => validated_username.as_str(),
-        display_name => current_display_name,
-        title => format!("Edit - {}", validated_username),
-        description => format!("Content management system to edit display name for user {}", validated_username.as_str()),
-        keywords => "edit, cms, content management, display name, profile"
-    })?;
+    let catalog = app_state.template_service.catalog();
+    let username_arg = [("username", validated_username.as_str().into())];
+    let html = app_state.template_service.render(
+        tenant,
+        "edit.html",
+        context! {
+            locale => locale.as_str(),
+            username => validated_username.as_str(),
+            display_name => current_display_name,
+            profile => profile,
+            variants_enabled => !app_state.moderation.enabled && !page.editing_other_user,
+            editing_other_user => page.editing_other_user,
+            form_action => page.form_action,
+            csrf_token => page.csrf_token,
+            display_name_overrides => display_name_overrides,
+            localized_display_names => localized_display_names,
+            default_app => page.default_app.map(ValidatedAppKey::as_str),
+            avatar_url => avatar.as_ref().map(|avatar| avatar_url(validated_username.as_str(), avatar)),
+            identicon_url => identicon_url(validated_username.as_str()),
+            avatar_max_bytes => app_state.avatars.config().max_upload_bytes,
+            pending_display_name => pending.as_ref().map(|p| p.display_name.as_str()),
+            pending_status => pending.as_ref().map(|p| p.status.as_str()),
+            rejection_reason => pending.as_ref().and_then(|p| p.rejection_reason.as_deref()),
+            title => catalog.message(locale, "edit-page-title", &username_arg),
+            description => catalog.message(locale, "edit-page-description", &username_arg),
+            keywords => catalog.message(locale, "edit-page-keywords", &[])
+        },
+    )?;
 
     Ok(Html(html))
 }
@@ -1,4 +1,13 @@
+pub mod delete_api_key;
+pub mod delete_api_username_locale;
+pub mod delete_api_username_override;
+pub mod display_name_variant;
+pub mod freshness;
+pub mod get_api_keys;
+pub mod get_api_moderation_pending;
 pub mod get_api_username;
+pub mod get_api_username_availability;
+pub mod get_avatar;
 pub mod get_debug_headers;
 pub mod get_debug_set_token;
 pub mod get_debug_validate_token;
@@ -7,4 +16,13 @@ pub mod get_edit;
 pub mod get_health;
 pub mod get_seed_status;
 pub mod get_static;
+pub mod post_api_avatar;
+pub mod post_api_keys;
+pub mod post_api_moderation_approve;
+pub mod post_api_moderation_reject;
+pub mod post_api_revocations;
 pub mod post_api_username;
+pub mod post_auth_logout;
+pub mod post_auth_session;
+pub mod put_api_username_locale;
+pub mod put_api_username_override;
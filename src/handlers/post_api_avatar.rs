@@ -0,0 +1,75 @@
+use axum::{
+    extract::{multipart::MultipartError, Extension, Multipart, State},
+    http::StatusCode,
+    response::Json,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::avatar::avatar_url;
+use crate::errors::AppError;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::ValidatedUsername;
+
+/// Name of the multipart field carrying the image
+const AVATAR_FIELD: &str = "avatar";
+
+#[derive(Debug, Serialize)]
+pub struct AvatarResponse {
+    pub username: String,
+    pub avatar_url: String,
+    pub etag: String,
+}
+
+/// POST /api/avatar - Upload an avatar for the authenticated user (multipart field `avatar`)
+pub async fn post_api_avatar(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    mut multipart: Multipart,
+) -> Result<Json<AvatarResponse>, AppError> {
+    let validated_username = ValidatedUsername::existing(auth.username.clone())?;
+
+    // Avatars belong to existing users; this also keeps reserved names from gaining one
+    match app_state.database.get_user(&tenant, validated_username.as_str()).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(AppError::user_not_found(validated_username.as_str())),
+        Err(e) => return Err(AppError::database_error(format!("Failed to get user: {}", e))),
+    }
+
+    let mut upload = None;
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() == Some(AVATAR_FIELD) {
+            upload = Some(field.bytes().await.map_err(multipart_error)?);
+            break;
+        }
+    }
+
+    let Some(bytes) = upload else {
+        return Err(AppError::invalid_input(format!("Missing multipart field '{}'", AVATAR_FIELD))
+            .localized("avatar-missing-field", [("field", AVATAR_FIELD.into())]));
+    };
+
+    let avatar = app_state.avatars.upload(&tenant, validated_username.as_str(), &bytes).await?;
+    tracing::info!(
+        "Uploaded avatar for '{}' ({} bytes stored)",
+        validated_username,
+        avatar.data.len()
+    );
+
+    Ok(Json(AvatarResponse {
+        avatar_url: avatar_url(validated_username.as_str(), &avatar),
+        username: validated_username.into_string(),
+        etag: avatar.etag,
+    }))
+}
+
+fn multipart_error(error: MultipartError) -> AppError {
+    if error.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::payload_too_large("Avatar upload is too large").localized("avatar-upload-too-large", [])
+    } else {
+        AppError::invalid_input(format!("Invalid multipart body: {}", error.body_text()))
+    }
+}
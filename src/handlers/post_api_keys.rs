@@ -0,0 +1,55 @@
+use axum::{extract::State, http::StatusCode, response::Json, Extension};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::api_keys::NewApiKey;
+use crate::database::{ApiKey, ApiKeyScope};
+use crate::errors::AppError;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scope: ApiKeyScope,
+    /// CIDR ranges the key may be used from; empty or absent allows any address
+    #[serde(default)]
+    pub allowed_ips: Vec<String>,
+    /// Requests allowed per minute; absent for no quota
+    pub quota_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKey,
+    /// The key itself, shown only in this response
+    pub key: String,
+}
+
+/// POST /api/keys - Create an API key for a backend service of the tenant
+pub async fn post_api_keys(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> Result<(StatusCode, Json<CreateApiKeyResponse>), AppError> {
+    let new_key = NewApiKey {
+        name: payload.name,
+        scope: payload.scope,
+        allowed_ips: payload.allowed_ips,
+        quota_per_minute: payload.quota_per_minute,
+    };
+    let (api_key, key) = app_state.api_keys.create(&tenant, new_key, &auth.username).await?;
+
+    tracing::info!(
+        "Admin '{}' created {} API key '{}' ({})",
+        auth.username,
+        api_key.scope.as_str(),
+        api_key.name,
+        api_key.id
+    );
+    Ok((StatusCode::CREATED, Json(CreateApiKeyResponse { api_key, key })))
+}
@@ -0,0 +1,57 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use std::sync::Arc;
+
+use crate::errors::{AppError, ErrorCode};
+use crate::handlers::get_api_username::UsernameResponse;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::ValidatedUsername;
+
+/// POST /api/moderation/{username}/approve - Publish a user's pending display name
+pub async fn post_api_moderation_approve(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Path(username): Path<String>,
+) -> Result<Json<UsernameResponse>, AppError> {
+    app_state.authorization.require_moderator(&auth)?;
+
+    let validated_username = ValidatedUsername::existing(username)?;
+
+    match app_state
+        .database
+        .approve_pending_display_name(&tenant, validated_username.as_str())
+        .await
+    {
+        Ok(Some(approved)) => {
+            tracing::info!(
+                "Moderator '{}' approved display name '{}' for '{}'",
+                auth.username,
+                approved.display_name,
+                validated_username
+            );
+
+            match app_state.database.get_user(&tenant, validated_username.as_str()).await {
+                Ok(Some(user)) => Ok(Json(UsernameResponse::from(user))),
+                Ok(None) => Err(AppError::user_not_found(validated_username.as_str())),
+                Err(e) => Err(AppError::database_error(format!("Failed to get user: {}", e))),
+            }
+        }
+        Ok(None) => Err(no_pending_display_name(&validated_username)),
+        Err(e) => {
+            tracing::error!("Database error approving display name for '{}': {}", validated_username, e);
+            Err(AppError::database_error(format!("Failed to approve display name: {}", e)))
+        }
+    }
+}
+
+/// 404 for a user with nothing awaiting review
+pub fn no_pending_display_name(username: &ValidatedUsername) -> AppError {
+    AppError::new(ErrorCode::UserNotFound, format!("No pending display name for '{}'", username))
+        .localized("pending-display-name-not-found", [("username", username.to_string().into())])
+}
@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::database::PendingDisplayName;
+use crate::errors::AppError;
+use crate::handlers::post_api_moderation_approve::no_pending_display_name;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::ValidatedUsername;
+
+/// Maximum length of a rejection reason, matching the `rejection_reason` column
+const REJECTION_REASON_MAX_LENGTH: usize = 500;
+
+#[derive(Debug, Deserialize)]
+pub struct RejectDisplayNameRequest {
+    pub reason: String,
+}
+
+/// POST /api/moderation/{username}/reject - Refuse a pending display name, with a reason shown to the user
+pub async fn post_api_moderation_reject(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Path(username): Path<String>,
+    Json(payload): Json<RejectDisplayNameRequest>,
+) -> Result<Json<PendingDisplayName>, AppError> {
+    app_state.authorization.require_moderator(&auth)?;
+
+    let validated_username = ValidatedUsername::existing(username)?;
+    let reason = validate_rejection_reason(&payload.reason)?;
+
+    match app_state
+        .database
+        .reject_pending_display_name(&tenant, validated_username.as_str(), reason)
+        .await
+    {
+        Ok(Some(rejected)) => {
+            tracing::info!(
+                "Moderator '{}' rejected display name '{}' for '{}': {}",
+                auth.username,
+                rejected.display_name,
+                validated_username,
+                reason
+            );
+            Ok(Json(rejected))
+        }
+        Ok(None) => Err(no_pending_display_name(&validated_username)),
+        Err(e) => {
+            tracing::error!("Database error rejecting display name for '{}': {}", validated_username, e);
+            Err(AppError::database_error(format!("Failed to reject display name: {}", e)))
+        }
+    }
+}
+
+fn validate_rejection_reason(reason: &str) -> Result<&str, AppError> {
+    let reason = reason.trim();
+
+    if reason.is_empty() {
+        return Err(
+            AppError::validation_failed("Rejection reason cannot be empty").localized("rejection-reason-empty", [])
+        );
+    }
+
+    if reason.chars().count() > REJECTION_REASON_MAX_LENGTH {
+        return Err(AppError::validation_failed(format!(
+            "Rejection reason cannot be longer than {} characters",
+            REJECTION_REASON_MAX_LENGTH
+        ))
+        .localized("rejection-reason-too-long", [("max", REJECTION_REASON_MAX_LENGTH.into())]));
+    }
+
+    if reason.chars().any(char::is_control) {
+        return Err(
+            AppError::validation_failed("Rejection reason cannot contain control characters")
+                .localized("rejection-reason-control-characters", []),
+        );
+    }
+
+    Ok(reason)
+}
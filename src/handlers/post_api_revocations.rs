@@ -0,0 +1,128 @@
+use axum::{extract::State, http::StatusCode, response::Json, Extension};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::errors::AppError;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+
+/// Longest `jti` or subject that can be revoked
+pub const MAX_REVOCATION_ID_LENGTH: usize = 255;
+
+/// Either `jti` (with the token's `exp` as `expires_at`), or `subject` (with `issued_before`),
+/// of tokens from `issuer` (default: the issuer of the admin's own token)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RevocationRequest {
+    pub issuer: Option<String>,
+    pub jti: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub subject: Option<String>,
+    pub issued_before: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RevocationResponse {
+    pub issuer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issued_before: Option<DateTime<Utc>>,
+    /// When the revocation is dropped; tokens it covers must have expired by then
+    pub expires_at: DateTime<Utc>,
+}
+
+/// POST /api/revocations - Revoke one token by its `jti`, or all tokens of a subject, in the admin's tenant
+pub async fn post_api_revocations(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Json(payload): Json<RevocationRequest>,
+) -> Result<(StatusCode, Json<RevocationResponse>), AppError> {
+    let issuer = payload.issuer.clone().unwrap_or_else(|| auth.issuer.clone());
+    if app_state.jwt.config().issuer(&issuer).is_none() {
+        return Err(AppError::invalid_input(format!("'{}' is not a trusted issuer", issuer))
+            .localized("revocation-issuer-unknown", [("issuer", issuer.into())]));
+    }
+
+    let response = match payload {
+        RevocationRequest {
+            jti: Some(jti),
+            expires_at,
+            subject: None,
+            issued_before: None,
+            ..
+        } => {
+            validate_id("jti", &jti)?;
+            let expires_at = app_state.revocation.revoke_token(&tenant, &issuer, &jti, expires_at).await?;
+            tracing::info!(
+                "Admin '{}' revoked token {} of {} in tenant {}",
+                auth.username,
+                jti,
+                issuer,
+                tenant
+            );
+            RevocationResponse {
+                issuer,
+                jti: Some(jti),
+                subject: None,
+                issued_before: None,
+                expires_at,
+            }
+        }
+        RevocationRequest {
+            jti: None,
+            expires_at: None,
+            subject: Some(subject),
+            issued_before,
+            ..
+        } => {
+            validate_id("subject", &subject)?;
+            let revoked = app_state
+                .revocation
+                .revoke_subject(&tenant, &issuer, &subject, issued_before)
+                .await?;
+            tracing::info!(
+                "Admin '{}' revoked tokens of subject {} of {} in tenant {} issued before {}",
+                auth.username,
+                revoked.subject,
+                revoked.issuer,
+                tenant,
+                revoked.issued_before
+            );
+            RevocationResponse {
+                issuer: revoked.issuer,
+                jti: None,
+                subject: Some(revoked.subject),
+                issued_before: Some(revoked.issued_before),
+                expires_at: revoked.expires_at,
+            }
+        }
+        _ => {
+            return Err(AppError::invalid_input(
+                "Revoke either a 'jti' (with optional 'expires_at') or a 'subject' (with optional 'issued_before')",
+            )
+            .localized("revocation-target-invalid", []))
+        }
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+fn validate_id(field: &'static str, value: &str) -> Result<(), AppError> {
+    if value.trim().is_empty() || value.len() > MAX_REVOCATION_ID_LENGTH {
+        return Err(AppError::invalid_input(format!(
+            "'{}' must be between 1 and {} characters long",
+            field, MAX_REVOCATION_ID_LENGTH
+        ))
+        .localized(
+            "revocation-id-invalid",
+            [("field", field.into()), ("max", MAX_REVOCATION_ID_LENGTH.into())],
+        ));
+    }
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use axum::{
-    extract::{Extension, State},
+    extract::{Extension, Path, State},
+    http::StatusCode,
     response::Json,
 };
 use serde::Deserialize;
@@ -7,47 +8,295 @@ use std::sync::Arc;
 
 use crate::errors::AppError;
 use crate::handlers::get_api_username::UsernameResponse;
-use crate::middleware::jwt_auth::Claims;
+use crate::middleware::{ApiKeyPrincipal, AuthenticatedUser};
+use crate::profile::ProfileUpdate;
 use crate::router::AppState;
-use crate::validation::{sanitize_display_name, ValidatedDisplayName, ValidatedUsername};
+use crate::tenant::Tenant;
+use crate::validation::{
+    impersonation_candidate, is_reserved_username, sanitize_display_name, validate_display_name_script,
+    ValidatedDisplayName, ValidatedUsername,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateUsernameRequest {
     pub display_name: String,
+    /// Optional profile fields; absent fields are left unchanged
+    #[serde(flatten)]
+    pub profile: ProfileUpdate,
 }
 
 pub async fn post_api_username(
     State(app_state): State<Arc<AppState>>,
-    Extension(claims): Extension<Claims>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
     Json(payload): Json<UpdateUsernameRequest>,
-) -> Result<Json<UsernameResponse>, AppError> {
-    let username = &claims.sub;
+) -> Result<(StatusCode, Json<UsernameResponse>), AppError> {
     // Validate username from JWT token
-    let validated_username = ValidatedUsername::new(username.clone())?;
+    let validated_username = claimable_username(&app_state, &tenant, &auth.username).await?;
+
+    update_user(&app_state, &tenant, validated_username, payload).await
+}
+
+/// POST /api/username/{username} - an admin, or a service with a write API key, updates another user of the tenant
+pub async fn post_api_username_for_user(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Path(username): Path<String>,
+    auth: Option<Extension<AuthenticatedUser>>,
+    api_key: Option<Extension<ApiKeyPrincipal>>,
+    Json(payload): Json<UpdateUsernameRequest>,
+) -> Result<(StatusCode, Json<UsernameResponse>), AppError> {
+    let validated_username = ValidatedUsername::existing(username)?;
+
+    match (auth, api_key) {
+        (Some(Extension(auth)), _) => tracing::info!("Admin '{}' is updating '{}'", auth.username, validated_username),
+        (None, Some(Extension(key))) => {
+            tracing::info!("API key '{}' ({}) is updating '{}'", key.name, key.id, validated_username)
+        }
+        // Only reachable if the route loses its admin guard; never edit on nobody's behalf
+        (None, None) => {
+            return Err(AppError::forbidden("Admin role required")
+                .localized("admin-role-required", [])
+                .auth_failure("admin_role_required"))
+        }
+    }
+
+    // Admins edit existing users; they do not create accounts
+    match app_state.database.get_user(&tenant, validated_username.as_str()).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(AppError::user_not_found(validated_username.into_string())),
+        Err(e) => return Err(AppError::database_error(format!("Failed to get user: {}", e))),
+    }
+    update_user(&app_state, &tenant, validated_username, payload).await
+}
+
+/// Validate and store a display name and profile update for `validated_username`
+async fn update_user(
+    app_state: &AppState,
+    tenant: &Tenant,
+    validated_username: ValidatedUsername,
+    payload: UpdateUsernameRequest,
+) -> Result<(StatusCode, Json<UsernameResponse>), AppError> {
+    let validated_display_name =
+        validate_new_display_name(app_state, tenant, &validated_username, &payload.display_name).await?;
+
+    let current_user = app_state
+        .database
+        .get_user(tenant, validated_username.as_str())
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to get user: {}", e)))?;
+    let current_profile = current_user.as_ref().map(|user| user.profile.clone()).unwrap_or_default();
+
+    // Validate every submitted profile field before anything is stored
+    let profile = payload.profile.apply(&current_profile)?;
+
+    let (status, pending_display_name) = if app_state.moderation.enabled {
+        submit_for_moderation(app_state, tenant, &validated_username, &validated_display_name).await?;
+        (StatusCode::ACCEPTED, Some(validated_display_name.into_string()))
+    } else {
+        if let Err(e) = app_state
+            .database
+            .update_user_display_name(tenant, validated_username.as_str(), validated_display_name.as_str())
+            .await
+        {
+            tracing::error!("Database error updating user '{}': {}", validated_username, e);
+            return Err(AppError::database_error(format!("Failed to update user: {}", e)));
+        }
+
+        tracing::info!(
+            "Updated display name for '{}' to '{}'",
+            validated_username,
+            validated_display_name
+        );
+        (StatusCode::OK, None)
+    };
+
+    if profile != current_profile {
+        if let Err(e) = app_state
+            .database
+            .update_user_profile(tenant, validated_username.as_str(), &profile)
+            .await
+        {
+            tracing::error!("Database error updating profile for '{}': {}", validated_username, e);
+            return Err(AppError::database_error(format!("Failed to update profile: {}", e)));
+        }
+
+        tracing::info!("Updated profile for '{}'", validated_username);
+    }
+
+    // Read the user back so the response carries the published name and stored timestamps
+    let stored_user = app_state
+        .database
+        .get_user(tenant, validated_username.as_str())
+        .await
+        .map_err(|e| AppError::database_error(format!("Failed to get user: {}", e)))?;
+
+    let response = match stored_user {
+        Some(user) => UsernameResponse {
+            pending_display_name,
+            ..UsernameResponse::from(user)
+        },
+        // A new user whose first display name is awaiting moderation has no stored record yet
+        None => UsernameResponse {
+            username: validated_username.to_string(),
+            display_name: validated_username.into_string(),
+            app: None,
+            display_name_locale: None,
+            pending_display_name,
+            profile,
+            created_at: None,
+            updated_at: None,
+        },
+    };
+
+    Ok((status, Json(response)))
+}
 
-    // Sanitize and validate display name
-    let sanitized_display_name = sanitize_display_name(&payload.display_name);
+/// Sanitize a submitted display name and run every check a published name must pass
+pub(crate) async fn validate_new_display_name(
+    app_state: &AppState,
+    tenant: &Tenant,
+    username: &ValidatedUsername,
+    display_name: &str,
+) -> Result<ValidatedDisplayName, AppError> {
+    let sanitized_display_name = sanitize_display_name(display_name);
     let validated_display_name = ValidatedDisplayName::new(sanitized_display_name)?;
+    app_state
+        .display_name_policies
+        .for_tenant(tenant)
+        .check(validated_display_name.as_str())?;
 
-    match app_state
+    if app_state.display_names.confusable_check {
+        check_confusable_display_name(app_state, tenant, username, &validated_display_name).await?;
+    }
+
+    Ok(validated_display_name)
+}
+
+/// Hold the display name for review; the published name stays unchanged until a moderator approves
+async fn submit_for_moderation(
+    app_state: &AppState,
+    tenant: &Tenant,
+    username: &ValidatedUsername,
+    display_name: &ValidatedDisplayName,
+) -> Result<(), AppError> {
+    if let Err(e) = app_state
         .database
-        .update_user_display_name(validated_username.as_str(), validated_display_name.as_str())
+        .submit_pending_display_name(tenant, username.as_str(), display_name.as_str())
         .await
     {
-        Ok(()) => {
-            tracing::info!(
-                "Updated display name for '{}' to '{}'",
-                validated_username,
-                validated_display_name
+        tracing::error!("Database error submitting display name for '{}': {}", username, e);
+        return Err(AppError::database_error(format!("Failed to submit display name: {}", e)));
+    }
+
+    tracing::info!("Display name '{}' for '{}' submitted for moderation", display_name, username);
+    Ok(())
+}
+
+/// Reserved names cannot be claimed, but accounts that already exist under one keep working
+async fn claimable_username(
+    app_state: &AppState,
+    tenant: &Tenant,
+    username: &str,
+) -> Result<ValidatedUsername, AppError> {
+    let validated_username = ValidatedUsername::existing(username.to_string())?;
+
+    if !is_reserved_username(validated_username.as_str()) {
+        return Ok(validated_username);
+    }
+
+    match app_state.database.get_user(tenant, validated_username.as_str()).await {
+        Ok(Some(_)) => Ok(validated_username),
+        Ok(None) => ValidatedUsername::new(username.to_string()),
+        Err(e) => Err(AppError::database_error(format!("Failed to get user: {}", e))),
+    }
+}
+
+/// Reject mixed-script display names and names that look like another existing user's handle
+async fn check_confusable_display_name(
+    app_state: &AppState,
+    tenant: &Tenant,
+    username: &ValidatedUsername,
+    display_name: &ValidatedDisplayName,
+) -> Result<(), AppError> {
+    validate_display_name_script(display_name.as_str())?;
+
+    let Some(candidate) = impersonation_candidate(display_name.as_str()) else {
+        return Ok(());
+    };
+
+    if candidate == username.as_str() {
+        return Ok(());
+    }
+
+    match app_state.database.get_user(tenant, &candidate).await {
+        Ok(Some(_)) => {
+            tracing::warn!(
+                "Rejected display name '{}' for '{}': resembles existing user '{}'",
+                display_name,
+                username,
+                candidate
             );
-            Ok(Json(UsernameResponse {
-                username: validated_username.into_string(),
-                display_name: validated_display_name.into_string(),
-            }))
-        }
-        Err(e) => {
-            tracing::error!("Database error updating user '{}': {}", validated_username, e);
-            Err(AppError::database_error(format!("Failed to update user: {}", e)))
+            Err(AppError::validation_failed("Display name cannot impersonate another user")
+                .localized("display-name-impersonation", []))
         }
+        Ok(None) => Ok(()),
+        Err(e) => Err(AppError::database_error(format!("Failed to get user: {}", e))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::display_name::DisplayNameConfig;
+    use crate::database::{mock::MockUserDatabase, UserDatabase};
+    use crate::profile::ProfileUpdate;
+
+    #[tokio::test]
+    async fn test_update_for_user_requires_a_principal() {
+        let db = Arc::new(MockUserDatabase::new());
+        let app_state = Arc::new(AppState::for_tests(db.clone()));
+
+        let error = post_api_username_for_user(
+            State(app_state),
+            Tenant::default(),
+            Path("admin".to_string()),
+            None,
+            None,
+            Json(UpdateUsernameRequest {
+                display_name: "Nobody".to_string(),
+                profile: ProfileUpdate::default(),
+            }),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(error.auth_failure, Some(crate::errors::AuthFailure("admin_role_required")));
+        let user = db.get_user(&Tenant::default(), "admin").await.unwrap().unwrap();
+        assert_eq!(user.display_name, "Administrator");
+    }
+
+    #[tokio::test]
+    async fn test_confusable_check_follows_config() {
+        let db = Arc::new(MockUserDatabase::new());
+        let username = ValidatedUsername::existing("testuser".to_string()).unwrap();
+        let mixed_script = "P\u{0430}ypal"; // Cyrillic "а"
+
+        let app_state = AppState::for_tests(db.clone());
+        assert!(
+            validate_new_display_name(&app_state, &Tenant::default(), &username, mixed_script)
+                .await
+                .is_ok()
+        );
+
+        let app_state = AppState {
+            display_names: DisplayNameConfig { confusable_check: true },
+            ..AppState::for_tests(db)
+        };
+        assert!(
+            validate_new_display_name(&app_state, &Tenant::default(), &username, mixed_script)
+                .await
+                .is_err()
+        );
     }
 }
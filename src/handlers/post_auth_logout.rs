@@ -0,0 +1,18 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use std::sync::Arc;
+
+use crate::router::AppState;
+
+/// POST /auth/logout - Clear the session cookie set by `POST /auth/session`
+///
+/// The token itself stays valid until it expires; revoke it (`POST /api/revocations`) to end it everywhere.
+pub async fn post_auth_logout(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        StatusCode::NO_CONTENT,
+        [(header::SET_COOKIE, app_state.session.cleared_cookie())],
+    )
+}
@@ -0,0 +1,42 @@
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::errors::AuthError;
+use crate::middleware::authenticate;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+
+/// POST /auth/session - Exchange the bearer token for an HttpOnly session cookie
+///
+/// Only the `Authorization` header is accepted, so an existing session cannot be extended by
+/// itself. The cookie expires with the token, and every request using it verifies the token again.
+pub async fn post_auth_session(State(app_state): State<Arc<AppState>>, tenant: Tenant, headers: HeaderMap) -> Response {
+    let Some(token) = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    else {
+        tracing::info!("Session exchange without a bearer token");
+        return AuthError::MissingToken.into_response();
+    };
+
+    let (user, claims) = match authenticate(&app_state, &tenant, token).await {
+        Ok(authenticated) => authenticated,
+        Err(response) => return response,
+    };
+
+    let max_age = (claims.exp as u64).saturating_sub(jsonwebtoken::get_current_timestamp());
+    tracing::info!("Started a session for '{}' lasting {}s", user.username, max_age);
+
+    let mut response = (
+        StatusCode::NO_CONTENT,
+        [(header::SET_COOKIE, app_state.session.session_cookie(token, max_age))],
+    )
+        .into_response();
+    response.extensions_mut().insert(user);
+    response
+}
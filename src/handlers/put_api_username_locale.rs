@@ -0,0 +1,43 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use std::sync::Arc;
+
+use crate::errors::AppError;
+use crate::handlers::display_name_variant::{set_display_name_variant, DisplayNameVariantRequest, VariantKey};
+use crate::handlers::get_api_username::UsernameResponse;
+use crate::middleware::AuthenticatedUser;
+use crate::profile::validate_locale;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::ValidatedUsername;
+
+/// Maximum number of languages a user can set a display name for
+pub const MAX_LOCALIZED_DISPLAY_NAMES: usize = 20;
+
+/// PUT /api/username/locales/{locale} - Set the display name for readers of one language
+pub async fn put_api_username_locale(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Path(locale): Path<String>,
+    Json(payload): Json<DisplayNameVariantRequest>,
+) -> Result<Json<UsernameResponse>, AppError> {
+    let validated_username = ValidatedUsername::existing(auth.username.clone())?;
+    let locale = validate_locale(locale.trim())?;
+
+    let user = set_display_name_variant(
+        &app_state,
+        &tenant,
+        &validated_username,
+        VariantKey::Locale(&locale),
+        &payload.display_name,
+        MAX_LOCALIZED_DISPLAY_NAMES,
+    )
+    .await?;
+
+    // Respond with the user as a reader of this language sees them
+    Ok(Json(UsernameResponse::for_reader(user, None, &[locale])))
+}
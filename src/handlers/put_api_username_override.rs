@@ -0,0 +1,41 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    Extension,
+};
+use std::sync::Arc;
+
+use crate::errors::AppError;
+use crate::handlers::display_name_variant::{set_display_name_variant, DisplayNameVariantRequest, VariantKey};
+use crate::handlers::get_api_username::UsernameResponse;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+use crate::validation::{ValidatedAppKey, ValidatedUsername};
+
+/// Maximum number of applications a user can set a display name for
+pub const MAX_DISPLAY_NAME_OVERRIDES: usize = 20;
+
+/// PUT /api/username/overrides/{app} - Set the display name shown to one application
+pub async fn put_api_username_override(
+    State(app_state): State<Arc<AppState>>,
+    tenant: Tenant,
+    Extension(auth): Extension<AuthenticatedUser>,
+    Path(app): Path<String>,
+    Json(payload): Json<DisplayNameVariantRequest>,
+) -> Result<Json<UsernameResponse>, AppError> {
+    let validated_username = ValidatedUsername::existing(auth.username.clone())?;
+    let app = ValidatedAppKey::new(&app)?;
+
+    let user = set_display_name_variant(
+        &app_state,
+        &tenant,
+        &validated_username,
+        VariantKey::App(&app),
+        &payload.display_name,
+        MAX_DISPLAY_NAME_OVERRIDES,
+    )
+    .await?;
+
+    Ok(Json(UsernameResponse::for_reader(user, Some(&app), &[])))
+}
@@ -0,0 +1,289 @@
+use anyhow::{Context, Result};
+use axum::{extract::FromRequestParts, http::request::Parts};
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use minijinja::value::{Kwargs, ValueKind};
+use minijinja::{Environment, State, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+use unic_langid::LanguageIdentifier;
+
+use crate::errors::{AppError, MessageArg};
+use crate::language::negotiate;
+
+/// Locale of deployments that do not set `DEFAULT_LOCALE`
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Directory holding one `<language-tag>.ftl` catalog per locale
+pub const DEFAULT_LOCALES_DIR: &str = "locales";
+
+/// Cookie and query parameter a reader can use to pick a language over `Accept-Language`
+pub const LOCALE_PARAMETER: &str = "lang";
+
+/// Translated UI strings and error messages, one Fluent bundle per locale.
+///
+/// Every `<language-tag>.ftl` file in the locales directory (`en.ftl`, `fr.ftl`, `pt-BR.ftl`)
+/// adds a locale, so a language ships without code changes. Messages missing from a locale
+/// fall back to the default locale's catalog.
+pub struct Catalog {
+    bundles: BTreeMap<String, FluentBundle<FluentResource>>,
+    default_locale: String,
+}
+
+impl Catalog {
+    /// Load every `.ftl` file in `dir`; the catalog for `default_locale` must be among them
+    pub fn load(dir: &Path, default_locale: &str) -> Result<Self> {
+        let mut bundles = BTreeMap::new();
+
+        let entries =
+            std::fs::read_dir(dir).with_context(|| format!("Failed to read locales from {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("ftl") {
+                continue;
+            }
+
+            let language: LanguageIdentifier = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default()
+                .parse()
+                .with_context(|| format!("{} is not named after a language tag", path.display()))?;
+
+            let source =
+                std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let resource = FluentResource::try_new(source)
+                .map_err(|(_, errors)| anyhow::anyhow!("Failed to parse {}: {:?}", path.display(), errors))?;
+
+            let mut bundle = FluentBundle::new_concurrent(vec![language.clone()]);
+            // Unicode isolation marks around arguments would end up in attributes and JSON
+            bundle.set_use_isolating(false);
+            bundle
+                .add_resource(resource)
+                .map_err(|errors| anyhow::anyhow!("Invalid messages in {}: {:?}", path.display(), errors))?;
+
+            bundles.insert(language.to_string(), bundle);
+        }
+
+        let default_locale = bundles
+            .keys()
+            .find(|locale| locale.eq_ignore_ascii_case(default_locale))
+            .cloned()
+            .with_context(|| format!("No catalog for default locale '{}' in {}", default_locale, dir.display()))?;
+
+        Ok(Self { bundles, default_locale })
+    }
+
+    /// Tags of all loaded locales, in conventional casing (`pt-BR`)
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.bundles.keys().map(String::as_str)
+    }
+
+    pub fn default_locale(&self) -> Locale {
+        Locale(self.default_locale.clone())
+    }
+
+    /// The loaded locale that best matches `ranges` (most preferred first), or the default locale
+    pub fn negotiate(&self, ranges: &[String]) -> Locale {
+        negotiate(ranges, self.locales()).map_or_else(|| self.default_locale(), |locale| Locale(locale.to_string()))
+    }
+
+    /// Format message `id` in `locale`.
+    ///
+    /// Falls back to the default locale, then to the id itself, so a missing translation
+    /// degrades to English rather than failing the request.
+    pub fn message(&self, locale: &Locale, id: &str, args: &[(&str, MessageArg)]) -> String {
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            match value {
+                MessageArg::Text(text) => fluent_args.set(*name, FluentValue::from(text.as_str())),
+                MessageArg::Number(number) => fluent_args.set(*name, FluentValue::from(*number)),
+            }
+        }
+
+        [locale.as_str(), self.default_locale.as_str()]
+            .into_iter()
+            .filter_map(|locale| self.bundles.get(locale))
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+                if !errors.is_empty() {
+                    tracing::warn!("Errors formatting message '{}' for {}: {:?}", id, locale, errors);
+                }
+                Some(text.into_owned())
+            })
+            .unwrap_or_else(|| {
+                tracing::warn!("Message '{}' is missing from the default locale catalog", id);
+                id.to_string()
+            })
+    }
+
+    /// Register the `t(id, **args)` template function, which formats a message in the
+    /// locale given by the template's `locale` variable
+    pub fn register_template_functions(self: &Arc<Self>, env: &mut Environment<'static>) {
+        let catalog = Arc::clone(self);
+        env.add_function(
+            "t",
+            move |state: &State, id: &str, kwargs: Kwargs| -> Result<String, minijinja::Error> {
+                let locale = state
+                    .lookup("locale")
+                    .and_then(|locale| locale.as_str().map(|locale| Locale(locale.to_string())))
+                    .unwrap_or_else(|| catalog.default_locale());
+
+                let args = kwargs
+                    .args()
+                    .map(|name| {
+                        let value: Value = kwargs.get(name)?;
+                        let value = match value.kind() {
+                            ValueKind::Number => MessageArg::Number(f64::try_from(value)?),
+                            _ => MessageArg::Text(value.to_string()),
+                        };
+                        Ok((name, value))
+                    })
+                    .collect::<Result<Vec<_>, minijinja::Error>>()?;
+
+                Ok(catalog.message(&locale, id, &args))
+            },
+        );
+    }
+}
+
+/// The language a request is served in, negotiated by the locale middleware from the
+/// `lang` query parameter, the `lang` cookie and `Accept-Language`, in that order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Locale(String);
+
+impl Locale {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale(DEFAULT_LOCALE.to_string())
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Locale>()
+            .cloned()
+            .ok_or_else(|| AppError::internal_server_error("Locale was not negotiated for this request"))
+    }
+}
+
+/// Language ranges a request asks for, most preferred first: the `lang` query parameter, the
+/// `lang` cookie, then `Accept-Language`. Collected by the locale middleware, which negotiates
+/// the [`Locale`] from them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestedLanguages(pub Vec<String>);
+
+impl<S: Send + Sync> FromRequestParts<S> for RequestedLanguages {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<RequestedLanguages>()
+            .cloned()
+            .ok_or_else(|| AppError::internal_server_error("Languages were not negotiated for this request"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> Arc<Catalog> {
+        Arc::new(Catalog::load(Path::new(DEFAULT_LOCALES_DIR), DEFAULT_LOCALE).unwrap())
+    }
+
+    #[test]
+    fn test_catalog_negotiate() {
+        let catalog = catalog();
+
+        assert_eq!(catalog.negotiate(&["fr-ca".to_string()]).as_str(), "fr");
+        assert_eq!(catalog.negotiate(&["xx".to_string(), "fr".to_string()]).as_str(), "fr");
+        assert_eq!(catalog.negotiate(&["xx".to_string()]), catalog.default_locale());
+        assert_eq!(catalog.negotiate(&[]).as_str(), DEFAULT_LOCALE);
+    }
+
+    #[test]
+    fn test_catalog_message() {
+        let catalog = catalog();
+        let french = catalog.negotiate(&["fr".to_string()]);
+        let args = [("username", MessageArg::from("jane"))];
+
+        assert_eq!(
+            catalog.message(&Locale::default(), "user-not-found", &args),
+            "User 'jane' not found"
+        );
+        assert_eq!(
+            catalog.message(&french, "user-not-found", &args),
+            "Utilisateur « jane » introuvable"
+        );
+
+        // Numbers select plural forms
+        let message = |count: usize| {
+            catalog.message(
+                &Locale::default(),
+                "time-ago",
+                &[("unit", "day".into()), ("count", count.into())],
+            )
+        };
+        assert_eq!(message(1), "1 day ago");
+        assert_eq!(message(3), "3 days ago");
+
+        // Unknown ids degrade to the id rather than failing
+        assert_eq!(catalog.message(&french, "no-such-message", &[]), "no-such-message");
+    }
+
+    #[test]
+    fn test_every_locale_translates_every_message() {
+        let dir = Path::new(DEFAULT_LOCALES_DIR);
+        // Messages start at the beginning of a line; terms (`-brand`) and comments do not count
+        let message_id = regex::Regex::new(r"^([a-zA-Z][a-zA-Z0-9_-]*) *=").unwrap();
+        let ids = |locale: &str| -> Vec<String> {
+            let source = std::fs::read_to_string(dir.join(format!("{locale}.ftl"))).unwrap();
+            let mut ids: Vec<String> = source
+                .lines()
+                .filter_map(|line| message_id.captures(line).map(|captures| captures[1].to_string()))
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        let catalog = catalog();
+        let expected = ids(DEFAULT_LOCALE);
+        for locale in catalog.locales() {
+            assert_eq!(ids(locale), expected, "{locale}.ftl does not match {DEFAULT_LOCALE}.ftl");
+        }
+    }
+
+    #[test]
+    fn test_template_function() {
+        let catalog = catalog();
+        let mut env = Environment::new();
+        catalog.register_template_functions(&mut env);
+
+        let template = r#"{{ t("user-not-found", username=name) }}"#;
+        let render = |locale: &str| {
+            env.render_str(template, minijinja::context! { locale => locale, name => "jane" })
+                .unwrap()
+        };
+        assert_eq!(render("en"), "User 'jane' not found");
+        assert_eq!(render("fr"), "Utilisateur « jane » introuvable");
+    }
+}
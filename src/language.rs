@@ -0,0 +1,129 @@
+use axum::http::{header, HeaderMap};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Upper bound on parsed `Accept-Language` entries, so a huge header cannot cost much work
+const MAX_LANGUAGE_RANGES: usize = 20;
+
+static LANGUAGE_RANGE_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_language_range_regex() -> &'static Regex {
+    LANGUAGE_RANGE_REGEX
+        .get_or_init(|| Regex::new(r"^[a-zA-Z]{1,8}(-[a-zA-Z0-9]{1,8})*$").expect("Invalid language range regex"))
+}
+
+/// A single language tag (e.g. from a `lang` query parameter or cookie), lowercased, if well-formed
+pub fn language_range(value: &str) -> Option<String> {
+    let value = value.trim();
+    get_language_range_regex().is_match(value).then(|| value.to_ascii_lowercase())
+}
+
+/// Language ranges from the `Accept-Language` header, most preferred first.
+///
+/// Ranges with `q=0`, the `*` wildcard and malformed entries are dropped; the
+/// default display name already serves as the wildcard match.
+pub fn accepted_languages(headers: &HeaderMap) -> Vec<String> {
+    let Some(value) = headers.get(header::ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok()) else {
+        return Vec::new();
+    };
+
+    let mut ranges: Vec<(String, f32)> = value
+        .split(',')
+        .take(MAX_LANGUAGE_RANGES)
+        .filter_map(|entry| {
+            let mut parts = entry.split(';').map(str::trim);
+            let range = language_range(parts.next()?)?;
+
+            let quality = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q)))?;
+
+            (quality > 0.0).then_some((range, quality))
+        })
+        .collect();
+
+    // Stable sort keeps the header order for equal weights
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranges.into_iter().map(|(range, _)| range).collect()
+}
+
+/// Pick the available language tag that best matches `ranges` (RFC 4647 lookup).
+///
+/// For each range in order of preference, an exact match wins, then a more specific
+/// tag (`en` matches `en-Latn`); otherwise the range is shortened (`zh-Hant-TW` → `zh-Hant`
+/// → `zh`) and tried again before moving on to the next range.
+pub fn negotiate<'a>(ranges: &[String], available: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let available: Vec<&str> = available.into_iter().collect();
+    if available.is_empty() {
+        return None;
+    }
+
+    for range in ranges {
+        let mut range = range.as_str();
+        loop {
+            if let Some(tag) = available.iter().find(|tag| tag.eq_ignore_ascii_case(range)) {
+                return Some(tag);
+            }
+
+            if let Some(tag) = available.iter().find(|tag| {
+                tag.len() > range.len()
+                    && tag.as_bytes()[range.len()] == b'-'
+                    && tag[..range.len()].eq_ignore_ascii_case(range)
+            }) {
+                return Some(tag);
+            }
+
+            match range.rfind('-') {
+                Some(end) => {
+                    range = &range[..end];
+                    // Never end on a single-letter extension singleton such as `-x`
+                    if let Some(singleton) = range.rfind('-').filter(|&start| range.len() - start == 2) {
+                        range = &range[..singleton];
+                    }
+                }
+                None => break,
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn languages(value: &'static str) -> Vec<String> {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static(value));
+        accepted_languages(&headers)
+    }
+
+    #[test]
+    fn test_accepted_languages_orders_by_quality() {
+        assert_eq!(languages("en;q=0.5, ja, de-DE;q=0.8"), ["ja", "de-de", "en"]);
+        assert_eq!(languages("fr, *;q=0.1, es;q=0, !!;q=0.9"), ["fr"]);
+        assert!(accepted_languages(&HeaderMap::new()).is_empty());
+    }
+
+    #[test]
+    fn test_language_range() {
+        assert_eq!(language_range(" fr-CA "), Some("fr-ca".to_string()));
+        assert_eq!(language_range("fr_CA"), None);
+        assert_eq!(language_range(""), None);
+    }
+
+    #[test]
+    fn test_negotiate() {
+        let available = ["ja", "en-Latn", "zh-Hant"];
+
+        assert_eq!(negotiate(&languages("ja-JP"), available), Some("ja"));
+        assert_eq!(negotiate(&languages("en-US"), available), Some("en-Latn"));
+        assert_eq!(negotiate(&languages("zh-Hant-TW"), available), Some("zh-Hant"));
+        assert_eq!(negotiate(&languages("de, en;q=0.5"), available), Some("en-Latn"));
+        assert_eq!(negotiate(&languages("ja-x-private"), available), Some("ja"));
+        assert_eq!(negotiate(&languages("de"), available), None);
+        assert_eq!(negotiate(&languages("fr"), []), None);
+    }
+}
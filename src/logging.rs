@@ -2,6 +2,9 @@ use std::time::Instant;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
+use crate::errors::AuthFailure;
+use crate::middleware::CsrfViolation;
+
 /// Structure for tracking request context and correlation IDs
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -240,6 +243,18 @@ pub async fn security_event_logging_middleware(
 
     let path = req.uri().path().to_string();
     let method = req.method().as_str().to_string();
+    let origin = req
+        .headers()
+        .get("Origin")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("none")
+        .to_string();
+    let fetch_site = req
+        .headers()
+        .get("Sec-Fetch-Site")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("none")
+        .to_string();
 
     // Check if it's a security-sensitive endpoint
     let is_auth_endpoint =
@@ -260,14 +275,32 @@ pub async fn security_event_logging_middleware(
 
     // Check for authentication failures (401)
     if response.status() == axum::http::StatusCode::UNAUTHORIZED {
+        let reason = response
+            .extensions()
+            .get::<AuthFailure>()
+            .map_or("unknown", |failure| failure.0);
         warn!(
             request_id = %request_id,
             path = %path,
             method = %method,
+            reason = %reason,
             "Authentication failure"
         );
     }
 
+    // Check for cookie-authenticated writes refused as cross-site request forgery
+    if let Some(CsrfViolation(reason)) = response.extensions().get::<CsrfViolation>() {
+        warn!(
+            request_id = %request_id,
+            path = %path,
+            method = %method,
+            reason = %reason,
+            origin = %origin,
+            sec_fetch_site = %fetch_site,
+            "CSRF violation"
+        );
+    }
+
     // Check for authorization failures (403)
     if response.status() == axum::http::StatusCode::FORBIDDEN {
         warn!(
@@ -1,25 +1,47 @@
 use anyhow::Result;
 use std::env;
+use std::net::SocketAddr;
 use tokio::net::TcpListener;
 use tracing::info;
 
+mod api_keys;
+mod avatar;
 mod config;
 mod database;
+mod display_name_policy;
 mod env_validation;
 mod errors;
 mod handlers;
+mod i18n;
+mod language;
 mod logging;
 mod metrics;
 mod middleware;
+mod profile;
+mod revocation;
 mod router;
 mod template;
+mod tenant;
 #[cfg(test)]
 mod tests;
 mod validation;
 
+use config::api_keys::create_api_key_service_from_env;
+use config::authorization::load_authorization_config;
+use config::avatar::create_avatar_service_from_env;
 use config::database::create_database_from_env;
+use config::display_name::load_display_name_config;
+use config::freshness::load_freshness_config;
+use config::jwt::{create_jwt_verifier_from_env, spawn_jwt_reloader, KEY_FILE_POLL_INTERVAL};
+use config::moderation::load_moderation_config;
+use config::rate_limit::load_rate_limit_config;
+use config::revocation::{create_revocation_service_from_env, REVOCATION_CLEANUP_INTERVAL};
+use config::session::load_session_config;
+use config::tenancy::load_tenancy_config;
+use display_name_policy::create_display_name_policy_from_env;
 use env_validation::validate_environment;
-use router::create_app;
+use middleware::RateLimiter;
+use router::{create_app, global_metrics, AppState};
 use template::create_template_service;
 
 #[tokio::main]
@@ -45,16 +67,103 @@ async fn main() -> Result<()> {
 
     tracing::subscriber::set_global_default(subscriber)?;
 
+    validation::init_reserved_usernames()?;
+    info!("- Reserved usernames loaded successfully");
+
     let database = create_database_from_env().await?;
     info!("- Database adapter initialized successfully");
 
+    let avatars = create_avatar_service_from_env(database.clone())?;
+    info!("- Avatar storage initialized successfully");
+
     let template_service = create_template_service()?;
     info!("- Template service initialized successfully");
 
+    let display_name_policies = create_display_name_policy_from_env()?;
+    info!(
+        "- Display name policy initialized successfully ({} tenant policies)",
+        display_name_policies.tenant_count()
+    );
+
+    let display_names = load_display_name_config();
+    info!(
+        "- Display name confusable check {}",
+        if display_names.confusable_check { "enabled" } else { "disabled" }
+    );
+
+    let moderation = load_moderation_config();
+    info!(
+        "- Display name moderation {}",
+        if moderation.enabled { "enabled" } else { "disabled" }
+    );
+
+    let tenancy = load_tenancy_config()?;
+    info!(
+        "- Tenancy: default tenant '{}', {} mapped hosts, tenant claim: {}",
+        tenancy.default_tenant,
+        tenancy.hosts.len(),
+        tenancy.claim.as_deref().unwrap_or("none")
+    );
+
+    let authorization = load_authorization_config();
+    info!(
+        "- Authorization: admin roles {:?}, moderator roles {:?}",
+        authorization.admin_roles, authorization.moderator_roles
+    );
+
+    let jwt = create_jwt_verifier_from_env()?;
+    spawn_jwt_reloader(jwt.clone(), KEY_FILE_POLL_INTERVAL)?;
+    info!(
+        "- JWT verifier initialized successfully ({} trusted issuers, reloads on SIGHUP)",
+        jwt.config().issuers.len()
+    );
+
+    let freshness = load_freshness_config()?;
+    info!(
+        "- Token freshness: {} routes require a recently issued token",
+        freshness.routes.len()
+    );
+
+    let session = load_session_config()?;
+    info!(
+        "- Session cookie: {} (Secure: {}, SameSite={})",
+        session.cookie_name, session.secure, session.same_site
+    );
+
+    let revocation = create_revocation_service_from_env(database.clone());
+    revocation.spawn_cleanup(REVOCATION_CLEANUP_INTERVAL);
+    info!("- Token revocation initialized successfully");
+
+    let api_keys = create_api_key_service_from_env(database.clone())?;
+    info!("- API keys initialized successfully");
+
+    let rate_limit = load_rate_limit_config()?;
+    info!(
+        "- Rate limiting {} ({} requests per minute and client)",
+        if rate_limit.enabled { "enabled" } else { "disabled" },
+        rate_limit.max_requests
+    );
+
     info!("- Starting Rust Micro Front-End Application");
     info!("- Log level: {}", log_level);
 
-    let app = create_app(database, template_service);
+    let app = create_app(AppState {
+        database,
+        template_service,
+        metrics: global_metrics(),
+        display_name_policies,
+        display_names,
+        moderation,
+        avatars,
+        tenancy,
+        authorization,
+        jwt,
+        revocation,
+        freshness,
+        session,
+        api_keys,
+        rate_limiter: RateLimiter::new(rate_limit),
+    });
 
     let port = env::var("PORT")
         .unwrap_or_else(|_| "80".to_string())
@@ -69,7 +178,8 @@ async fn main() -> Result<()> {
     info!("Server started successfully on http://{}", bind_address);
     info!("Health check available at http://{}/health", bind_address);
 
-    axum::serve(listener, app).await?;
+    // The peer address lets API key address ranges ignore X-Forwarded-For from untrusted clients
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
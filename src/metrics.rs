@@ -6,6 +6,7 @@ use prometheus::{
 use std::{sync::Arc, time::Instant};
 
 use crate::router::AppState;
+use crate::tenant::Tenant;
 
 /// Struct containing all Prometheus metrics
 #[derive(Clone)]
@@ -19,6 +20,7 @@ pub struct AppMetrics {
     // Authentication metrics
     pub auth_success_total: IntCounterVec,
     pub auth_failure_total: IntCounterVec,
+    pub api_key_requests_total: IntCounterVec,
 
     // Database metrics
     pub database_queries_total: IntCounterVec,
@@ -40,7 +42,7 @@ impl AppMetrics {
         Self {
             http_requests_total: IntCounterVec::new(
                 opts!("http_requests_total", "Total number of HTTP requests"),
-                &["method", "path", "status"],
+                &["method", "path", "status", "tenant"],
             )
             .unwrap(),
 
@@ -50,7 +52,7 @@ impl AppMetrics {
                     "HTTP request duration in seconds",
                     vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
                 ),
-                &["method", "path"],
+                &["method", "path", "tenant"],
             )
             .unwrap(),
 
@@ -62,19 +64,25 @@ impl AppMetrics {
 
             auth_success_total: IntCounterVec::new(
                 opts!("auth_success_total", "Total number of successful authentication attempts"),
-                &["username"],
+                &["issuer", "tenant"],
             )
             .unwrap(),
 
             auth_failure_total: IntCounterVec::new(
                 opts!("auth_failure_total", "Total number of failed authentication attempts"),
-                &["reason"],
+                &["reason", "tenant"],
+            )
+            .unwrap(),
+
+            api_key_requests_total: IntCounterVec::new(
+                opts!("api_key_requests_total", "Total number of requests made with an API key"),
+                &["key_id", "outcome", "tenant"],
             )
             .unwrap(),
 
             database_queries_total: IntCounterVec::new(
                 opts!("database_queries_total", "Total number of database queries"),
-                &["operation", "table"],
+                &["operation", "table", "tenant"],
             )
             .unwrap(),
 
@@ -84,7 +92,7 @@ impl AppMetrics {
                     "Database query duration in seconds",
                     vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]
                 ),
-                &["operation", "table"],
+                &["operation", "table", "tenant"],
             )
             .unwrap(),
 
@@ -94,15 +102,21 @@ impl AppMetrics {
                     "Template rendering duration in seconds",
                     vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25]
                 ),
-                &["template"],
+                &["template", "tenant"],
             )
             .unwrap(),
 
-            cache_hit_total: IntCounterVec::new(opts!("cache_hit_total", "Total number of cache hits"), &["cache"])
-                .unwrap(),
+            cache_hit_total: IntCounterVec::new(
+                opts!("cache_hit_total", "Total number of cache hits"),
+                &["cache", "tenant"],
+            )
+            .unwrap(),
 
-            cache_miss_total: IntCounterVec::new(opts!("cache_miss_total", "Total number of cache misses"), &["cache"])
-                .unwrap(),
+            cache_miss_total: IntCounterVec::new(
+                opts!("cache_miss_total", "Total number of cache misses"),
+                &["cache", "tenant"],
+            )
+            .unwrap(),
         }
     }
 
@@ -123,14 +137,14 @@ impl AppMetrics {
         let http_requests_total = register_int_counter_vec!(
             "http_requests_total",
             "Total number of HTTP requests",
-            &["method", "path", "status"]
+            &["method", "path", "status", "tenant"]
         )
         .unwrap();
 
         let http_requests_duration_seconds = register_histogram_vec!(
             "http_requests_duration_seconds",
             "HTTP request duration in seconds",
-            &["method", "path"],
+            &["method", "path", "tenant"],
             vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]
         )
         .unwrap();
@@ -142,14 +156,21 @@ impl AppMetrics {
         let auth_success_total = register_int_counter_vec!(
             "auth_success_total",
             "Total number of successful authentication attempts",
-            &["username"]
+            &["issuer", "tenant"]
         )
         .unwrap();
 
         let auth_failure_total = register_int_counter_vec!(
             "auth_failure_total",
             "Total number of failed authentication attempts",
-            &["reason"]
+            &["reason", "tenant"]
+        )
+        .unwrap();
+
+        let api_key_requests_total = register_int_counter_vec!(
+            "api_key_requests_total",
+            "Total number of requests made with an API key",
+            &["key_id", "outcome", "tenant"]
         )
         .unwrap();
 
@@ -157,14 +178,14 @@ impl AppMetrics {
         let database_queries_total = register_int_counter_vec!(
             "database_queries_total",
             "Total number of database queries",
-            &["operation", "status"]
+            &["operation", "status", "tenant"]
         )
         .unwrap();
 
         let database_query_duration_seconds = register_histogram_vec!(
             "database_query_duration_seconds",
             "Database query duration in seconds",
-            &["operation"],
+            &["operation", "tenant"],
             vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]
         )
         .unwrap();
@@ -173,16 +194,17 @@ impl AppMetrics {
         let template_render_duration_seconds = register_histogram_vec!(
             "template_render_duration_seconds",
             "Template rendering duration in seconds",
-            &["template"],
+            &["template", "tenant"],
             vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5]
         )
         .unwrap();
 
         let cache_hit_total =
-            register_int_counter_vec!("cache_hit_total", "Total number of cache hits", &["cache"]).unwrap();
+            register_int_counter_vec!("cache_hit_total", "Total number of cache hits", &["cache", "tenant"]).unwrap();
 
         let cache_miss_total =
-            register_int_counter_vec!("cache_miss_total", "Total number of cache misses", &["cache"]).unwrap();
+            register_int_counter_vec!("cache_miss_total", "Total number of cache misses", &["cache", "tenant"])
+                .unwrap();
 
         Self {
             http_requests_total,
@@ -190,6 +212,7 @@ impl AppMetrics {
             http_requests_in_flight,
             auth_success_total,
             auth_failure_total,
+            api_key_requests_total,
             database_queries_total,
             database_query_duration_seconds,
             template_render_duration_seconds,
@@ -206,6 +229,8 @@ impl Default for AppMetrics {
 }
 
 /// Middleware to track HTTP request metrics (request count, duration)
+///
+/// The tenant label comes from the response extensions set by the tenant middleware.
 pub async fn track_metrics(
     State(app_state): State<Arc<AppState>>,
     req: axum::extract::Request,
@@ -234,11 +259,17 @@ pub async fn track_metrics(
     // Calculate duration
     let duration = start.elapsed().as_secs_f64();
 
+    let tenant = response
+        .extensions()
+        .get::<Tenant>()
+        .map(|tenant| tenant.as_str().to_string())
+        .unwrap_or_default();
+
     // Record duration
     app_state
         .metrics
         .http_requests_duration_seconds
-        .with_label_values(&[&method, &path])
+        .with_label_values(&[&method, &path, &tenant])
         .observe(duration);
 
     // Record request count
@@ -246,7 +277,7 @@ pub async fn track_metrics(
     app_state
         .metrics
         .http_requests_total
-        .with_label_values(&[&method, &path, &status])
+        .with_label_values(&[&method, &path, &status, &tenant])
         .inc();
 
     response
@@ -268,37 +299,49 @@ pub async fn get_metrics(_state: State<Arc<AppState>>) -> impl IntoResponse {
 }
 
 // Helper functions to track template rendering time
-pub fn track_template_rendering(metrics: &AppMetrics, template_name: &str, duration: f64) {
+pub fn track_template_rendering(metrics: &AppMetrics, tenant: &Tenant, template_name: &str, duration: f64) {
     metrics
         .template_render_duration_seconds
-        .with_label_values(&[template_name])
+        .with_label_values(&[template_name, tenant.as_str()])
         .observe(duration);
 }
 
 // Helper function to track cache operations
-pub fn track_cache_hit(metrics: &AppMetrics, cache_name: &str) {
-    metrics.cache_hit_total.with_label_values(&[cache_name]).inc();
+pub fn track_cache_hit(metrics: &AppMetrics, tenant: &Tenant, cache_name: &str) {
+    metrics.cache_hit_total.with_label_values(&[cache_name, tenant.as_str()]).inc();
 }
 
-pub fn track_cache_miss(metrics: &AppMetrics, cache_name: &str) {
-    metrics.cache_miss_total.with_label_values(&[cache_name]).inc();
+pub fn track_cache_miss(metrics: &AppMetrics, tenant: &Tenant, cache_name: &str) {
+    metrics.cache_miss_total.with_label_values(&[cache_name, tenant.as_str()]).inc();
 }
 
 // Helper functions to track database operations
-pub fn track_database_query(metrics: &AppMetrics, operation: &str, status: &str, duration: f64) {
-    metrics.database_queries_total.with_label_values(&[operation, status]).inc();
+pub fn track_database_query(metrics: &AppMetrics, tenant: &Tenant, operation: &str, status: &str, duration: f64) {
+    metrics
+        .database_queries_total
+        .with_label_values(&[operation, status, tenant.as_str()])
+        .inc();
 
     metrics
         .database_query_duration_seconds
-        .with_label_values(&[operation])
+        .with_label_values(&[operation, tenant.as_str()])
         .observe(duration);
 }
 
 // Helper functions to track authentication events
-pub fn track_auth_success(metrics: &AppMetrics, username: &str) {
-    metrics.auth_success_total.with_label_values(&[username]).inc();
+/// Count a successful authentication; usernames are not a label, as every user would add a series
+pub fn track_auth_success(metrics: &AppMetrics, tenant: &Tenant, issuer: &str) {
+    metrics.auth_success_total.with_label_values(&[issuer, tenant.as_str()]).inc();
 }
 
-pub fn track_auth_failure(metrics: &AppMetrics, reason: &str) {
-    metrics.auth_failure_total.with_label_values(&[reason]).inc();
+pub fn track_auth_failure(metrics: &AppMetrics, tenant: &Tenant, reason: &str) {
+    metrics.auth_failure_total.with_label_values(&[reason, tenant.as_str()]).inc();
+}
+
+/// Count a request made with an API key; `outcome` is `allowed` or the reason it was refused
+pub fn track_api_key_request(metrics: &AppMetrics, tenant: &Tenant, key_id: &str, outcome: &str) {
+    metrics
+        .api_key_requests_total
+        .with_label_values(&[key_id, outcome, tenant.as_str()])
+        .inc();
 }
@@ -0,0 +1,125 @@
+use axum::{
+    extract::{ConnectInfo, MatchedPath, Request, State},
+    http::{HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::api_keys::ApiKeyRejection;
+use crate::database::ApiKeyScope;
+use crate::errors::{AppError, AuthError};
+use crate::metrics::track_api_key_request;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+
+/// Header carrying a service's API key
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Routes that accept an API key in place of a user's token
+const API_KEY_ROUTES: &[&str] = &["/api/username/{username}"];
+
+/// The API key a request was made with, left in the request extensions by `api_key_middleware`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKeyPrincipal {
+    pub id: String,
+    pub name: String,
+    pub scope: ApiKeyScope,
+}
+
+/// Authenticate requests to the `/api/username` routes carrying an `X-API-Key` header.
+///
+/// Runs inside `host_tenant_middleware`: the key must belong to the host's tenant. GET and
+/// HEAD need a read key, other methods a write key. Accepted keys stand in for the user
+/// `jwt_auth_middleware` and `require_admin_middleware` would otherwise ask for. The header
+/// is ignored on other routes. Address ranges are checked against the connection's peer,
+/// or the `X-Forwarded-For` client when the peer is one of `API_KEY_TRUSTED_PROXIES`.
+pub async fn api_key_middleware(State(app_state): State<Arc<AppState>>, mut request: Request, next: Next) -> Response {
+    let Some(secret) = request.headers().get(API_KEY_HEADER) else {
+        return next.run(request).await;
+    };
+    let accepted = request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|path| API_KEY_ROUTES.contains(&path.as_str()));
+    if !accepted {
+        return next.run(request).await;
+    }
+
+    let tenant = request.extensions().get::<Tenant>().cloned().unwrap_or_default();
+    let key = match secret.to_str() {
+        Ok(secret) => app_state.api_keys.find(&tenant, secret.trim()).await,
+        Err(_) => None,
+    };
+    let Some(key) = key else {
+        warn!(tenant = %tenant, path = %request.uri().path(), "Unknown API key");
+        return AuthError::InvalidApiKey.into_response();
+    };
+
+    let required = if matches!(*request.method(), Method::GET | Method::HEAD) {
+        ApiKeyScope::Read
+    } else {
+        ApiKeyScope::Write
+    };
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let forwarded_for = request.headers().get("x-forwarded-for").and_then(|value| value.to_str().ok());
+    let client_ip = app_state.api_keys.client_ip(peer, forwarded_for);
+    let outcome = app_state.api_keys.authorize(&key, required, client_ip);
+    track_api_key_request(
+        &app_state.metrics,
+        &tenant,
+        &key.id,
+        outcome.map_or_else(|rejection| rejection.as_str(), |()| "allowed"),
+    );
+
+    if let Err(rejection) = outcome {
+        warn!(
+            api_key_id = %key.id,
+            api_key_name = %key.name,
+            tenant = %tenant,
+            method = %request.method(),
+            path = %request.uri().path(),
+            client_ip = ?client_ip,
+            reason = %rejection.as_str(),
+            "API key request refused"
+        );
+        return rejection_response(rejection);
+    }
+
+    info!(
+        api_key_id = %key.id,
+        api_key_name = %key.name,
+        tenant = %tenant,
+        method = %request.method(),
+        path = %request.uri().path(),
+        client_ip = ?client_ip,
+        "API key request"
+    );
+    request.extensions_mut().insert(ApiKeyPrincipal {
+        id: key.id,
+        name: key.name,
+        scope: key.scope,
+    });
+    next.run(request).await
+}
+
+fn rejection_response(rejection: ApiKeyRejection) -> Response {
+    match rejection {
+        ApiKeyRejection::Quota => {
+            let mut response = (StatusCode::TOO_MANY_REQUESTS, "API key quota exceeded").into_response();
+            response.headers_mut().insert("Retry-After", HeaderValue::from_static("60"));
+            response
+        }
+        ApiKeyRejection::Scope | ApiKeyRejection::IpAddress => {
+            AppError::forbidden("API key not allowed for this request")
+                .localized("api-key-forbidden", [])
+                .auth_failure(rejection.as_str())
+                .into_response()
+        }
+    }
+}
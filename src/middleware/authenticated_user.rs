@@ -0,0 +1,252 @@
+use serde::Deserialize;
+use std::env;
+
+use crate::errors::AppError;
+use crate::middleware::jwt_auth::{Audience, Claims};
+use crate::validation::ValidatedUsername;
+
+/// The user a verified token was issued to, as handlers see it.
+///
+/// Built from the token's claims through the issuer's [`ClaimMapping`] by
+/// `jwt_auth_middleware`, which inserts it as a request extension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthenticatedUser {
+    /// Canonical username, from the mapped username claim
+    pub username: String,
+    /// The token's `sub`, which may be an opaque id rather than the username
+    pub subject: String,
+    pub issuer: String,
+    pub audience: Audience,
+    pub display_name: Option<String>,
+    pub roles: Vec<String>,
+    /// Raw value of the tenant claim; checked against the host by the tenancy configuration
+    pub tenant: Option<String>,
+    /// The token's `iat`, in seconds since the epoch
+    pub issued_at: u64,
+}
+
+/// Which claims provide the user's identity.
+///
+/// Claim names may be dotted paths into nested objects, such as `realm_access.roles`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClaimMapping {
+    #[serde(default = "default_username_claim")]
+    pub username: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Array of role names, or a space-separated string
+    #[serde(default)]
+    pub roles: Option<String>,
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+fn default_username_claim() -> String {
+    "sub".to_string()
+}
+
+impl Default for ClaimMapping {
+    fn default() -> Self {
+        Self {
+            username: default_username_claim(),
+            display_name: None,
+            roles: None,
+            tenant: None,
+        }
+    }
+}
+
+impl ClaimMapping {
+    /// Load the claim mapping from environment variables
+    ///
+    /// - `JWT_USERNAME_CLAIM`: claim holding the username (default: `sub`)
+    /// - `JWT_DISPLAY_NAME_CLAIM`: optional claim holding the display name
+    /// - `JWT_ROLES_CLAIM`: optional claim holding the user's roles
+    /// - `TENANT_CLAIM`: optional claim holding the tenant id
+    pub fn from_env() -> Self {
+        let claim = |var: &str| {
+            env::var(var)
+                .ok()
+                .map(|claim| claim.trim().to_string())
+                .filter(|claim| !claim.is_empty())
+        };
+
+        Self {
+            username: claim("JWT_USERNAME_CLAIM").unwrap_or_else(default_username_claim),
+            display_name: claim("JWT_DISPLAY_NAME_CLAIM"),
+            roles: claim("JWT_ROLES_CLAIM"),
+            tenant: claim("TENANT_CLAIM"),
+        }
+    }
+
+    /// The user described by verified claims; a missing or malformed mapped claim is rejected
+    pub fn user(&self, claims: &Claims) -> Result<AuthenticatedUser, AppError> {
+        let payload = serde_json::to_value(claims).unwrap_or_default();
+
+        let username = match claim_value(&payload, &self.username) {
+            Some(serde_json::Value::String(username)) => ValidatedUsername::existing(username.clone())
+                .map_err(|e| {
+                    invalid_claim(
+                        &self.username,
+                        format!("Token claim '{}' is not a valid username: {}", self.username, e.message),
+                    )
+                })?
+                .into_string(),
+            Some(_) => {
+                return Err(invalid_claim(
+                    &self.username,
+                    format!("Token claim '{}' must be a string", self.username),
+                ))
+            }
+            None => {
+                return Err(invalid_claim(
+                    &self.username,
+                    format!("Token has no '{}' username claim", self.username),
+                ))
+            }
+        };
+
+        let display_name = self
+            .display_name
+            .as_deref()
+            .map(|claim| optional_string(&payload, claim))
+            .transpose()?
+            .flatten();
+        let tenant = self
+            .tenant
+            .as_deref()
+            .map(|claim| optional_string(&payload, claim))
+            .transpose()?
+            .flatten();
+
+        let roles = match self.roles.as_deref() {
+            None => Vec::new(),
+            Some(claim) => match claim_value(&payload, claim) {
+                None | Some(serde_json::Value::Null) => Vec::new(),
+                Some(serde_json::Value::String(roles)) => roles.split_whitespace().map(str::to_string).collect(),
+                Some(serde_json::Value::Array(roles)) => roles
+                    .iter()
+                    .map(|role| role.as_str().map(str::to_string))
+                    .collect::<Option<_>>()
+                    .ok_or_else(|| invalid_claim(claim, format!("Token claim '{claim}' must list role names")))?,
+                Some(_) => {
+                    return Err(invalid_claim(claim, format!("Token claim '{claim}' must list role names")));
+                }
+            },
+        };
+
+        Ok(AuthenticatedUser {
+            username,
+            subject: claims.sub.clone(),
+            issuer: claims.iss.clone(),
+            audience: claims.aud.clone(),
+            display_name,
+            roles,
+            tenant,
+            issued_at: claims.iat as u64,
+        })
+    }
+}
+
+/// Value at a dotted claim path
+fn claim_value<'a>(payload: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
+    // A claim whose name itself contains dots (such as a URI) takes precedence
+    if let Some(value) = payload.get(path) {
+        return Some(value);
+    }
+    path.split('.').try_fold(payload, |value, key| value.get(key))
+}
+
+fn optional_string(payload: &serde_json::Value, claim: &str) -> Result<Option<String>, AppError> {
+    match claim_value(payload, claim) {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::String(value)) => Ok(Some(value.clone())),
+        Some(_) => Err(invalid_claim(claim, format!("Token claim '{claim}' must be a string"))),
+    }
+}
+
+fn invalid_claim(claim: &str, message: String) -> AppError {
+    tracing::warn!("Rejected token: {}", message);
+    AppError::forbidden(message)
+        .localized("token-claim-invalid", [("claim", claim.into())])
+        .auth_failure("token_claim_invalid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(payload: serde_json::Value) -> Claims {
+        let mut claims = serde_json::json!({
+            "sub": "5f0c7c1e-opaque",
+            "iat": 0,
+            "exp": 0,
+            "aud": "micro-frontend-service",
+            "iss": "test-auth-service",
+        });
+        claims.as_object_mut().unwrap().extend(payload.as_object().unwrap().clone());
+        serde_json::from_value(claims).unwrap()
+    }
+
+    fn mapping() -> ClaimMapping {
+        ClaimMapping {
+            username: "preferred_username".to_string(),
+            display_name: Some("name".to_string()),
+            roles: Some("realm_access.roles".to_string()),
+            tenant: Some("tenant".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_claim_mapping_extracts_user() {
+        let user = mapping()
+            .user(&claims(serde_json::json!({
+                "preferred_username": "Alice",
+                "name": "Alice Liddell",
+                "realm_access": { "roles": ["editor", "moderator"] },
+                "tenant": "brand-a",
+            })))
+            .unwrap();
+
+        assert_eq!(user.username, "alice");
+        assert_eq!(user.subject, "5f0c7c1e-opaque");
+        assert_eq!(user.display_name.as_deref(), Some("Alice Liddell"));
+        assert_eq!(user.roles, vec!["editor", "moderator"]);
+        assert_eq!(user.tenant.as_deref(), Some("brand-a"));
+
+        // Optional claims may be absent, and roles may be a space-separated string
+        let mapping = ClaimMapping {
+            roles: Some("scope".to_string()),
+            ..mapping()
+        };
+        let user = mapping
+            .user(&claims(
+                serde_json::json!({ "preferred_username": "alice", "scope": "editor admin" }),
+            ))
+            .unwrap();
+        assert_eq!(user.display_name, None);
+        assert_eq!(user.tenant, None);
+        assert_eq!(user.roles, vec!["editor", "admin"]);
+
+        // The default mapping keeps using sub
+        let user = ClaimMapping::default()
+            .user(&claims(serde_json::json!({ "preferred_username": "alice" })))
+            .unwrap();
+        assert_eq!(user.username, "5f0c7c1e-opaque");
+    }
+
+    #[test]
+    fn test_claim_mapping_rejects_invalid_claims() {
+        for payload in [
+            serde_json::json!({}),
+            serde_json::json!({ "preferred_username": 42 }),
+            serde_json::json!({ "preferred_username": "not a username!" }),
+            serde_json::json!({ "preferred_username": "alice", "name": ["Alice"] }),
+            serde_json::json!({ "preferred_username": "alice", "realm_access": { "roles": [1, 2] } }),
+        ] {
+            let error = mapping().user(&claims(payload.clone())).unwrap_err();
+            assert_eq!(error.code, crate::errors::ErrorCode::Forbidden, "{payload}");
+        }
+    }
+}
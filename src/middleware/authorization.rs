@@ -0,0 +1,38 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::database::ApiKeyScope;
+use crate::errors::AppError;
+use crate::middleware::{ApiKeyPrincipal, AuthenticatedUser};
+use crate::router::AppState;
+
+/// Only let users with an admin role (see `ADMIN_ROLES`) through.
+///
+/// Runs inside `jwt_auth_middleware`, which provides the authenticated user. Requests made
+/// with a write API key (see `api_key_middleware`) are let through too.
+pub async fn require_admin_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    if request
+        .extensions()
+        .get::<ApiKeyPrincipal>()
+        .is_some_and(|key| key.scope.allows(ApiKeyScope::Write))
+    {
+        return Ok(next.run(request).await);
+    }
+
+    let Some(user) = request.extensions().get::<AuthenticatedUser>() else {
+        return Err(AppError::forbidden("Admin role required")
+            .localized("admin-role-required", [])
+            .auth_failure("admin_role_required"));
+    };
+
+    app_state.authorization.require_admin(user)?;
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,145 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+
+use crate::errors::AppError;
+
+/// Header carrying the CSRF token that `GET /edit` embeds in the page
+pub const CSRF_TOKEN_HEADER: &str = "x-csrf-token";
+
+/// Where the request's token came from, left in the request extensions by `jwt_auth_middleware`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenSource {
+    /// `Authorization: Bearer`, which a cross-site form cannot send
+    Bearer,
+    /// The session cookie or the legacy `jwt_token` cookie, which browsers attach to any request
+    Cookie { csrf_token: String },
+}
+
+/// Reason a cookie-authenticated write was refused, left in the response extensions for
+/// `security_event_logging_middleware`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsrfViolation(pub &'static str);
+
+/// The CSRF token of a cookie session: a hash of the cookie's token, which a cross-site page
+/// can neither read (the cookie is HttpOnly) nor compute
+pub fn csrf_token(session_token: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(format!("csrf:{session_token}").as_bytes()))
+}
+
+/// Refuse state-changing requests authenticated by a cookie unless they come from this
+/// service's own pages: `Sec-Fetch-Site` and `Origin`, when sent, must be same-origin, and
+/// the `X-CSRF-Token` header must hold the session's CSRF token.
+///
+/// Runs inside `jwt_auth_middleware`. Bearer-authenticated requests are exempt.
+pub async fn csrf_protection_middleware(request: Request, next: Next) -> Response {
+    let Some(TokenSource::Cookie { csrf_token }) = request.extensions().get::<TokenSource>() else {
+        return next.run(request).await;
+    };
+    if is_safe_method(request.method()) {
+        return next.run(request).await;
+    }
+
+    match check_request(request.headers(), csrf_token) {
+        Ok(()) => next.run(request).await,
+        Err(reason) => {
+            let mut response = AppError::forbidden("CSRF check failed")
+                .localized("csrf-check-failed", [])
+                .auth_failure("csrf_violation")
+                .into_response();
+            response.extensions_mut().insert(CsrfViolation(reason));
+            response
+        }
+    }
+}
+
+fn is_safe_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+fn check_request(headers: &HeaderMap, csrf_token: &str) -> Result<(), &'static str> {
+    let header_value = |name| headers.get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(site) = header_value(header::HeaderName::from_static("sec-fetch-site")) {
+        if site != "same-origin" && site != "none" {
+            return Err("cross_site_request");
+        }
+    }
+    if let Some(origin) = header_value(header::ORIGIN) {
+        if !is_same_origin(origin, header_value(header::HOST)) {
+            return Err("origin_mismatch");
+        }
+    }
+
+    match header_value(header::HeaderName::from_static(CSRF_TOKEN_HEADER)) {
+        None => Err("missing_csrf_token"),
+        Some(token) if constant_time_eq(token.as_bytes(), csrf_token.as_bytes()) => Ok(()),
+        Some(_) => Err("invalid_csrf_token"),
+    }
+}
+
+/// Whether `origin` names the host the request was sent to (`null` and unparseable origins never do)
+fn is_same_origin(origin: &str, host: Option<&str>) -> bool {
+    let Some(host) = host else {
+        return false;
+    };
+    let Ok(origin) = url::Url::parse(origin) else {
+        return false;
+    };
+    let authority = match (origin.host_str(), origin.port()) {
+        (Some(origin_host), Some(port)) => format!("{origin_host}:{port}"),
+        (Some(origin_host), None) => origin_host.to_string(),
+        (None, _) => return false,
+    };
+    authority.eq_ignore_ascii_case(host)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn headers(values: &[(&'static str, &'static str)]) -> HeaderMap {
+        values
+            .iter()
+            .map(|(name, value)| (header::HeaderName::from_static(name), HeaderValue::from_static(value)))
+            .collect()
+    }
+
+    #[test]
+    fn test_csrf_checks() {
+        let token = csrf_token("abc.def.ghi");
+        assert_ne!(token, csrf_token("abc.def.ghj"));
+
+        let mut valid = headers(&[
+            ("host", "example.com:8080"),
+            ("origin", "https://example.com:8080"),
+            ("sec-fetch-site", "same-origin"),
+        ]);
+        assert_eq!(check_request(&valid, &token), Err("missing_csrf_token"));
+        valid.insert(CSRF_TOKEN_HEADER, HeaderValue::from_str(&token).unwrap());
+        assert_eq!(check_request(&valid, &token), Ok(()));
+        assert_eq!(check_request(&valid, &csrf_token("other")), Err("invalid_csrf_token"));
+
+        let mut cross_site = valid.clone();
+        cross_site.insert("sec-fetch-site", HeaderValue::from_static("cross-site"));
+        assert_eq!(check_request(&cross_site, &token), Err("cross_site_request"));
+
+        for origin in ["https://evil.example", "https://example.com", "null"] {
+            let mut other_origin = valid.clone();
+            other_origin.insert(header::ORIGIN, HeaderValue::from_static(origin));
+            assert_eq!(check_request(&other_origin, &token), Err("origin_mismatch"), "{origin}");
+        }
+        assert!(is_same_origin("https://Example.com", Some("example.com")));
+    }
+}
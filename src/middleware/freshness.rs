@@ -0,0 +1,43 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::errors::AuthError;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+
+/// Reject tokens older than the route's freshness requirement (see `TOKEN_FRESHNESS_ROUTES`).
+///
+/// Runs inside `jwt_auth_middleware`. The 401 carries a step-up challenge (RFC 9470) with
+/// the accepted `max_age`, which tells the host application to have the user sign in again.
+pub async fn token_freshness_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let max_age = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| app_state.freshness.max_age(request.method(), path.as_str()));
+    let (Some(max_age), Some(user)) = (max_age, request.extensions().get::<AuthenticatedUser>()) else {
+        return next.run(request).await;
+    };
+
+    let age = jsonwebtoken::get_current_timestamp().saturating_sub(user.issued_at);
+    if age <= max_age.as_secs() {
+        return next.run(request).await;
+    }
+
+    tracing::info!(
+        "Token of '{}' issued {}s ago is too old for {} {} (max {}s)",
+        user.username,
+        age,
+        request.method(),
+        request.uri().path(),
+        max_age.as_secs()
+    );
+    AuthError::TokenNotFresh { max_age: max_age.as_secs() }.into_response()
+}
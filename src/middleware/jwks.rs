@@ -0,0 +1,282 @@
+use jsonwebtoken::jwk::{Jwk, PublicKeyUse};
+use jsonwebtoken::DecodingKey;
+use serde::Deserialize;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Default time a fetched key set is trusted before it is refreshed
+pub const DEFAULT_JWKS_CACHE_SECONDS: u64 = 300;
+/// Default minimum time between two fetches of the key set
+pub const DEFAULT_JWKS_MIN_REFRESH_SECONDS: u64 = 30;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Verification keys published by an identity provider as a JWKS document.
+///
+/// Keys are selected by `kid`. The set is refetched once it is older than the cache
+/// lifetime, or when a token names a key the set does not contain, but never more than
+/// once per minimum refresh interval. A failed fetch keeps serving the last good set.
+pub struct JwksCache {
+    url: String,
+    client: reqwest::Client,
+    cache_ttl: Duration,
+    min_refresh_interval: Duration,
+    keys: RwLock<KeySet>,
+    /// Time of the last fetch attempt; held for the duration of a fetch so
+    /// concurrent requests for an unknown key trigger a single request
+    last_attempt: tokio::sync::Mutex<Option<Instant>>,
+}
+
+#[derive(Default)]
+struct KeySet {
+    keys: Vec<(Option<String>, DecodingKey)>,
+    fetched_at: Option<Instant>,
+}
+
+#[derive(Deserialize)]
+struct JwksDocument {
+    keys: Vec<serde_json::Value>,
+}
+
+impl KeySet {
+    /// Key named `kid`; a token without `kid` may only use a set holding a single key
+    fn find(&self, kid: Option<&str>) -> Option<DecodingKey> {
+        match kid {
+            Some(kid) => self
+                .keys
+                .iter()
+                .find(|(key_id, _)| key_id.as_deref() == Some(kid))
+                .map(|(_, key)| key.clone()),
+            None if self.keys.len() == 1 => Some(self.keys[0].1.clone()),
+            None => None,
+        }
+    }
+
+    fn is_fresh(&self, cache_ttl: Duration) -> bool {
+        self.fetched_at.is_some_and(|fetched_at| fetched_at.elapsed() < cache_ttl)
+    }
+}
+
+impl JwksCache {
+    pub fn new(url: impl Into<String>, cache_ttl: Duration, min_refresh_interval: Duration) -> Self {
+        let client = reqwest::Client::builder().timeout(FETCH_TIMEOUT).build().unwrap_or_default();
+
+        Self {
+            url: url.into(),
+            client,
+            cache_ttl,
+            min_refresh_interval,
+            keys: RwLock::new(KeySet::default()),
+            last_attempt: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether `other` fetches the same URL with the same cache settings
+    pub fn same_source(&self, other: &JwksCache) -> bool {
+        self.url == other.url
+            && self.cache_ttl == other.cache_ttl
+            && self.min_refresh_interval == other.min_refresh_interval
+    }
+
+    /// Verification key for a token header's `kid`, refreshing the set when needed
+    pub async fn key(&self, kid: Option<&str>) -> Option<DecodingKey> {
+        {
+            let keys = self.keys.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if keys.is_fresh(self.cache_ttl) {
+                if let Some(key) = keys.find(kid) {
+                    return Some(key);
+                }
+            }
+        }
+
+        self.refresh().await;
+
+        let keys = self.keys.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let key = keys.find(kid);
+        if key.is_none() {
+            debug!("No key with kid {:?} in JWKS from {}", kid, self.url);
+        }
+        key
+    }
+
+    /// Refetch the key set unless it was attempted within the minimum refresh interval
+    async fn refresh(&self) {
+        let mut last_attempt = self.last_attempt.lock().await;
+        if last_attempt.is_some_and(|attempt| attempt.elapsed() < self.min_refresh_interval) {
+            debug!("Skipping JWKS refresh from {}: rate limited", self.url);
+            return;
+        }
+        *last_attempt = Some(Instant::now());
+
+        match self.fetch().await {
+            Ok(keys) => {
+                info!("Loaded {} signing keys from {}", keys.len(), self.url);
+                let mut key_set = self.keys.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+                *key_set = KeySet { keys, fetched_at: Some(Instant::now()) };
+            }
+            Err(e) => warn!("Failed to fetch JWKS from {}, keeping the last good key set: {}", self.url, e),
+        }
+    }
+
+    async fn fetch(&self) -> anyhow::Result<Vec<(Option<String>, DecodingKey)>> {
+        let document: JwksDocument = self.client.get(&self.url).send().await?.error_for_status()?.json().await?;
+
+        // Skip keys this service cannot use rather than rejecting the whole set
+        let keys: Vec<_> = document
+            .keys
+            .into_iter()
+            .filter_map(|value| match serde_json::from_value::<Jwk>(value) {
+                Ok(jwk) if matches!(jwk.common.public_key_use, Some(PublicKeyUse::Encryption)) => None,
+                Ok(jwk) => match DecodingKey::from_jwk(&jwk) {
+                    Ok(key) => Some((jwk.common.key_id, key)),
+                    Err(e) => {
+                        warn!("Ignoring JWK {:?} from {}: {}", jwk.common.key_id, self.url, e);
+                        None
+                    }
+                },
+                Err(e) => {
+                    warn!("Ignoring unsupported JWK from {}: {}", self.url, e);
+                    None
+                }
+            })
+            .collect();
+
+        if keys.is_empty() {
+            anyhow::bail!("no usable signing keys");
+        }
+        Ok(keys)
+    }
+}
+
+impl std::fmt::Debug for JwksCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwksCache")
+            .field("url", &self.url)
+            .field("cache_ttl", &self.cache_ttl)
+            .field("min_refresh_interval", &self.min_refresh_interval)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::tests::test_keys::TestKey;
+    use axum::{extract::State, http::StatusCode, routing::get, Router};
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Local HTTP server serving a JWKS file, counting requests
+    pub(crate) struct JwksServer {
+        pub url: String,
+        pub file: PathBuf,
+        pub hits: Arc<AtomicUsize>,
+    }
+
+    impl JwksServer {
+        pub(crate) async fn start(file: PathBuf) -> Self {
+            let hits = Arc::new(AtomicUsize::new(0));
+            let app = Router::new()
+                .route(
+                    "/.well-known/jwks.json",
+                    get(|State((file, hits)): State<(PathBuf, Arc<AtomicUsize>)>| async move {
+                        hits.fetch_add(1, Ordering::SeqCst);
+                        tokio::fs::read_to_string(&file)
+                            .await
+                            .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+                    }),
+                )
+                .with_state((file.clone(), hits.clone()));
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let address = listener.local_addr().unwrap();
+            tokio::spawn(async move { axum::serve(listener, app).await });
+
+            Self {
+                url: format!("http://{address}/.well-known/jwks.json"),
+                file,
+                hits,
+            }
+        }
+
+        pub(crate) fn publish(&self, keys: &[&TestKey]) {
+            let jwks = serde_json::json!({ "keys": keys.iter().map(|key| key.jwk.clone()).collect::<Vec<_>>() });
+            std::fs::write(&self.file, jwks.to_string()).unwrap();
+        }
+
+        pub(crate) fn hits(&self) -> usize {
+            self.hits.load(Ordering::SeqCst)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jwks_selects_key_by_kid_and_refreshes_on_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let old_key = TestKey::generate(dir.path(), "old");
+        let new_key = TestKey::generate(dir.path(), "new");
+        let server = JwksServer::start(dir.path().join("jwks.json")).await;
+        server.publish(&[&old_key]);
+
+        let cache = JwksCache::new(&server.url, Duration::from_secs(300), Duration::ZERO);
+        assert!(cache.key(Some("old")).await.is_some());
+        assert!(cache.key(Some("old")).await.is_some());
+        assert_eq!(server.hits(), 1, "fresh keys are served from the cache");
+
+        // A single key may be used by tokens without kid
+        assert!(cache.key(None).await.is_some());
+
+        // The provider rotates: an unknown kid triggers a refresh
+        server.publish(&[&old_key, &new_key]);
+        assert!(cache.key(Some("new")).await.is_some());
+        assert_eq!(server.hits(), 2);
+        assert!(cache.key(None).await.is_none(), "kid is required once there are several keys");
+    }
+
+    #[tokio::test]
+    async fn test_jwks_refresh_is_rate_limited() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = TestKey::generate(dir.path(), "current");
+        let server = JwksServer::start(dir.path().join("jwks.json")).await;
+        server.publish(&[&key]);
+
+        let cache = JwksCache::new(&server.url, Duration::from_secs(300), Duration::from_secs(60));
+        assert!(cache.key(Some("current")).await.is_some());
+
+        // Tokens with made-up kids cannot make us hammer the identity provider
+        for _ in 0..5 {
+            assert!(cache.key(Some("unknown")).await.is_none());
+        }
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_jwks_keeps_last_good_set_when_fetch_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = TestKey::generate(dir.path(), "current");
+        let server = JwksServer::start(dir.path().join("jwks.json")).await;
+        server.publish(&[&key]);
+
+        // Every lookup finds the set stale and refetches it
+        let cache = JwksCache::new(&server.url, Duration::ZERO, Duration::ZERO);
+        assert!(cache.key(Some("current")).await.is_some());
+
+        std::fs::remove_file(&server.file).unwrap();
+        assert!(cache.key(Some("current")).await.is_some());
+
+        std::fs::write(&server.file, r#"{"keys": [{"kty": "unknown"}]}"#).unwrap();
+        assert!(cache.key(Some("current")).await.is_some());
+        assert_eq!(server.hits(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_jwks_unreachable_without_keys() {
+        let cache = JwksCache::new("http://127.0.0.1:9/jwks.json", Duration::from_secs(300), Duration::ZERO);
+        assert!(cache.key(Some("any")).await.is_none());
+    }
+}
@@ -1,44 +1,143 @@
 #![allow(clippy::uninlined_format_args)]
 
+use anyhow::{anyhow, bail, Context, Result};
 use axum::{
-    extract::Request,
-    http::{header::AUTHORIZATION, header::COOKIE, StatusCode},
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, header::COOKIE},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
 use base64::Engine as _;
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, TokenData, Validation};
 use serde::{Deserialize, Serialize};
-use std::env;
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use super::api_key::ApiKeyPrincipal;
+use super::authenticated_user::{AuthenticatedUser, ClaimMapping};
+use super::csrf::{csrf_token, TokenSource};
+use super::jwks::{JwksCache, DEFAULT_JWKS_CACHE_SECONDS, DEFAULT_JWKS_MIN_REFRESH_SECONDS};
+use crate::config::session::SessionConfig;
+use crate::errors::AuthError;
+use crate::router::AppState;
+use crate::tenant::Tenant;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
-    pub sub: String, // Subject (username)
-    pub iat: usize,  // Issued at
-    pub exp: usize,  // Expiration time
-    pub aud: String, // Audience
-    pub iss: String, // Issuer
+    pub sub: String,   // Subject (username)
+    pub iat: usize,    // Issued at
+    pub exp: usize,    // Expiration time
+    pub aud: Audience, // Audience
+    pub iss: String,   // Issuer
+    /// Token id, which lets a single token be revoked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// Any other claims, such as those named by the issuer's claim mapping
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
-pub struct JwtConfig {
-    pub public_key: DecodingKey,
-    pub validation: Validation,
+/// The `aud` claim, which the JWT spec allows as a single audience or an array of them
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum Audience {
+    One(String),
+    Many(Vec<String>),
 }
 
-impl std::fmt::Debug for JwtConfig {
+impl Audience {
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        match self {
+            Audience::One(audience) => std::slice::from_ref(audience).iter(),
+            Audience::Many(audiences) => audiences.iter(),
+        }
+        .map(String::as_str)
+    }
+}
+
+impl From<&str> for Audience {
+    fn from(audience: &str) -> Self {
+        Audience::One(audience.to_string())
+    }
+}
+
+impl std::fmt::Display for Audience {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("JwtConfig")
-            .field("public_key", &"<redacted>")
-            .field("validation", &self.validation)
-            .finish()
+        write!(f, "{}", self.iter().collect::<Vec<_>>().join(", "))
     }
 }
 
-impl JwtConfig {
-    pub fn from_env() -> Result<Self, Box<dyn std::error::Error>> {
-        let mut public_key_pem =
-            env::var("JWT_PUBLIC_KEY").map_err(|_| "JWT_PUBLIC_KEY environment variable not set")?;
+/// Signature algorithms accepted in `JWT_ALGORITHM`
+pub const SUPPORTED_ALGORITHMS: [&str; 6] = ["RS256", "RS384", "PS256", "ES256", "ES384", "EdDSA"];
+
+// DER encodings of the named curve OIDs in an EC public key
+const P256_OID: &[u8] = &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+const P384_OID: &[u8] = &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22];
+
+/// Parse a `JWT_ALGORITHM` value
+pub fn parse_algorithm(name: &str) -> Result<Algorithm> {
+    match name {
+        "RS256" => Ok(Algorithm::RS256),
+        "RS384" => Ok(Algorithm::RS384),
+        "PS256" => Ok(Algorithm::PS256),
+        "ES256" => Ok(Algorithm::ES256),
+        "ES384" => Ok(Algorithm::ES384),
+        "EdDSA" => Ok(Algorithm::EdDSA),
+        _ => bail!(
+            "Unsupported JWT algorithm: {name} (expected one of {})",
+            SUPPORTED_ALGORITHMS.join(", ")
+        ),
+    }
+}
+
+/// Algorithm of `JWT_ALGORITHM`, RS256 by default
+pub fn algorithm_from_env() -> Result<Algorithm> {
+    parse_algorithm(&env::var("JWT_ALGORITHM").unwrap_or_else(|_| "RS256".to_string()))
+}
+
+/// Where token signatures are verified from
+pub enum VerificationKeys {
+    /// A single PEM key, from a key file or `JWT_PUBLIC_KEY`
+    Static(DecodingKey),
+    /// Keys published at a JWKS URL, selected by the token's `kid`
+    Jwks(Arc<JwksCache>),
+}
+
+impl VerificationKeys {
+    /// Keys published at `url`, cached for `JWT_JWKS_CACHE_SECONDS`
+    pub fn jwks(url: &str) -> Self {
+        let cache_seconds = env::var("JWT_JWKS_CACHE_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_JWKS_CACHE_SECONDS);
+        let min_refresh_seconds = env::var("JWT_JWKS_MIN_REFRESH_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_JWKS_MIN_REFRESH_SECONDS);
+
+        VerificationKeys::Jwks(Arc::new(JwksCache::new(
+            url,
+            Duration::from_secs(cache_seconds),
+            Duration::from_secs(min_refresh_seconds),
+        )))
+    }
+
+    /// A single public key read from a PEM file
+    pub fn from_pem_file(path: &Path, algorithm: Algorithm) -> Result<Self> {
+        let public_key_pem = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read JWT public key {}", path.display()))?;
+        Self::from_pem(&public_key_pem, algorithm)
+    }
+
+    /// A single public key in PEM format, as found in `.env` files, of the type `algorithm` needs
+    pub fn from_pem(public_key_pem: &str, algorithm: Algorithm) -> Result<Self> {
+        let mut public_key_pem = public_key_pem.trim().to_string();
 
         // Remove surrounding single or double quotes if present
         if (public_key_pem.starts_with('"') && public_key_pem.ends_with('"'))
@@ -54,101 +153,511 @@ impl JwtConfig {
 
         tracing::debug!("Processing public key PEM ({} chars): {}", public_key_pem.len(), public_key_pem);
 
-        let algorithm = env::var("JWT_ALGORITHM").unwrap_or_else(|_| "RS256".to_string());
-        let audience = env::var("JWT_AUDIENCE").unwrap_or_else(|_| "micro-frontend-service".to_string());
-        let issuer = env::var("JWT_ISSUER").unwrap_or_else(|_| "test-auth-service".to_string());
+        let pem = public_key_pem.as_bytes();
+        let (public_key, key_type) = match algorithm {
+            Algorithm::RS256 | Algorithm::RS384 | Algorithm::PS256 => (DecodingKey::from_rsa_pem(pem), "an RSA"),
+            Algorithm::ES256 => (ec_key_on_curve(&public_key_pem, P256_OID), "a P-256 EC"),
+            Algorithm::ES384 => (ec_key_on_curve(&public_key_pem, P384_OID), "a P-384 EC"),
+            Algorithm::EdDSA => (DecodingKey::from_ed_pem(pem), "an Ed25519"),
+            _ => bail!("Unsupported JWT algorithm: {algorithm:?}"),
+        };
+        let public_key = public_key
+            .with_context(|| format!("Failed to parse JWT public key: {algorithm:?} requires {key_type} public key"))?;
 
-        let public_key = DecodingKey::from_rsa_pem(public_key_pem.as_bytes())
-            .map_err(|e| format!("Failed to parse JWT public key: {e}"))?;
+        tracing::debug!("Successfully parsed {:?} public key", algorithm);
 
-        tracing::debug!("Successfully parsed RSA public key");
+        Ok(VerificationKeys::Static(public_key))
+    }
+}
 
-        let mut validation = Validation::new(match algorithm.as_str() {
-            "RS256" => Algorithm::RS256,
-            "ES256" => Algorithm::ES256,
-            _ => return Err(format!("Unsupported JWT algorithm: {algorithm}").into()),
-        });
+/// EC public key, rejected unless it is on the curve whose OID is `curve`
+fn ec_key_on_curve(public_key_pem: &str, curve: &[u8]) -> jsonwebtoken::errors::Result<DecodingKey> {
+    let key = DecodingKey::from_ec_pem(public_key_pem.as_bytes())?;
+
+    let der: String = public_key_pem
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .map(str::trim)
+        .collect();
+    let der = STANDARD.decode(der).unwrap_or_default();
+    if !der.windows(curve.len()).any(|window| window == curve) {
+        return Err(jsonwebtoken::errors::ErrorKind::InvalidEcdsaKey.into());
+    }
+
+    Ok(key)
+}
+
+/// A trusted token issuer, with the keys, algorithm and audiences its tokens are verified with
+pub struct IssuerConfig {
+    pub issuer: String,
+    pub keys: VerificationKeys,
+    pub validation: Validation,
+    /// Key file the keys were read from, watched for rotation
+    pub key_file: Option<PathBuf>,
+    /// Claims providing the user's identity
+    pub claims: ClaimMapping,
+    /// Oldest accepted token, by its `iat` claim
+    pub max_age: Duration,
+}
 
-        validation.set_audience(&[audience]);
+impl IssuerConfig {
+    /// Accept `algorithm` signatures by `keys` on tokens from `issuer` for any of `audiences`
+    ///
+    /// Fails if `JWT_MAX_AGE_SECONDS` or `JWT_CLOCK_SKEW_SECONDS` is not a number of seconds.
+    pub fn new(issuer: &str, keys: VerificationKeys, algorithm: Algorithm, audiences: &[String]) -> Result<Self> {
+        let mut validation = Validation::new(algorithm);
+
+        validation.set_audience(audiences);
         validation.set_issuer(&[issuer]);
 
         // Configure validation parameters
-        let _max_age = env::var("JWT_MAX_AGE_SECONDS")
-            .unwrap_or_else(|_| "3600".to_string())
-            .parse::<u64>()
-            .unwrap_or(3600);
+        let max_age = seconds_from_env("JWT_MAX_AGE_SECONDS", 3600)?;
+        validation.leeway = seconds_from_env("JWT_CLOCK_SKEW_SECONDS", 60)?;
+
+        Ok(Self {
+            issuer: issuer.to_string(),
+            keys,
+            validation,
+            key_file: None,
+            claims: ClaimMapping::from_env(),
+            max_age: Duration::from_secs(max_age),
+        })
+    }
+
+    /// Verify a token of this issuer
+    async fn decode(&self, token: &str) -> Result<TokenData<Claims>, TokenError> {
+        let token_data = match &self.keys {
+            VerificationKeys::Static(key) => {
+                decode::<Claims>(token, key, &self.validation).map_err(TokenError::Invalid)?
+            }
+            VerificationKeys::Jwks(cache) => {
+                let kid = decode_header(token).map_err(TokenError::Invalid)?.kid;
+                let key = cache
+                    .key(kid.as_deref())
+                    .await
+                    .ok_or_else(|| TokenError::UnknownKey(kid.clone()))?;
+                decode::<Claims>(token, &key, &self.validation).map_err(TokenError::Invalid)?
+            }
+        };
+
+        self.check_issued_at(token_data.claims.iat as u64, jsonwebtoken::get_current_timestamp())?;
+        Ok(token_data)
+    }
+
+    /// Reject tokens older than the maximum age, or issued in the future, beyond the clock skew leeway
+    fn check_issued_at(&self, issued_at: u64, now: u64) -> Result<(), TokenError> {
+        if issued_at > now + self.validation.leeway {
+            return Err(TokenError::IssuedInFuture);
+        }
+        if now.saturating_sub(issued_at) > self.max_age.as_secs() + self.validation.leeway {
+            return Err(TokenError::TooOld);
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for IssuerConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("IssuerConfig");
+        debug.field("issuer", &self.issuer);
+        match &self.keys {
+            VerificationKeys::Static(_) => debug.field("public_key", &"<redacted>"),
+            VerificationKeys::Jwks(cache) => debug.field("jwks_url", &cache.url()),
+        };
+        debug
+            .field("validation", &self.validation)
+            .field("claims", &self.claims)
+            .field("max_age", &self.max_age)
+            .finish()
+    }
+}
+
+/// Seconds in `var`, or `default` when it is unset
+fn seconds_from_env(var: &str, default: u64) -> Result<u64> {
+    match env::var(var) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| anyhow!("{var} must be a number of seconds, got: {value}")),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Issuer entry of `JWT_ISSUERS_FILE` (JSON array)
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IssuerSettings {
+    issuer: String,
+    audiences: Vec<String>,
+    #[serde(default = "default_algorithm")]
+    algorithm: String,
+    jwks_url: Option<String>,
+    public_key_file: Option<PathBuf>,
+    public_key: Option<String>,
+    /// Overrides the claim mapping of the `JWT_*_CLAIM` variables
+    claims: Option<ClaimMapping>,
+    /// Overrides `JWT_MAX_AGE_SECONDS`
+    max_age_seconds: Option<u64>,
+}
+
+fn default_algorithm() -> String {
+    "RS256".to_string()
+}
+
+impl IssuerSettings {
+    fn into_config(self) -> Result<IssuerConfig> {
+        let algorithm = parse_algorithm(&self.algorithm)?;
+        if self.audiences.is_empty() {
+            bail!("Issuer {} must list at least one audience", self.issuer);
+        }
+
+        let (keys, key_file) = match (self.jwks_url, self.public_key_file, self.public_key) {
+            (Some(url), None, None) => (VerificationKeys::jwks(&url), None),
+            (None, Some(path), None) => (VerificationKeys::from_pem_file(&path, algorithm)?, Some(path)),
+            (None, None, Some(pem)) => (VerificationKeys::from_pem(&pem, algorithm)?, None),
+            _ => bail!(
+                "Issuer {} must have exactly one of jwks_url, public_key_file or public_key",
+                self.issuer
+            ),
+        };
+
+        let mut config = IssuerConfig::new(&self.issuer, keys, algorithm, &self.audiences)?;
+        config.key_file = key_file;
+        if let Some(claims) = self.claims {
+            config.claims = claims;
+        }
+        if let Some(max_age) = self.max_age_seconds {
+            config.max_age = Duration::from_secs(max_age);
+        }
+        Ok(config)
+    }
+}
+
+pub struct JwtConfig {
+    /// Trusted issuers; a token is verified by the one named in its `iss` claim
+    pub issuers: Vec<IssuerConfig>,
+    /// `JWT_ISSUERS_FILE`, watched for changes
+    pub issuers_file: Option<PathBuf>,
+}
+
+/// Why a token was rejected
+#[derive(Debug)]
+pub enum TokenError {
+    /// The token is not three base64url segments with a JSON payload
+    Malformed,
+    /// The token's `iss` is not a trusted issuer
+    UnknownIssuer(Option<String>),
+    /// The token names a key the JWKS does not contain, even after a refresh
+    UnknownKey(Option<String>),
+    /// The token's `iat` is older than the issuer's maximum token age
+    TooOld,
+    /// The token's `iat` is later than now, beyond the clock skew leeway
+    IssuedInFuture,
+    Invalid(jsonwebtoken::errors::Error),
+}
+
+impl std::fmt::Display for TokenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenError::Malformed => write!(f, "token is not a JWT"),
+            TokenError::UnknownIssuer(Some(issuer)) => write!(f, "untrusted issuer '{issuer}'"),
+            TokenError::UnknownIssuer(None) => write!(f, "token has no issuer"),
+            TokenError::UnknownKey(Some(kid)) => write!(f, "unknown signing key '{kid}'"),
+            TokenError::UnknownKey(None) => write!(f, "token has no kid and the key set holds several keys"),
+            TokenError::TooOld => write!(f, "token is older than the maximum token age"),
+            TokenError::IssuedInFuture => write!(f, "token is issued in the future"),
+            TokenError::Invalid(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl TokenError {
+    /// The error a request with this token is refused with
+    pub fn auth_error(&self) -> AuthError {
+        use jsonwebtoken::errors::ErrorKind;
+
+        match self {
+            TokenError::Malformed => AuthError::MalformedToken,
+            TokenError::UnknownIssuer(_) => AuthError::InvalidIssuer,
+            TokenError::UnknownKey(_) => AuthError::InvalidSignature,
+            TokenError::TooOld => AuthError::TokenTooOld,
+            TokenError::IssuedInFuture => AuthError::TokenIssuedInFuture,
+            TokenError::Invalid(e) => match e.kind() {
+                ErrorKind::InvalidToken | ErrorKind::Base64(_) | ErrorKind::Json(_) | ErrorKind::Utf8(_) => {
+                    AuthError::MalformedToken
+                }
+                ErrorKind::ExpiredSignature => AuthError::TokenExpired,
+                ErrorKind::InvalidSignature | ErrorKind::InvalidAlgorithm => AuthError::InvalidSignature,
+                ErrorKind::InvalidAudience => AuthError::InvalidAudience,
+                ErrorKind::InvalidIssuer => AuthError::InvalidIssuer,
+                _ => AuthError::InvalidToken,
+            },
+        }
+    }
+}
+
+impl std::fmt::Debug for JwtConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtConfig").field("issuers", &self.issuers).finish()
+    }
+}
+
+impl JwtConfig {
+    /// Trusted issuers of `JWT_ISSUERS_FILE`, or the single issuer described by the `JWT_*` variables
+    pub fn from_env() -> Result<Self> {
+        if let Ok(path) = env::var("JWT_ISSUERS_FILE") {
+            return Self::from_file(Path::new(&path));
+        }
+
+        let algorithm = algorithm_from_env()?;
+        let issuer = env::var("JWT_ISSUER").unwrap_or_else(|_| "test-auth-service".to_string());
+        let audiences: Vec<String> = env::var("JWT_AUDIENCE")
+            .unwrap_or_else(|_| "micro-frontend-service".to_string())
+            .split(',')
+            .map(|audience| audience.trim().to_string())
+            .filter(|audience| !audience.is_empty())
+            .collect();
+
+        // Keys from `JWT_JWKS_URL`, `JWT_PUBLIC_KEY_FILE` or `JWT_PUBLIC_KEY`, in that order
+        let key_file = env::var("JWT_PUBLIC_KEY_FILE").ok().map(PathBuf::from);
+        let keys = match (env::var("JWT_JWKS_URL"), &key_file) {
+            (Ok(url), _) => VerificationKeys::jwks(&url),
+            (Err(_), Some(path)) => VerificationKeys::from_pem_file(path, algorithm)?,
+            (Err(_), None) => VerificationKeys::from_pem(
+                &env::var("JWT_PUBLIC_KEY")
+                    .map_err(|_| anyhow!("None of JWT_JWKS_URL, JWT_PUBLIC_KEY_FILE or JWT_PUBLIC_KEY is set"))?,
+                algorithm,
+            )?,
+        };
+
+        let mut config = IssuerConfig::new(&issuer, keys, algorithm, &audiences)?;
+        config.key_file = key_file.filter(|_| env::var("JWT_JWKS_URL").is_err());
+        Ok(Self::new(vec![config]))
+    }
+
+    /// Trusted issuers listed in a JSON file
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read JWT issuers {}", path.display()))?;
+        let settings: Vec<IssuerSettings> =
+            serde_json::from_str(&content).with_context(|| format!("Invalid JWT issuers file {}", path.display()))?;
+        if settings.is_empty() {
+            bail!("JWT issuers file {} lists no issuers", path.display());
+        }
+
+        let mut issuers: Vec<IssuerConfig> = Vec::with_capacity(settings.len());
+        for settings in settings {
+            if issuers.iter().any(|issuer| issuer.issuer == settings.issuer) {
+                bail!("Issuer {} is listed twice", settings.issuer);
+            }
+            issuers.push(settings.into_config()?);
+        }
 
-        let clock_skew = env::var("JWT_CLOCK_SKEW_SECONDS")
-            .unwrap_or_else(|_| "60".to_string())
-            .parse::<u64>()
-            .unwrap_or(60);
+        Ok(Self {
+            issuers,
+            issuers_file: Some(path.to_path_buf()),
+        })
+    }
+
+    pub fn new(issuers: Vec<IssuerConfig>) -> Self {
+        Self { issuers, issuers_file: None }
+    }
 
-        validation.leeway = clock_skew;
+    /// Files the configuration was read from, reloaded when they change
+    pub fn watched_files(&self) -> Vec<PathBuf> {
+        self.issuers_file
+            .iter()
+            .chain(self.issuers.iter().filter_map(|issuer| issuer.key_file.as_ref()))
+            .cloned()
+            .collect()
+    }
+
+    /// Verify a token's signature and claims with the configuration of its issuer
+    pub async fn decode(&self, token: &str) -> Result<TokenData<Claims>, TokenError> {
+        let mut claims = unverified_claims(token).ok_or(TokenError::Malformed)?;
+        let issuer = claims.remove("iss").and_then(|issuer| issuer.as_str().map(str::to_string));
+        let config = issuer
+            .as_deref()
+            .and_then(|issuer| self.issuer(issuer))
+            .ok_or(TokenError::UnknownIssuer(issuer.clone()))?;
+        config.decode(token).await
+    }
 
-        Ok(JwtConfig { public_key, validation })
+    /// Configuration of a trusted issuer
+    pub fn issuer(&self, issuer: &str) -> Option<&IssuerConfig> {
+        self.issuers.iter().find(|config| config.issuer == issuer)
     }
 }
 
-pub async fn jwt_auth_middleware(mut request: Request, next: Next) -> Result<Response, StatusCode> {
+/// The claims of a token's payload, read before its signature is verified; `None` if it is not a JWT
+fn unverified_claims(token: &str) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let mut segments = token.split('.');
+    let (Some(_), Some(payload), Some(_), None) = (segments.next(), segments.next(), segments.next(), segments.next())
+    else {
+        return None;
+    };
+    serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()
+}
+
+type JwtConfigLoader = dyn Fn() -> Result<JwtConfig> + Send + Sync;
+
+/// The JWT configuration shared by the authentication middleware and the debug handlers.
+///
+/// It is built once at startup, and [`JwtVerifier::reload`] swaps in a freshly loaded
+/// configuration; an invalid one is rejected and the current configuration stays in use.
+#[derive(Clone)]
+pub struct JwtVerifier {
+    config: Arc<RwLock<Arc<JwtConfig>>>,
+    loader: Arc<JwtConfigLoader>,
+}
+
+impl JwtVerifier {
+    /// Build the configuration with `loader`, which [`JwtVerifier::reload`] calls again
+    pub fn new(loader: impl Fn() -> Result<JwtConfig> + Send + Sync + 'static) -> Result<Self> {
+        let config = loader()?;
+        Ok(Self {
+            config: Arc::new(RwLock::new(Arc::new(config))),
+            loader: Arc::new(loader),
+        })
+    }
+
+    /// Verifier configured by the environment
+    pub fn from_env() -> Result<Self> {
+        Self::new(JwtConfig::from_env)
+    }
+
+    /// Verifier rejecting every token, for tests that do not authenticate
+    #[cfg(test)]
+    pub fn new_for_tests() -> Self {
+        Self::new(|| Ok(JwtConfig::new(Vec::new()))).unwrap()
+    }
+
+    /// The current configuration
+    pub fn config(&self) -> Arc<JwtConfig> {
+        self.config.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    /// Load the configuration again, such as a rotated key file or a new JWKS URL.
+    ///
+    /// Issuers whose JWKS source is unchanged keep their cache, so the last good key set
+    /// survives the reload and is not fetched again.
+    pub fn reload(&self) -> Result<()> {
+        let mut config = (self.loader)()?;
+        let current = self.config();
+        for issuer in &mut config.issuers {
+            let VerificationKeys::Jwks(cache) = &issuer.keys else {
+                continue;
+            };
+            let existing = current.issuer(&issuer.issuer).and_then(|existing| match &existing.keys {
+                VerificationKeys::Jwks(existing) if existing.same_source(cache) => Some(existing.clone()),
+                _ => None,
+            });
+            if let Some(existing) = existing {
+                issuer.keys = VerificationKeys::Jwks(existing);
+            }
+        }
+
+        tracing::info!("JWT configuration reloaded: {:?}", config);
+        *self.config.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(config);
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for JwtVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JwtVerifier").field("config", &self.config()).finish()
+    }
+}
+
+/// Verify the request's token and insert the [`AuthenticatedUser`](super::AuthenticatedUser) it was issued to.
+///
+/// Missing and invalid tokens are rejected with an [`AuthError`]; a valid token whose mapped
+/// claims do not describe a usable identity is rejected with `403 Forbidden`. The user is also
+/// left in the response extensions, where `auth_metrics_middleware` counts the success.
+pub async fn jwt_auth_middleware(
+    State(app_state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, Response> {
     tracing::info!("JWT authentication middleware started");
 
+    // Services authenticated by `api_key_middleware` have no user token
+    if request.extensions().get::<ApiKeyPrincipal>().is_some() {
+        return Ok(next.run(request).await);
+    }
+
     // Try to extract JWT token from multiple sources
-    let token = extract_jwt_token(&request).ok_or_else(|| {
+    let (token, source) = extract_jwt_token(&request, &app_state.session).ok_or_else(|| {
         tracing::info!("No JWT token found in request");
-        StatusCode::UNAUTHORIZED
+        AuthError::MissingToken.into_response()
     })?;
 
     tracing::info!("JWT token found, length: {}, starting validation", token.len());
 
+    let host_tenant = request.extensions().get::<Tenant>().cloned().unwrap_or_default();
+    let (user, _) = authenticate(&app_state, &host_tenant, &token).await?;
+    request.extensions_mut().insert(user.clone());
+    request.extensions_mut().insert(source);
+
+    let mut response = next.run(request).await;
+    response.extensions_mut().insert(user);
+    Ok(response)
+}
+
+/// Verify `token`, used on a host of `host_tenant`, and map its claims to the user it was issued
+/// to, with the verified claims.
+///
+/// Revocations are checked in the tenant the request resolves to (see `TENANT_CLAIM`). Errors
+/// are the responses `jwt_auth_middleware` rejects the request with: an [`AuthError`] for a
+/// token that does not verify or is revoked, `403 Forbidden` for unusable claims.
+pub async fn authenticate(
+    app_state: &AppState,
+    host_tenant: &Tenant,
+    token: &str,
+) -> Result<(AuthenticatedUser, Claims), Response> {
     // Validate JWT token
-    let jwt_config = JwtConfig::from_env().map_err(|e| {
-        tracing::error!("JWT config error: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let jwt_config = app_state.jwt.config();
 
+    tracing::info!("JWT config loaded, {} trusted issuers", jwt_config.issuers.len());
+
+    let token_data = jwt_config.decode(token).await.map_err(|error| {
+        let auth_error = error.auth_error();
+        tracing::warn!("JWT validation failed: {} ({})", error, auth_error.reason());
+        auth_error.into_response()
+    })?;
     tracing::info!(
-        "JWT config loaded, algorithm: {:?}, leeway: {}",
-        jwt_config.validation.algorithms,
-        jwt_config.validation.leeway
+        "JWT token validated successfully. Subject: {}, Expiry: {}, Issuer: {:?}",
+        token_data.claims.sub,
+        token_data.claims.exp,
+        token_data.claims.iss
     );
 
-    match decode::<Claims>(&token, &jwt_config.public_key, &jwt_config.validation) {
-        Ok(token_data) => {
-            tracing::info!(
-                "JWT token validated successfully. Subject: {}, Expiry: {}, Issuer: {:?}",
-                token_data.claims.sub,
-                token_data.claims.exp,
-                token_data.claims.iss
-            );
-            request.extensions_mut().insert(token_data.claims);
-            Ok(next.run(request).await)
-        }
-        Err(e) => {
-            tracing::error!("JWT validation failed: {}", e);
-
-            // Log extra information for debugging JWT issues
-            match &e.kind() {
-                jsonwebtoken::errors::ErrorKind::ExpiredSignature => {
-                    tracing::error!("JWT token has expired");
-                }
-                jsonwebtoken::errors::ErrorKind::InvalidSignature => {
-                    tracing::error!("JWT token has invalid signature - check public key");
-                }
-                jsonwebtoken::errors::ErrorKind::InvalidToken => {
-                    tracing::error!("JWT token is invalid");
-                }
-                other => {
-                    tracing::error!("JWT error kind: {:?}", other);
-                }
-            }
-
-            // We return a basic 401 to avoid leaking too much info to clients
-            Err(StatusCode::UNAUTHORIZED)
-        }
+    let mapping = jwt_config
+        .issuer(&token_data.claims.iss)
+        .map(|issuer| &issuer.claims)
+        .ok_or_else(|| AuthError::InvalidIssuer.into_response())?;
+    let user = mapping.user(&token_data.claims).map_err(IntoResponse::into_response)?;
+
+    let tenant = app_state
+        .tenancy
+        .tenant_for_user(&user, host_tenant.clone())
+        .map_err(IntoResponse::into_response)?;
+    let claims = &token_data.claims;
+    if app_state
+        .revocation
+        .is_revoked(&tenant, &claims.iss, claims.jti.as_deref(), &claims.sub, claims.iat as i64)
+        .await
+    {
+        tracing::warn!("Rejected revoked token for subject {} (jti: {:?})", claims.sub, claims.jti);
+        return Err(AuthError::TokenRevoked.into_response());
     }
+
+    Ok((user, token_data.claims))
 }
 
-fn extract_jwt_token(request: &Request) -> Option<String> {
+/// The request's token and where it came from: the session cookie (see `POST /auth/session`) when
+/// present, otherwise the first unexpired one of the `Authorization` bearer token and the legacy
+/// `jwt_token` cookie
+fn extract_jwt_token(request: &Request, session: &SessionConfig) -> Option<(String, TokenSource)> {
     tracing::debug!("Extracting JWT token from request");
 
     // Define a function to validate token expiry before using it
@@ -180,38 +689,39 @@ fn extract_jwt_token(request: &Request) -> Option<String> {
         true
     };
 
+    // 1. Prefer the HttpOnly session cookie, which scripts cannot read or replace
+    if let Some(token) = session.token(request.headers()) {
+        tracing::info!("Found JWT token in session cookie, length: {}", token.len());
+        return Some((token.to_string(), cookie_source(token)));
+    }
+
     // Collect all tokens from different sources
     let mut potential_tokens = Vec::new();
 
-    // 1. Check for jwt_token cookie
-    if let Some(cookie_header) = request.headers().get(COOKIE).and_then(|header| header.to_str().ok()) {
-        tracing::info!("Found cookies header: {}", cookie_header);
+    // 2. Check Authorization header (Bearer token)
+    if let Some(auth_header) = request.headers().get(AUTHORIZATION).and_then(|header| header.to_str().ok()) {
+        if let Some(token) = auth_header.strip_prefix("Bearer ") {
+            tracing::info!("Found JWT token in Authorization header, length: {}", token.len());
+            potential_tokens.push((token.to_string(), TokenSource::Bearer));
+        }
+    }
 
+    // 3. Check for the legacy jwt_token cookie
+    if let Some(cookie_header) = request.headers().get(COOKIE).and_then(|header| header.to_str().ok()) {
         for cookie_part in cookie_header.split(';') {
             let cookie_part = cookie_part.trim();
-            tracing::debug!("Processing cookie part: {}", cookie_part);
-
             if let Some(token) = cookie_part.strip_prefix("jwt_token=") {
                 tracing::info!("Found JWT token in cookie, length: {}", token.len());
-                potential_tokens.push(token.to_string());
+                potential_tokens.push((token.to_string(), cookie_source(token)));
             }
         }
     }
 
-    // 2. Check Authorization header (Bearer token)
-    if let Some(auth_header) = request.headers().get(AUTHORIZATION).and_then(|header| header.to_str().ok()) {
-        tracing::debug!("Found Authorization header: {}", auth_header);
-        if let Some(token) = auth_header.strip_prefix("Bearer ") {
-            tracing::info!("Found JWT token in Authorization header, length: {}", token.len());
-            potential_tokens.push(token.to_string());
-        }
-    }
-
     // Return the first valid token, prioritizing non-expired tokens
-    for token in &potential_tokens {
+    for (token, source) in &potential_tokens {
         if is_valid_token(token) {
             tracing::info!("Using valid, non-expired token of length {}", token.len());
-            return Some(token.clone());
+            return Some((token.clone(), source.clone()));
         }
     }
 
@@ -225,6 +735,10 @@ fn extract_jwt_token(request: &Request) -> Option<String> {
     None
 }
 
+fn cookie_source(token: &str) -> TokenSource {
+    TokenSource::Cookie { csrf_token: csrf_token(token) }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,4 +789,171 @@ mod tests {
             env::remove_var("JWT_ISSUER");
         }
     }
+
+    #[tokio::test]
+    async fn test_jwt_config_verifies_with_jwks() {
+        use crate::middleware::jwks::tests::JwksServer;
+        use crate::tests::test_keys::TestKey;
+
+        let dir = tempfile::tempdir().unwrap();
+        let key = TestKey::generate(dir.path(), "signing");
+        let other = TestKey::generate(dir.path(), "other");
+        let server = JwksServer::start(dir.path().join("jwks.json")).await;
+        server.publish(&[&key]);
+
+        let config = JwtConfig::new(vec![IssuerConfig::new(
+            "test-auth-service",
+            VerificationKeys::Jwks(Arc::new(JwksCache::new(&server.url, Duration::from_secs(300), Duration::ZERO))),
+            Algorithm::RS256,
+            &["micro-frontend-service".to_string()],
+        )
+        .unwrap()]);
+
+        let now = chrono::Utc::now().timestamp();
+        let claims = serde_json::json!({
+            "sub": "admin",
+            "iat": now,
+            "exp": now + 3600,
+            "aud": "micro-frontend-service",
+            "iss": "test-auth-service",
+        });
+
+        let token_data = config.decode(&key.sign(&claims)).await.unwrap();
+        assert_eq!(token_data.claims.sub, "admin");
+
+        // A key missing from the published set is rejected, not guessed
+        assert!(matches!(
+            config.decode(&other.sign(&claims)).await,
+            Err(TokenError::UnknownKey(Some(kid))) if kid == "other"
+        ));
+
+        // Once the provider publishes it, it verifies without a restart
+        server.publish(&[&key, &other]);
+        assert!(config.decode(&other.sign(&claims)).await.is_ok());
+        assert!(format!("{config:?}").contains(&server.url));
+    }
+
+    #[test]
+    fn test_seconds_from_env_rejects_malformed_values() {
+        env::remove_var("TEST_JWT_SECONDS");
+        assert_eq!(seconds_from_env("TEST_JWT_SECONDS", 60).unwrap(), 60);
+        env::set_var("TEST_JWT_SECONDS", "120");
+        assert_eq!(seconds_from_env("TEST_JWT_SECONDS", 60).unwrap(), 120);
+        for value in ["1h", "-5", ""] {
+            env::set_var("TEST_JWT_SECONDS", value);
+            assert!(seconds_from_env("TEST_JWT_SECONDS", 60).is_err());
+        }
+        env::remove_var("TEST_JWT_SECONDS");
+    }
+
+    #[tokio::test]
+    async fn test_reload_keeps_fetched_jwks() {
+        use crate::middleware::jwks::tests::JwksServer;
+        use crate::tests::test_keys::TestKey;
+
+        let dir = tempfile::tempdir().unwrap();
+        let key = TestKey::generate(dir.path(), "signing");
+        let server = JwksServer::start(dir.path().join("jwks.json")).await;
+        server.publish(&[&key]);
+
+        let url = server.url.clone();
+        let verifier = JwtVerifier::new(move || {
+            Ok(JwtConfig::new(vec![IssuerConfig::new(
+                "test-auth-service",
+                VerificationKeys::Jwks(Arc::new(JwksCache::new(&url, Duration::from_secs(300), Duration::ZERO))),
+                Algorithm::RS256,
+                &["micro-frontend-service".to_string()],
+            )?]))
+        })
+        .unwrap();
+
+        let now = chrono::Utc::now().timestamp();
+        let token = key.sign(&serde_json::json!({
+            "sub": "admin",
+            "iat": now,
+            "exp": now + 3600,
+            "aud": "micro-frontend-service",
+            "iss": "test-auth-service",
+        }));
+        assert!(verifier.config().decode(&token).await.is_ok());
+        assert_eq!(server.hits(), 1);
+
+        // The key set fetched before the reload is still used, without another fetch
+        verifier.reload().unwrap();
+        assert!(verifier.config().decode(&token).await.is_ok());
+        assert_eq!(server.hits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_jwt_config_selects_issuer_and_accepts_audience_arrays() {
+        use crate::middleware::jwks::tests::JwksServer;
+        use crate::tests::test_keys::TestKey;
+
+        let dir = tempfile::tempdir().unwrap();
+        let old_key = TestKey::generate(dir.path(), "old");
+        let new_key = TestKey::generate(dir.path(), "new");
+        let old_server = JwksServer::start(dir.path().join("old.json")).await;
+        let new_server = JwksServer::start(dir.path().join("new.json")).await;
+        old_server.publish(&[&old_key]);
+        new_server.publish(&[&new_key]);
+
+        let issuers_file = dir.path().join("issuers.json");
+        let issuers = serde_json::json!([
+            {"issuer": "https://old-idp.example.com", "audiences": ["micro-frontend-service"], "jwks_url": old_server.url},
+            {"issuer": "https://new-idp.example.com", "audiences": ["chat", "micro-frontend-service"], "algorithm": "RS256", "jwks_url": new_server.url},
+        ]);
+        std::fs::write(&issuers_file, issuers.to_string()).unwrap();
+        let config = JwtConfig::from_file(&issuers_file).unwrap();
+        assert_eq!(config.watched_files(), [issuers_file]);
+
+        let now = chrono::Utc::now().timestamp();
+        let claims = |issuer: &str, audience: serde_json::Value| serde_json::json!({"sub": "admin", "iat": now, "exp": now + 3600, "aud": audience, "iss": issuer});
+
+        let token = old_key.sign(&claims("https://old-idp.example.com", "micro-frontend-service".into()));
+        let token_data = config.decode(&token).await.unwrap();
+        assert_eq!(token_data.claims.aud, Audience::from("micro-frontend-service"));
+
+        let token = new_key.sign(&claims("https://new-idp.example.com", serde_json::json!(["chat", "other"])));
+        let token_data = config.decode(&token).await.unwrap();
+        assert_eq!(token_data.claims.aud.iter().collect::<Vec<_>>(), ["chat", "other"]);
+
+        // Each issuer only trusts its own keys and audiences
+        let token = new_key.sign(&claims("https://old-idp.example.com", "micro-frontend-service".into()));
+        assert!(matches!(config.decode(&token).await, Err(TokenError::UnknownKey(_))));
+        let token = old_key.sign(&claims("https://old-idp.example.com", "chat".into()));
+        assert!(matches!(config.decode(&token).await, Err(TokenError::Invalid(_))));
+        let token = new_key.sign(&claims("https://evil.example.com", "chat".into()));
+        assert!(matches!(
+            config.decode(&token).await,
+            Err(TokenError::UnknownIssuer(Some(issuer))) if issuer == "https://evil.example.com"
+        ));
+    }
+
+    #[test]
+    fn test_jwt_issuers_file_validation() {
+        let dir = tempfile::tempdir().unwrap();
+        let issuers_file = dir.path().join("issuers.json");
+        let load = |issuers: serde_json::Value| {
+            std::fs::write(&issuers_file, issuers.to_string()).unwrap();
+            JwtConfig::from_file(&issuers_file).map(|_| ()).unwrap_err().to_string()
+        };
+
+        let jwks = "https://idp.example.com/jwks.json";
+        assert!(load(serde_json::json!([])).contains("no issuers"));
+        assert!(load(serde_json::json!([{"issuer": "a", "audiences": []}])).contains("at least one audience"));
+        assert!(load(serde_json::json!([{"issuer": "a", "audiences": ["x"]}])).contains("exactly one of"));
+        assert!(
+            load(serde_json::json!([{"issuer": "a", "audiences": ["x"], "jwks_url": jwks, "public_key": "pem"}]))
+                .contains("exactly one of")
+        );
+        assert!(
+            load(serde_json::json!([{"issuer": "a", "audiences": ["x"], "jwks_url": jwks, "algorithm": "HS256"}]))
+                .contains("Unsupported JWT algorithm")
+        );
+        assert!(load(serde_json::json!([
+            {"issuer": "a", "audiences": ["x"], "jwks_url": jwks},
+            {"issuer": "a", "audiences": ["y"], "jwks_url": jwks},
+        ]))
+        .contains("listed twice"));
+    }
 }
@@ -0,0 +1,113 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::errors::{error_body, LocalizedError};
+use crate::i18n::{Catalog, Locale, RequestedLanguages, LOCALE_PARAMETER};
+use crate::language::{accepted_languages, language_range};
+use crate::router::AppState;
+
+/// Negotiate the locale of every request and localize the response.
+///
+/// The `lang` query parameter wins over the `lang` cookie, which wins over `Accept-Language`;
+/// handlers get the ranges themselves as [`RequestedLanguages`].
+/// Error responses carrying a catalog message are re-rendered in the negotiated locale, and
+/// localized responses get `Content-Language` and `Vary` headers.
+pub async fn locale_middleware(State(app_state): State<Arc<AppState>>, mut request: Request, next: Next) -> Response {
+    let catalog = app_state.template_service.catalog();
+    let languages = requested_languages(&request);
+    let locale = catalog.negotiate(&languages);
+
+    request.extensions_mut().insert(locale.clone());
+    request.extensions_mut().insert(RequestedLanguages(languages));
+    let response = next.run(request).await;
+    localize_response(catalog, &locale, response)
+}
+
+/// Language ranges a request asks for, most preferred first
+pub fn requested_languages(request: &Request) -> Vec<String> {
+    let query = request.uri().query().and_then(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find(|(name, _)| name == LOCALE_PARAMETER)
+            .and_then(|(_, value)| language_range(&value))
+    });
+
+    let cookie = request
+        .headers()
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|cookie| {
+            let (name, value) = cookie.trim().split_once('=')?;
+            (name == LOCALE_PARAMETER).then(|| language_range(value)).flatten()
+        });
+
+    query
+        .into_iter()
+        .chain(cookie)
+        .chain(accepted_languages(request.headers()))
+        .collect()
+}
+
+fn localize_response(catalog: &Catalog, locale: &Locale, response: Response) -> Response {
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/html"));
+
+    let (mut parts, body) = response.into_parts();
+    let body = match parts.extensions.get::<LocalizedError>() {
+        Some(error) => {
+            let message = catalog.message(locale, error.message.id, &error.message.args);
+            let body = error_body(error.code, &message, Some(&error.message), error.details.as_deref());
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Body::from(body.to_string())
+        }
+        None if is_html => body,
+        None => return Response::from_parts(parts, body),
+    };
+
+    if let Ok(value) = HeaderValue::from_str(locale.as_str()) {
+        parts.headers.insert(header::CONTENT_LANGUAGE, value);
+    }
+    for field in ["Accept-Language", "Cookie"] {
+        parts.headers.append(header::VARY, HeaderValue::from_static(field));
+    }
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_request(uri: &str, headers: &[(header::HeaderName, &'static str)]) -> Request {
+        let mut request = Request::builder().uri(uri);
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        request.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_requested_languages_precedence() {
+        let request = build_request(
+            "/display/username/admin?app=chat&lang=de-CH",
+            &[
+                (header::COOKIE, "jwt_token=abc; lang=fr"),
+                (header::ACCEPT_LANGUAGE, "ja, en;q=0.5"),
+            ],
+        );
+        assert_eq!(requested_languages(&request), ["de-ch", "fr", "ja", "en"]);
+
+        // Malformed values are ignored rather than rejected
+        let request = build_request("/?lang=%3Cscript%3E", &[(header::COOKIE, "lang=")]);
+        assert!(requested_languages(&request).is_empty());
+    }
+}
@@ -1,10 +1,25 @@
+pub mod api_key;
+pub mod authenticated_user;
+pub mod authorization;
+pub mod csrf;
+pub mod freshness;
+pub mod jwks;
 pub mod jwt_auth;
+pub mod locale;
 pub mod rate_limiting;
 pub mod security;
+pub mod tenant;
 
+pub use api_key::*;
+pub use authenticated_user::*;
+pub use authorization::*;
+pub use csrf::*;
+pub use freshness::*;
 pub use jwt_auth::*;
+pub use locale::*;
 pub use rate_limiting::*;
 pub use security::*;
+pub use tenant::*;
 
 use axum::{
     extract::{Request, State},
@@ -16,28 +31,41 @@ use std::sync::Arc;
 use tracing::warn;
 
 use crate::{
+    errors::AuthFailure,
     metrics::{track_auth_failure, track_auth_success},
     router::AppState,
+    tenant::Tenant,
 };
 
 /// Middleware to track authentication metrics
 pub async fn auth_metrics_middleware(State(app_state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
     let response = next.run(request).await;
+    let tenant = response.extensions().get::<Tenant>().cloned().unwrap_or_default();
 
     // Check response status to determine authentication success/failure
     match response.status() {
         StatusCode::UNAUTHORIZED => {
-            warn!("Authentication failed");
-            track_auth_failure(&app_state.metrics, "invalid_token");
+            // `AuthError` responses name the reason the token was refused
+            let reason = response
+                .extensions()
+                .get::<AuthFailure>()
+                .map_or("invalid_token", |failure| failure.0);
+            warn!("Authentication failed: {}", reason);
+            track_auth_failure(&app_state.metrics, &tenant, reason);
         }
         StatusCode::FORBIDDEN => {
-            warn!("Authorization failed");
-            track_auth_failure(&app_state.metrics, "insufficient_permissions");
+            // Permission checks record why they refused the request
+            let reason = response
+                .extensions()
+                .get::<AuthFailure>()
+                .map_or("insufficient_permissions", |failure| failure.0);
+            warn!("Authorization failed: {}", reason);
+            track_auth_failure(&app_state.metrics, &tenant, reason);
         }
         _ => {
-            // Check if we have a username in the extensions (successful auth)
-            if let Some(username) = response.extensions().get::<String>() {
-                track_auth_success(&app_state.metrics, username);
+            // `jwt_auth_middleware` leaves the user it authenticated in the extensions
+            if let Some(user) = response.extensions().get::<AuthenticatedUser>() {
+                track_auth_success(&app_state.metrics, &tenant, &user.issuer);
             }
         }
     }
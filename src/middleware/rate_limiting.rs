@@ -1,49 +1,65 @@
 use axum::{
     body::Body,
-    extract::Request,
+    extract::{ConnectInfo, Request, State},
     http::{HeaderValue, StatusCode},
     middleware::Next,
     response::Response,
 };
 use std::collections::HashMap;
-use std::net::IpAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use tracing::warn;
 
-/// Rate limiter based on IP address
+use crate::config::rate_limit::RateLimitConfig;
+use crate::router::AppState;
+
+/// Clients tracked before idle ones are dropped
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Rate limiter based on IP address, shared by every request through `AppState`
 #[derive(Clone)]
 pub struct RateLimiter {
     requests: Arc<RwLock<HashMap<IpAddr, Vec<Instant>>>>,
-    max_requests: usize,
-    window: Duration,
+    config: RateLimitConfig,
 }
 
 impl RateLimiter {
-    pub fn new(max_requests: usize, window: Duration) -> Self {
+    pub fn new(config: RateLimitConfig) -> Self {
         Self {
             requests: Arc::new(RwLock::new(HashMap::new())),
-            max_requests,
-            window,
+            config,
         }
     }
 
+    pub fn config(&self) -> &RateLimitConfig {
+        &self.config
+    }
+
     pub fn check_rate_limit(&self, ip: IpAddr) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+
         let now = Instant::now();
-        let cutoff = now - self.window;
+        let cutoff = now.checked_sub(self.config.window).unwrap_or(now);
 
         let mut requests = match self.requests.write() {
             Ok(requests) => requests,
             Err(_) => return false, // Allow request if lock is poisoned
         };
 
-        let ip_requests = requests.entry(ip).or_insert_with(Vec::new);
+        if requests.len() >= MAX_TRACKED_CLIENTS {
+            requests.retain(|_, times| times.last().is_some_and(|&time| time > cutoff));
+        }
+
+        let ip_requests = requests.entry(ip).or_default();
 
         // Remove old requests
         ip_requests.retain(|&request_time| request_time > cutoff);
 
         // Check if under limit
-        if ip_requests.len() < self.max_requests {
+        if ip_requests.len() < self.config.max_requests {
             ip_requests.push(now);
             true
         } else {
@@ -52,24 +68,36 @@ impl RateLimiter {
     }
 }
 
-/// Rate limiting middleware
-pub async fn rate_limiting_middleware(request: Request, next: Next) -> Result<Response, StatusCode> {
-    // Get client IP from headers or connection info
-    let client_ip = get_client_ip(&request).unwrap_or(IpAddr::from([127, 0, 0, 1]));
-
-    // Create rate limiter (in production, this would be a singleton)
-    let max_requests = std::env::var("RATE_LIMIT_REQUESTS_PER_MINUTE")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(60);
-
-    let rate_limiter = RateLimiter::new(max_requests, Duration::from_secs(60));
+/// Rate limiting middleware.
+///
+/// Clients are identified by the connection's peer address, or the `X-Forwarded-For` client
+/// when the peer is one of `API_KEY_TRUSTED_PROXIES`, so a client cannot pick its own key.
+pub async fn rate_limiting_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let forwarded_for = request.headers().get("x-forwarded-for").and_then(|value| value.to_str().ok());
+    // A malformed header from a proxy counts against the proxy; without a peer address
+    // (not served over a socket) every request shares one budget
+    let client_ip = app_state
+        .api_keys
+        .client_ip(peer, forwarded_for)
+        .or(peer)
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
 
+    let rate_limiter = &app_state.rate_limiter;
     if !rate_limiter.check_rate_limit(client_ip) {
         warn!("Rate limit exceeded for IP: {}", client_ip);
         let mut response = Response::new(Body::from("Rate limit exceeded"));
         *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
-        response.headers_mut().insert("Retry-After", HeaderValue::from_static("60"));
+        if let Ok(retry_after) = HeaderValue::from_str(&rate_limiter.config().window.as_secs().to_string()) {
+            response.headers_mut().insert("Retry-After", retry_after);
+        }
         return Ok(response);
     }
 
@@ -77,24 +105,31 @@ pub async fn rate_limiting_middleware(request: Request, next: Next) -> Result<Re
     Ok(response)
 }
 
-/// Extract client IP from request headers or connection info
-fn get_client_ip(request: &Request) -> Option<IpAddr> {
-    // Check common proxy headers
-    for header_name in ["x-forwarded-for", "x-real-ip", "cf-connecting-ip"] {
-        if let Some(header_value) = request.headers().get(header_name) {
-            if let Ok(header_str) = header_value.to_str() {
-                // Take the first IP if there are multiple (comma-separated)
-                if let Some(ip_str) = header_str.split(',').next() {
-                    if let Ok(ip) = ip_str.trim().parse() {
-                        return Some(ip);
-                    }
-                }
-            }
-        }
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_rate_limiter_counts_per_client() {
+        let limiter = RateLimiter::new(RateLimitConfig {
+            enabled: true,
+            max_requests: 2,
+            window: Duration::from_secs(60),
+        });
+        let (client, other) = (IpAddr::from([192, 0, 2, 1]), IpAddr::from([192, 0, 2, 2]));
 
-    // Fallback to connection info (if available)
-    // Note: This would require access to connection info which isn't directly available in axum middleware
-    // In practice, you'd use a different approach or configure your reverse proxy to set headers
-    None
+        assert!(limiter.check_rate_limit(client));
+        assert!(limiter.check_rate_limit(client));
+        assert!(!limiter.check_rate_limit(client));
+        assert!(limiter.check_rate_limit(other));
+
+        let disabled = RateLimiter::new(RateLimitConfig {
+            enabled: false,
+            max_requests: 1,
+            window: Duration::from_secs(60),
+        });
+        assert!(disabled.check_rate_limit(client));
+        assert!(disabled.check_rate_limit(client));
+    }
 }
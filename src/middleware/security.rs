@@ -36,6 +36,16 @@ pub async fn security_headers_middleware(request: Request<Body>, next: Next) ->
         ),
     );
 
+    // Localized responses must not be served from a shared cache to readers of another language;
+    // pages and localized errors already say so through the locale middleware
+    if varies_by_language(&uri_path) {
+        for field in ["Accept-Language", "Cookie"] {
+            if !vary_lists(headers, &field.to_ascii_lowercase()) {
+                headers.append(header::VARY, HeaderValue::from_static(field));
+            }
+        }
+    }
+
     // Cache control headers for performance
     if let Some(content_type) = headers.get(header::CONTENT_TYPE) {
         if let Ok(content_type_str) = content_type.to_str() {
@@ -76,3 +86,36 @@ pub async fn security_headers_middleware(request: Request<Body>, next: Next) ->
 fn is_user_specific_page(path: &str) -> bool {
     path == "/edit" || path.starts_with("/display/username/")
 }
+
+/// Whether the `Vary` headers already name `name` (lowercase)
+fn vary_lists(headers: &header::HeaderMap, name: &str) -> bool {
+    headers
+        .get_all(header::VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|field| field.trim().eq_ignore_ascii_case(name))
+}
+
+/// Paths whose response is negotiated on `Accept-Language` and the `lang` cookie (localized display names)
+fn varies_by_language(path: &str) -> bool {
+    path.starts_with("/display/username/")
+        || path
+            .strip_prefix("/api/username/")
+            .is_some_and(|username| !username.is_empty() && !username.contains('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varies_by_language() {
+        assert!(varies_by_language("/display/username/alice"));
+        assert!(varies_by_language("/api/username/alice"));
+        assert!(!varies_by_language("/api/username/alice/availability"));
+        assert!(!varies_by_language("/api/username/locales/ja"));
+        assert!(!varies_by_language("/api/username"));
+        assert!(!varies_by_language("/edit"));
+    }
+}
@@ -0,0 +1,59 @@
+use axum::{
+    extract::{Request, State},
+    http::header::HOST,
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::errors::AppError;
+use crate::middleware::AuthenticatedUser;
+use crate::router::AppState;
+use crate::tenant::Tenant;
+
+/// Resolve the tenant from the `Host` header for every request.
+///
+/// The tenant is also copied to the response extensions, where the metrics middleware
+/// picks it up for its `tenant` label.
+pub async fn host_tenant_middleware(
+    State(app_state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let host = request
+        .headers()
+        .get(HOST)
+        .and_then(|value| value.to_str().ok())
+        .or_else(|| request.uri().host());
+    let tenant = app_state.tenancy.tenant_for_host(host);
+
+    request.extensions_mut().insert(tenant.clone());
+    let mut response = next.run(request).await;
+
+    // Authenticated routes may have narrowed it down from the token
+    if response.extensions().get::<Tenant>().is_none() {
+        response.extensions_mut().insert(tenant);
+    }
+    response
+}
+
+/// Resolve the tenant of an authenticated request from its token (see `TENANT_CLAIM`).
+///
+/// Runs inside `jwt_auth_middleware`, which provides the authenticated user.
+pub async fn claim_tenant_middleware(
+    State(app_state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(user) = request.extensions().get::<AuthenticatedUser>() else {
+        return Ok(next.run(request).await);
+    };
+
+    let host_tenant = request.extensions().get::<Tenant>().cloned().unwrap_or_default();
+    let tenant = app_state.tenancy.tenant_for_user(user, host_tenant)?;
+
+    request.extensions_mut().insert(tenant.clone());
+    let mut response = next.run(request).await;
+    response.extensions_mut().insert(tenant);
+    Ok(response)
+}
@@ -0,0 +1,295 @@
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::database::{RevokedSubject, RevokedToken, UserDatabase};
+use crate::errors::AppError;
+use crate::tenant::Tenant;
+
+/// How long revocations are kept and how often other instances' revocations are picked up
+#[derive(Debug, Clone)]
+pub struct RevocationConfig {
+    /// Lifetime of a revocation without a known expiry; must cover the longest token lifetime
+    pub retention: Duration,
+    /// Maximum age of the in-memory revocation list before it is reloaded from the database
+    pub refresh_interval: Duration,
+}
+
+impl Default for RevocationConfig {
+    fn default() -> Self {
+        Self {
+            retention: Duration::from_secs(24 * 60 * 60),
+            refresh_interval: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Revoked tokens (by `jti`) and subjects (by `sub` and issue time).
+///
+/// Revocations apply to one tenant and issuer, since token ids and subjects are only unique
+/// per issuer. Revocations are stored in the database and checked against an in-memory copy, which is
+/// reloaded every refresh interval so revocations made on other instances take effect. If the
+/// database cannot be read, the last loaded list stays in use.
+#[derive(Clone)]
+pub struct RevocationService {
+    database: Arc<dyn UserDatabase>,
+    config: RevocationConfig,
+    cache: Arc<RwLock<RevocationCache>>,
+}
+
+/// Tenant, issuer and `jti` or subject of a revocation
+type RevocationKey = (Tenant, String, String);
+
+#[derive(Default)]
+struct RevocationCache {
+    /// Token to the time its revocation expires
+    tokens: HashMap<RevocationKey, DateTime<Utc>>,
+    /// Subject to the time before which its tokens are revoked
+    subjects: HashMap<RevocationKey, DateTime<Utc>>,
+    loaded_at: Option<Instant>,
+}
+
+fn revocation_key(tenant: &Tenant, issuer: &str, id: &str) -> RevocationKey {
+    (tenant.clone(), issuer.to_string(), id.to_string())
+}
+
+impl RevocationService {
+    pub fn new(database: Arc<dyn UserDatabase>, config: RevocationConfig) -> Self {
+        Self {
+            database,
+            config,
+            cache: Arc::new(RwLock::new(RevocationCache::default())),
+        }
+    }
+
+    /// Whether a verified token of `issuer` used in `tenant` has been revoked, by its `jti` or by its subject
+    pub async fn is_revoked(
+        &self,
+        tenant: &Tenant,
+        issuer: &str,
+        jti: Option<&str>,
+        subject: &str,
+        issued_at: i64,
+    ) -> bool {
+        if self.is_stale() {
+            self.refresh().await;
+        }
+
+        let cache = self.cache.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Utc::now();
+        let token_revoked = jti
+            .and_then(|jti| cache.tokens.get(&revocation_key(tenant, issuer, jti)))
+            .is_some_and(|expires_at| *expires_at > now);
+        let subject_revoked = cache
+            .subjects
+            .get(&revocation_key(tenant, issuer, subject))
+            .is_some_and(|issued_before| issued_at < issued_before.timestamp());
+        token_revoked || subject_revoked
+    }
+
+    /// Revoke one token of `issuer` in `tenant` until `expires_at` (its `exp`), or for the retention period
+    pub async fn revoke_token(
+        &self,
+        tenant: &Tenant,
+        issuer: &str,
+        jti: &str,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<DateTime<Utc>, AppError> {
+        let revoked = RevokedToken {
+            issuer: issuer.to_string(),
+            jti: jti.to_string(),
+            expires_at: expires_at.unwrap_or_else(|| Utc::now() + self.config.retention),
+        };
+
+        self.database
+            .revoke_token(tenant, &revoked)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to revoke token: {}", e)))?;
+
+        let mut cache = self.cache.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let entry = cache
+            .tokens
+            .entry(revocation_key(tenant, issuer, jti))
+            .or_insert(revoked.expires_at);
+        *entry = (*entry).max(revoked.expires_at);
+        Ok(revoked.expires_at)
+    }
+
+    /// Revoke every token of `issuer`'s `subject` in `tenant` issued before `issued_before` (default: now).
+    ///
+    /// An earlier cutoff than one already in place leaves the existing one unchanged; the
+    /// returned revocation has the cutoff in effect.
+    pub async fn revoke_subject(
+        &self,
+        tenant: &Tenant,
+        issuer: &str,
+        subject: &str,
+        issued_before: Option<DateTime<Utc>>,
+    ) -> Result<RevokedSubject, AppError> {
+        let issued_before = issued_before.unwrap_or_else(Utc::now);
+        let revoked = RevokedSubject {
+            issuer: issuer.to_string(),
+            subject: subject.to_string(),
+            issued_before,
+            expires_at: issued_before + self.config.retention,
+        };
+
+        self.database
+            .revoke_subject(tenant, &revoked)
+            .await
+            .map_err(|e| AppError::database_error(format!("Failed to revoke subject: {}", e)))?;
+
+        let mut cache = self.cache.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let entry = cache
+            .subjects
+            .entry(revocation_key(tenant, issuer, subject))
+            .or_insert(issued_before);
+        *entry = (*entry).max(issued_before);
+        Ok(RevokedSubject {
+            issued_before: *entry,
+            expires_at: *entry + self.config.retention,
+            ..revoked
+        })
+    }
+
+    /// Periodically delete revocations whose tokens have all expired
+    pub fn spawn_cleanup(&self, interval: Duration) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            loop {
+                ticks.tick().await;
+                match service.database.delete_expired_revocations(Utc::now()).await {
+                    Ok(0) => {}
+                    Ok(removed) => tracing::info!("Removed {} expired token revocations", removed),
+                    Err(e) => tracing::warn!("Failed to remove expired token revocations: {}", e),
+                }
+            }
+        });
+    }
+
+    fn is_stale(&self) -> bool {
+        let cache = self.cache.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        cache
+            .loaded_at
+            .is_none_or(|loaded_at| loaded_at.elapsed() >= self.config.refresh_interval)
+    }
+
+    async fn refresh(&self) {
+        let result = self.database.get_revocations(Utc::now()).await;
+
+        let mut cache = self.cache.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // A failed load is retried after the refresh interval rather than on every request
+        cache.loaded_at = Some(Instant::now());
+        match result {
+            Ok(revocations) => {
+                cache.tokens = revocations
+                    .tokens
+                    .into_iter()
+                    .map(|(tenant, revoked)| ((tenant, revoked.issuer, revoked.jti), revoked.expires_at))
+                    .collect();
+                cache.subjects = revocations
+                    .subjects
+                    .into_iter()
+                    .map(|(tenant, revoked)| ((tenant, revoked.issuer, revoked.subject), revoked.issued_before))
+                    .collect();
+            }
+            Err(e) => tracing::warn!("Failed to load token revocations, keeping the last loaded list: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::mock::MockUserDatabase;
+
+    const ISSUER: &str = "test-auth-service";
+
+    #[tokio::test]
+    async fn test_revoke_token_and_subject() {
+        let service = RevocationService::new(Arc::new(MockUserDatabase::new()), RevocationConfig::default());
+        let tenant = Tenant::default();
+        let now = Utc::now().timestamp();
+
+        assert!(!service.is_revoked(&tenant, ISSUER, Some("token-1"), "alice", now).await);
+
+        service.revoke_token(&tenant, ISSUER, "token-1", None).await.unwrap();
+        assert!(service.is_revoked(&tenant, ISSUER, Some("token-1"), "alice", now).await);
+        assert!(!service.is_revoked(&tenant, ISSUER, Some("token-2"), "alice", now).await);
+        assert!(!service.is_revoked(&tenant, ISSUER, None, "alice", now).await);
+
+        // Only tokens issued before the revocation are affected
+        let issued_before = Utc::now() - chrono::Duration::seconds(60);
+        service
+            .revoke_subject(&tenant, ISSUER, "bob", Some(issued_before))
+            .await
+            .unwrap();
+        assert!(service.is_revoked(&tenant, ISSUER, None, "bob", now - 120).await);
+        assert!(!service.is_revoked(&tenant, ISSUER, None, "bob", now).await);
+
+        // An older cutoff does not un-revoke tokens covered by a newer one
+        let revoked = service
+            .revoke_subject(&tenant, ISSUER, "bob", Some(issued_before - chrono::Duration::seconds(600)))
+            .await
+            .unwrap();
+        assert_eq!(revoked.issued_before, issued_before);
+        assert!(service.is_revoked(&tenant, ISSUER, None, "bob", now - 120).await);
+    }
+
+    #[tokio::test]
+    async fn test_revocations_are_scoped_to_tenant_and_issuer() {
+        let service = RevocationService::new(Arc::new(MockUserDatabase::new()), RevocationConfig::default());
+        let tenant = Tenant::default();
+        let brand = Tenant::new("brand-a").unwrap();
+        let now = Utc::now().timestamp();
+
+        service.revoke_token(&tenant, ISSUER, "token-1", None).await.unwrap();
+        service.revoke_subject(&tenant, ISSUER, "bob", None).await.unwrap();
+
+        assert!(!service.is_revoked(&brand, ISSUER, Some("token-1"), "alice", now).await);
+        assert!(!service.is_revoked(&tenant, "other-issuer", Some("token-1"), "alice", now).await);
+        assert!(!service.is_revoked(&brand, ISSUER, None, "bob", now - 10).await);
+        assert!(!service.is_revoked(&tenant, "other-issuer", None, "bob", now - 10).await);
+    }
+
+    #[tokio::test]
+    async fn test_revocations_reach_other_instances() {
+        let database: Arc<dyn UserDatabase> = Arc::new(MockUserDatabase::new());
+        let config = RevocationConfig {
+            refresh_interval: Duration::ZERO,
+            ..RevocationConfig::default()
+        };
+        let instance_a = RevocationService::new(database.clone(), config.clone());
+        let instance_b = RevocationService::new(database.clone(), config);
+        let tenant = Tenant::new("brand-a").unwrap();
+        let now = Utc::now().timestamp();
+
+        assert!(!instance_b.is_revoked(&tenant, ISSUER, Some("token-1"), "alice", now).await);
+        instance_a.revoke_token(&tenant, ISSUER, "token-1", None).await.unwrap();
+        instance_a.revoke_subject(&tenant, ISSUER, "bob", None).await.unwrap();
+        assert!(instance_b.is_revoked(&tenant, ISSUER, Some("token-1"), "alice", now).await);
+        assert!(instance_b.is_revoked(&tenant, ISSUER, None, "bob", now - 10).await);
+        assert!(!instance_b.is_revoked(&Tenant::default(), ISSUER, None, "bob", now - 10).await);
+        instance_a
+            .revoke_subject(&tenant, ISSUER, "bob", Some(Utc::now() - chrono::Duration::hours(1)))
+            .await
+            .unwrap();
+        assert!(instance_b.is_revoked(&tenant, ISSUER, None, "bob", now - 10).await);
+
+        // Expired revocations are dropped
+        instance_a
+            .revoke_token(&tenant, ISSUER, "token-2", Some(Utc::now() - chrono::Duration::seconds(1)))
+            .await
+            .unwrap();
+        assert!(!instance_b.is_revoked(&tenant, ISSUER, Some("token-2"), "alice", now).await);
+        assert_eq!(
+            database
+                .delete_expired_revocations(Utc::now() + chrono::Duration::days(2))
+                .await
+                .unwrap(),
+            3
+        );
+    }
+}
@@ -1,7 +1,8 @@
 use axum::{
+    extract::DefaultBodyLimit,
     http::{header, Method},
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post, put, MethodRouter},
     Router,
 };
 use lazy_static::lazy_static;
@@ -13,24 +14,52 @@ use tower_http::{
     trace::TraceLayer,
 };
 
+use crate::api_keys::ApiKeyService;
+use crate::avatar::AvatarService;
+use crate::config::authorization::AuthorizationConfig;
+use crate::config::display_name::DisplayNameConfig;
+use crate::config::freshness::FreshnessConfig;
+use crate::config::moderation::ModerationConfig;
+use crate::config::session::SessionConfig;
+use crate::config::tenancy::TenancyConfig;
 use crate::database::UserDatabase;
+use crate::display_name_policy::DisplayNamePolicies;
 use crate::handlers::{
+    delete_api_key::delete_api_key,
+    delete_api_username_locale::delete_api_username_locale,
+    delete_api_username_override::delete_api_username_override,
+    get_api_keys::get_api_keys,
+    get_api_moderation_pending::get_api_moderation_pending,
     get_api_username::get_api_username,
+    get_api_username_availability::get_api_username_availability,
+    get_avatar::get_avatar,
     get_debug_headers::get_debug_headers,
     get_debug_set_token::get_debug_set_token,
     get_debug_validate_token::get_debug_validate_token,
     get_display::get_display_username,
-    get_edit::get_edit,
+    get_edit::{get_edit, get_edit_for_user},
     get_health::get_health,
     get_seed_status::get_seed_status,
     get_static::{get_manifest, get_robots_txt, get_sitemap},
-    post_api_username::post_api_username,
+    post_api_avatar::post_api_avatar,
+    post_api_keys::post_api_keys,
+    post_api_moderation_approve::post_api_moderation_approve,
+    post_api_moderation_reject::post_api_moderation_reject,
+    post_api_revocations::post_api_revocations,
+    post_api_username::{post_api_username, post_api_username_for_user},
+    post_auth_logout::post_auth_logout,
+    post_auth_session::post_auth_session,
+    put_api_username_locale::put_api_username_locale,
+    put_api_username_override::put_api_username_override,
 };
 use crate::logging::{error_logging_middleware, request_context_middleware, security_event_logging_middleware};
 use crate::metrics::{get_metrics, track_metrics, AppMetrics};
 use crate::middleware::{
-    auth_metrics_middleware, jwt_auth_middleware, rate_limiting_middleware, security_headers_middleware,
+    api_key_middleware, auth_metrics_middleware, claim_tenant_middleware, csrf_protection_middleware,
+    host_tenant_middleware, jwt_auth_middleware, locale_middleware, rate_limiting_middleware, require_admin_middleware,
+    security_headers_middleware, token_freshness_middleware, JwtVerifier, RateLimiter,
 };
+use crate::revocation::RevocationService;
 use crate::template::TemplateService;
 
 /// Application state containing all shared services
@@ -39,6 +68,47 @@ pub struct AppState {
     pub database: Arc<dyn UserDatabase>,
     pub template_service: TemplateService,
     pub metrics: AppMetrics,
+    pub display_name_policies: DisplayNamePolicies,
+    pub display_names: DisplayNameConfig,
+    pub moderation: ModerationConfig,
+    pub avatars: AvatarService,
+    pub tenancy: TenancyConfig,
+    pub authorization: AuthorizationConfig,
+    pub jwt: JwtVerifier,
+    pub revocation: RevocationService,
+    pub freshness: FreshnessConfig,
+    pub session: SessionConfig,
+    pub api_keys: ApiKeyService,
+    pub rate_limiter: RateLimiter,
+}
+
+impl AppState {
+    /// State around `database` with default settings and unregistered metrics, for tests
+    #[cfg(test)]
+    pub fn for_tests(database: Arc<dyn UserDatabase>) -> Self {
+        use crate::api_keys::ApiKeyConfig;
+        use crate::avatar::{AvatarConfig, DatabaseAvatarStore};
+        use crate::config::rate_limit::RateLimitConfig;
+        use crate::revocation::RevocationConfig;
+
+        Self {
+            template_service: TemplateService::new(false, false).unwrap(),
+            metrics: AppMetrics::new_for_tests(),
+            display_name_policies: DisplayNamePolicies::default(),
+            display_names: DisplayNameConfig::default(),
+            moderation: ModerationConfig::default(),
+            avatars: AvatarService::new(Arc::new(DatabaseAvatarStore::new(database.clone())), AvatarConfig::default()),
+            tenancy: TenancyConfig::default(),
+            authorization: AuthorizationConfig::default(),
+            jwt: JwtVerifier::new_for_tests(),
+            revocation: RevocationService::new(database.clone(), RevocationConfig::default()),
+            freshness: FreshnessConfig::default(),
+            session: SessionConfig::default(),
+            api_keys: ApiKeyService::new(database.clone(), ApiKeyConfig::default()),
+            rate_limiter: RateLimiter::new(RateLimitConfig::default()),
+            database,
+        }
+    }
 }
 
 // Global metrics instance for use in database and other places where
@@ -47,6 +117,11 @@ lazy_static! {
     static ref GLOBAL_METRICS: AppMetrics = AppMetrics::new();
 }
 
+/// The metrics registered with the global Prometheus registry, which `GET /metrics` exposes
+pub fn global_metrics() -> AppMetrics {
+    GLOBAL_METRICS.clone()
+}
+
 /// Get a reference to the global metrics instance
 ///
 /// This implementation directly returns a reference to the lazy_static
@@ -57,50 +132,156 @@ pub fn get_metrics_instance() -> Option<&'static AppMetrics> {
     Some(&GLOBAL_METRICS)
 }
 
-pub fn create_app(database: Arc<dyn UserDatabase>, template_service: TemplateService) -> Router {
-    // Initialize metrics - use test-specific metrics in test context
-    #[cfg(test)]
-    let app_metrics = AppMetrics::new_for_tests();
-
-    #[cfg(not(test))]
-    let app_metrics = {
-        // For non-test environments, use the same global metrics instance
-        // that's accessible from other parts of the code
-        // This ensures we have only one set of metrics for the application
-        GLOBAL_METRICS.clone()
-    };
-
-    let app_state = Arc::new(AppState {
-        database,
-        template_service,
-        metrics: app_metrics,
-    });
+type RouteTable = Vec<(&'static str, MethodRouter<Arc<AppState>>)>;
+
+/// Request body limit for every route except uploads
+const REQUEST_BODY_LIMIT_BYTES: usize = 1024 * 16; // 16KB
+
+/// Room for multipart boundaries and part headers on top of the uploaded file itself
+const MULTIPART_OVERHEAD_BYTES: usize = 1024 * 16;
+
+/// Public routes (no authentication required)
+fn public_routes() -> RouteTable {
+    vec![
+        ("/health", get(get_health)),
+        ("/metrics", get(get_metrics)), // Add Prometheus metrics endpoint
+        ("/api/username/{username}", get(get_api_username)),
+        ("/display/username/{username}", get(get_display_username)),
+        ("/avatar/{username}", get(get_avatar)),
+        ("/auth/session", post(post_auth_session)), // Exchange a bearer token for a session cookie
+        ("/auth/logout", post(post_auth_logout)),
+        ("/debug/set-token/{username}", get(get_debug_set_token)),
+        ("/debug/headers", get(get_debug_headers)), // Debug endpoint for checking headers
+        ("/debug/validate-token/{token}", get(get_debug_validate_token)), // Token validation debug
+        ("/database/seed-status", get(get_seed_status)), // Add seed status endpoint
+        ("/manifest.json", get(get_manifest)),
+        ("/robots.txt", get(get_robots_txt)),
+        ("/sitemap.xml", get(get_sitemap)),
+    ]
+}
+
+/// Public routes that look up several users per request, rate limited per client
+fn rate_limited_public_routes() -> RouteTable {
+    vec![("/api/username/{username}/availability", get(get_api_username_availability))]
+}
+
+/// Protected routes (JWT authentication required)
+fn protected_routes() -> RouteTable {
+    vec![
+        ("/api/username", post(post_api_username)),
+        (
+            "/api/username/overrides/{app}",
+            put(put_api_username_override).delete(delete_api_username_override),
+        ),
+        (
+            "/api/username/locales/{locale}",
+            put(put_api_username_locale).delete(delete_api_username_locale),
+        ),
+        ("/api/moderation/pending", get(get_api_moderation_pending)),
+        ("/api/moderation/{username}/approve", post(post_api_moderation_approve)),
+        ("/api/moderation/{username}/reject", post(post_api_moderation_reject)),
+        ("/edit", get(get_edit)),
+    ]
+}
+
+/// Protected routes for users with an admin role (see `ADMIN_ROLES`)
+fn admin_routes() -> RouteTable {
+    vec![
+        ("/api/username/{username}", post(post_api_username_for_user)),
+        ("/edit/{username}", get(get_edit_for_user)),
+        ("/api/revocations", post(post_api_revocations)),
+        ("/api/keys", get(get_api_keys).post(post_api_keys)),
+        ("/api/keys/{id}", delete(delete_api_key)),
+    ]
+}
+
+/// Protected routes that accept file uploads, with a body limit sized for the upload
+fn upload_routes() -> RouteTable {
+    vec![("/api/avatar", post(post_api_avatar))]
+}
+
+/// Static path segments of every route (`health`, `api`, `username`, `robots`, ...)
+///
+/// These are reserved as usernames so a handle can never shadow a route.
+pub fn route_names() -> Vec<String> {
+    let mut names: Vec<String> = public_routes()
+        .into_iter()
+        .chain(rate_limited_public_routes())
+        .chain(protected_routes())
+        .chain(admin_routes())
+        .chain(upload_routes())
+        .flat_map(|(path, _)| path.split('/'))
+        .filter(|segment| !segment.is_empty() && !segment.starts_with('{'))
+        .map(|segment| segment.split('.').next().unwrap_or(segment).to_string())
+        .collect();
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Build the router around the application state
+pub fn create_app(app_state: AppState) -> Router {
+    let app_state = Arc::new(app_state);
 
     // Public routes (no authentication required)
-    let public_routes = Router::new()
-        .route("/health", get(get_health))
-        .route("/metrics", get(get_metrics)) // Add Prometheus metrics endpoint
-        .route("/api/username/{username}", get(get_api_username))
-        .route("/display/username/{username}", get(get_display_username))
-        .route("/debug/set-token/{username}", get(get_debug_set_token))
-        .route("/debug/headers", get(get_debug_headers)) // Debug endpoint for checking headers
-        .route("/debug/validate-token/{token}", get(get_debug_validate_token)) // Token validation debug
-        .route("/database/seed-status", get(get_seed_status)) // Add seed status endpoint
-        .route("/manifest.json", get(get_manifest))
-        .route("/robots.txt", get(get_robots_txt))
-        .route("/sitemap.xml", get(get_sitemap));
+    let public_routes = public_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (path, handler)| router.route(path, handler));
+
+    let rate_limited_public_routes = rate_limited_public_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (path, handler)| router.route(path, handler))
+        .layer(middleware::from_fn_with_state(app_state.clone(), rate_limiting_middleware));
 
     // Protected routes (JWT authentication required) - apply rate limiting to auth endpoints
-    let protected_routes = Router::new()
-        .route("/api/username", post(post_api_username))
-        .route("/edit", get(get_edit))
-        .layer(middleware::from_fn(rate_limiting_middleware))
-        .layer(middleware::from_fn(jwt_auth_middleware));
+    let protected_routes = protected_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (path, handler)| router.route(path, handler))
+        .layer(middleware::from_fn_with_state(app_state.clone(), token_freshness_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), claim_tenant_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), rate_limiting_middleware))
+        .layer(middleware::from_fn(csrf_protection_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), jwt_auth_middleware));
 
-    // Combine routes with performance and security optimizations
-    Router::new()
+    // Admin routes (JWT authentication and an admin role required)
+    let admin_routes = admin_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (path, handler)| router.route(path, handler))
+        .layer(middleware::from_fn_with_state(app_state.clone(), token_freshness_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), require_admin_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), claim_tenant_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), rate_limiting_middleware))
+        .layer(middleware::from_fn(csrf_protection_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), jwt_auth_middleware));
+
+    let limited_routes = Router::new()
         .merge(public_routes)
+        .merge(rate_limited_public_routes)
         .merge(protected_routes)
+        .merge(admin_routes)
+        .layer(RequestBodyLimitLayer::new(REQUEST_BODY_LIMIT_BYTES));
+
+    // Upload routes replace the 16KB limit with one sized for the upload (and axum's own 2MB default)
+    let upload_limit = app_state.avatars.config().max_upload_bytes + MULTIPART_OVERHEAD_BYTES;
+    let upload_routes = upload_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (path, handler)| router.route(path, handler))
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(upload_limit))
+        .layer(middleware::from_fn_with_state(app_state.clone(), token_freshness_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), claim_tenant_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), rate_limiting_middleware))
+        .layer(middleware::from_fn(csrf_protection_middleware))
+        .layer(middleware::from_fn_with_state(app_state.clone(), jwt_auth_middleware));
+
+    // Combine routes with performance and security optimizations
+    Router::new()
+        .merge(limited_routes)
+        .merge(upload_routes)
+        .layer(middleware::from_fn_with_state(app_state.clone(), api_key_middleware)) // Authenticate service API keys
+        .layer(middleware::from_fn_with_state(app_state.clone(), host_tenant_middleware)) // Resolve the tenant
+        .layer(middleware::from_fn_with_state(app_state.clone(), locale_middleware)) // Negotiate the locale
         .layer(middleware::from_fn_with_state(app_state.clone(), track_metrics)) // Add metrics tracking
         .layer(middleware::from_fn_with_state(app_state.clone(), auth_metrics_middleware)) // Add auth metrics tracking
         .layer(middleware::from_fn(request_context_middleware)) // Add structured logging
@@ -109,12 +290,11 @@ pub fn create_app(database: Arc<dyn UserDatabase>, template_service: TemplateSer
         .layer(middleware::from_fn(security_headers_middleware))
         .layer(TraceLayer::new_for_http())
         .layer(CompressionLayer::new().gzip(true).br(true))
-        .layer(RequestBodyLimitLayer::new(1024 * 16)) // 16KB limit
         .layer(
             CorsLayer::new()
                 .allow_origin(Any)
-                .allow_methods([Method::GET, Method::POST, Method::OPTIONS])
-                .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+                .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
+                .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE, header::IF_MODIFIED_SINCE])
                 .max_age(Duration::from_secs(3600)),
         )
         .with_state(app_state)
@@ -1,9 +1,13 @@
 use anyhow::Result;
 use minijinja::{Environment, Value};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{Arc, RwLock};
 use tracing::{debug, info};
 
+use crate::i18n::{Catalog, DEFAULT_LOCALE, DEFAULT_LOCALES_DIR};
+use crate::tenant::Tenant;
+
 /// Template cache service for improved performance
 #[derive(Clone)]
 pub struct TemplateService {
@@ -11,13 +15,22 @@ pub struct TemplateService {
     cache_enabled: bool,
     template_cache: Arc<RwLock<HashMap<String, String>>>,
     minify_enabled: bool,
+    catalog: Arc<Catalog>,
 }
 
 impl TemplateService {
-    /// Create a new template service with caching and minification
+    /// Create a new template service with caching and minification, using the bundled locales
+    #[allow(dead_code)]
     pub fn new(cache_enabled: bool, minify_enabled: bool) -> Result<Self> {
+        let catalog = Catalog::load(Path::new(DEFAULT_LOCALES_DIR), DEFAULT_LOCALE)?;
+        Self::with_catalog(cache_enabled, minify_enabled, Arc::new(catalog))
+    }
+
+    /// Create a template service whose `t()` function translates from `catalog`
+    pub fn with_catalog(cache_enabled: bool, minify_enabled: bool, catalog: Arc<Catalog>) -> Result<Self> {
         let mut env = Environment::new();
         env.set_loader(minijinja::path_loader("templates"));
+        catalog.register_template_functions(&mut env);
 
         info!(
             "Template service initialized with caching: {}, minification: {}",
@@ -29,16 +42,32 @@ impl TemplateService {
             cache_enabled,
             template_cache: Arc::new(RwLock::new(HashMap::new())),
             minify_enabled,
+            catalog,
         })
     }
 
-    /// Render a template with the given context
-    pub fn render(&self, template_name: &str, context: Value) -> Result<String> {
+    /// Message catalog for UI strings and error messages
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+
+    /// Render a template with the given context for a tenant
+    ///
+    /// Cached output is keyed by tenant, so one brand's page is never served to another.
+    /// Pages pass their `locale` in the context, which `t()` translates into (and which
+    /// therefore also keys the cache).
+    pub fn render(&self, tenant: &Tenant, template_name: &str, context: Value) -> Result<String> {
         // For user-specific templates, always render fresh to avoid stale data
         let should_cache = self.cache_enabled && !self.is_user_specific_template(template_name);
 
         if should_cache {
-            let cache_key = format!("{}:{}:{}", template_name, self.context_hash(&context), self.minify_enabled);
+            let cache_key = format!(
+                "{}:{}:{}:{}",
+                tenant,
+                template_name,
+                self.context_hash(&context),
+                self.minify_enabled
+            );
 
             // Check cache first if enabled
             if let Some(cached_html) = self.get_cached_html(&cache_key) {
@@ -62,7 +91,7 @@ impl TemplateService {
 
             // Track template rendering metrics
             if let Some(metrics) = crate::router::get_metrics_instance() {
-                crate::metrics::track_template_rendering(metrics, template_name, duration);
+                crate::metrics::track_template_rendering(metrics, tenant, template_name, duration);
             }
 
             // Apply minification if enabled
@@ -93,7 +122,7 @@ impl TemplateService {
 
             // Track template rendering metrics
             if let Some(metrics) = crate::router::get_metrics_instance() {
-                crate::metrics::track_template_rendering(metrics, template_name, duration);
+                crate::metrics::track_template_rendering(metrics, tenant, template_name, duration);
             }
 
             // Apply minification if enabled
@@ -206,5 +235,15 @@ pub fn create_template_service() -> Result<TemplateService> {
         .parse()
         .unwrap_or(false);
 
-    TemplateService::new(cache_enabled, minify_enabled)
+    let locales_dir = std::env::var("LOCALES_DIR").unwrap_or_else(|_| DEFAULT_LOCALES_DIR.to_string());
+    let default_locale = std::env::var("DEFAULT_LOCALE").unwrap_or_else(|_| DEFAULT_LOCALE.to_string());
+    let catalog = Catalog::load(Path::new(&locales_dir), &default_locale)?;
+    info!(
+        "Loaded locales {} from {} (default: {})",
+        catalog.locales().collect::<Vec<_>>().join(", "),
+        locales_dir,
+        default_locale
+    );
+
+    TemplateService::with_catalog(cache_enabled, minify_enabled, Arc::new(catalog))
 }
@@ -0,0 +1,84 @@
+use axum::{extract::FromRequestParts, http::request::Parts};
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::errors::AppError;
+
+/// Tenant of deployments that do not configure tenancy, and of all pre-existing data
+pub const DEFAULT_TENANT: &str = "default";
+
+static TENANT_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_tenant_regex() -> &'static Regex {
+    TENANT_REGEX.get_or_init(|| Regex::new(r"^[a-z0-9][a-z0-9_-]{0,63}$").expect("Invalid tenant regex"))
+}
+
+/// The tenant (brand) a request belongs to; every stored user lives in exactly one tenant.
+///
+/// Resolved by the tenant middleware from the `Host` header, and from the JWT claim named by
+/// `TENANT_CLAIM` on authenticated routes. Handlers take it as an extractor.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tenant(String);
+
+impl Tenant {
+    /// Validate a tenant id; ids are case-insensitive and stored in lowercase
+    pub fn new(id: &str) -> Result<Self, AppError> {
+        let id = id.trim().to_ascii_lowercase();
+
+        if !get_tenant_regex().is_match(&id) {
+            return Err(AppError::validation_failed(
+                "Tenant must be 1-64 characters: letters, numbers, underscores and hyphens",
+            )
+            .localized("tenant-invalid", []));
+        }
+
+        Ok(Tenant(id))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.0 == DEFAULT_TENANT
+    }
+}
+
+impl Default for Tenant {
+    fn default() -> Self {
+        Tenant(DEFAULT_TENANT.to_string())
+    }
+}
+
+impl std::fmt::Display for Tenant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Tenant {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<Tenant>()
+            .cloned()
+            .ok_or_else(|| AppError::internal_server_error("Tenant was not resolved for this request"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenant_new() {
+        assert_eq!(Tenant::new(" Brand-A ").unwrap().as_str(), "brand-a");
+        assert!(Tenant::default().is_default());
+        assert!(Tenant::new("").is_err());
+        assert!(Tenant::new("brand/a").is_err());
+        assert!(Tenant::new("_brand").is_err());
+        assert!(Tenant::new(&"a".repeat(65)).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_admin_edits_other_users() {
+        let app = TestApp::default().build();
+        let admin = TestToken::new("carol").roles(&["editor", "admin"]);
+        let user = TestToken::new("alice").roles(&["editor"]);
+
+        let (status, body) = send(&app, "GET", "/edit/johndoe", &admin, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("John Doe"));
+        assert!(body.contains("Editing user:"));
+
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/api/username/johndoe",
+            &admin,
+            Some(r#"{"display_name":"Johnny"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["username"], "johndoe");
+        assert_eq!(json["display_name"], "Johnny");
+
+        // Admins edit existing users only
+        let (status, _) = send(&app, "GET", "/edit/nobody", &admin, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/username/nobody",
+            &admin,
+            Some(r#"{"display_name":"Nobody"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Other users keep editing only themselves
+        for (method, uri) in [("GET", "/edit/johndoe"), ("POST", "/api/username/johndoe")] {
+            let (status, body) = send(&app, method, uri, &user, Some(r#"{"display_name":"Hijacked"}"#)).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{method} {uri}");
+            let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(json["error"]["message_id"], "admin-role-required");
+        }
+        let (status, _) = send(&app, "POST", "/api/username", &user, Some(r#"{"display_name":"Alice"}"#)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The public read of the same path is unaffected
+        let request = Request::builder().uri("/api/username/johndoe").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["display_name"], "Johnny");
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use crate::middleware::jwt_auth::VerificationKeys;
+    use crate::tests::e2e_fixtures::{TestApp, TestToken};
+    use crate::tests::test_keys::{generate_key_pair, ED25519_KEY, P256_KEY, P384_KEY, RSA_KEY};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use jsonwebtoken::Algorithm;
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_signature_algorithm_families() {
+        let dir = tempfile::tempdir().unwrap();
+        let rsa = generate_key_pair(dir.path(), "rsa", RSA_KEY);
+        let p256 = generate_key_pair(dir.path(), "p256", P256_KEY);
+        let p384 = generate_key_pair(dir.path(), "p384", P384_KEY);
+        let ed25519 = generate_key_pair(dir.path(), "ed25519", ED25519_KEY);
+
+        let families = [
+            (Algorithm::RS256, &rsa),
+            (Algorithm::RS384, &rsa),
+            (Algorithm::PS256, &rsa),
+            (Algorithm::ES256, &p256),
+            (Algorithm::ES384, &p384),
+            (Algorithm::EdDSA, &ed25519),
+        ];
+
+        for (algorithm, key) in families {
+            let app = TestApp::default().issuer_key(key, algorithm).build();
+
+            // Only tokens signed with the configured algorithm are accepted
+            for (token_algorithm, key) in families {
+                let request = Request::builder()
+                    .uri("/edit")
+                    .header(header::AUTHORIZATION, TestToken::new("admin").bearer_with(token_algorithm, key))
+                    .body(Body::empty())
+                    .unwrap();
+                let response = app.clone().oneshot(request).await.unwrap();
+
+                let expected = if token_algorithm == algorithm {
+                    StatusCode::OK
+                } else {
+                    StatusCode::UNAUTHORIZED
+                };
+                assert_eq!(
+                    response.status(),
+                    expected,
+                    "{token_algorithm:?} token against {algorithm:?} verifier"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_type_must_match_algorithm() {
+        let dir = tempfile::tempdir().unwrap();
+        let rsa = generate_key_pair(dir.path(), "rsa", RSA_KEY).public_pem;
+        let p256 = generate_key_pair(dir.path(), "p256", P256_KEY).public_pem;
+        let p384 = generate_key_pair(dir.path(), "p384", P384_KEY).public_pem;
+        let ed25519 = generate_key_pair(dir.path(), "ed25519", ED25519_KEY).public_pem;
+
+        let mismatches = [
+            (&p256, Algorithm::RS256),
+            (&ed25519, Algorithm::PS256),
+            (&rsa, Algorithm::ES256),
+            (&p384, Algorithm::ES256),
+            (&p256, Algorithm::ES384),
+            (&ed25519, Algorithm::ES256),
+            (&rsa, Algorithm::EdDSA),
+            (&p256, Algorithm::EdDSA),
+        ];
+        for (public_key, algorithm) in mismatches {
+            let error = VerificationKeys::from_pem(public_key, algorithm).err().unwrap();
+            assert!(
+                format!("{error:#}").contains(&format!("{algorithm:?} requires")),
+                "unexpected error: {error:#}"
+            );
+        }
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::tenancy::{parse_tenant_hosts, TenancyConfig};
+    use crate::errors::AuthFailure;
+    use crate::tests::e2e_fixtures::{TestApp, TestToken};
+    use axum::body::Body;
+    use axum::extract::ConnectInfo;
+    use axum::http::{header, Request, StatusCode};
+    use std::net::SocketAddr;
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_service_api_keys() {
+        let tenancy = TenancyConfig {
+            hosts: parse_tenant_hosts("other.example.com=other").unwrap(),
+            ..TenancyConfig::default()
+        };
+        let app = TestApp::default().tenancy(tenancy).build();
+        let request = |method: &str, uri: &str, credential: (&'static str, String), body: String| {
+            let request = Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(credential.0, credential.1)
+                .header(header::CONTENT_TYPE, "application/json")
+                .extension(ConnectInfo(SocketAddr::from(([192, 0, 2, 10], 40000))))
+                .body(Body::from(body))
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default())
+            }
+        };
+        let admin = || ("authorization", TestToken::new("carol").roles(&["admin"]).bearer());
+        let api_key = |key: &serde_json::Value| ("x-api-key", key["key"].as_str().unwrap().to_string());
+        let create = |body: &str| request("POST", "/api/keys", admin(), body.to_string());
+
+        // Only admins manage keys
+        let user = ("authorization", TestToken::new("alice").bearer());
+        let (status, _) = request("POST", "/api/keys", user, r#"{"name":"sync","scope":"read"}"#.into()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, reader) = create(r#"{"name":"reader","scope":"read"}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(reader["key"].as_str().unwrap().starts_with("mfe_"));
+        assert_eq!(reader["created_by"], "carol");
+        let (status, writer) =
+            create(r#"{"name":"writer","scope":"write","allowed_ips":["192.0.2.0/24"],"quota_per_minute":2}"#).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(writer["allowed_ips"][0], "192.0.2.0/24");
+        let (status, _) = create(r#"{"name":"bad","scope":"write","allowed_ips":["192.0.2.0/99"]}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Listing never shows the keys themselves
+        let (status, list) = request("GET", "/api/keys", admin(), String::new()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(list["keys"].as_array().unwrap().len(), 2);
+        assert!(list["keys"].as_array().unwrap().iter().all(|key| key.get("key").is_none()));
+        assert!(list["keys"].as_array().unwrap().iter().all(|key| key.get("key_hash").is_none()));
+
+        // A read key reads but cannot write
+        let update = r#"{"display_name":"Synced Name"}"#.to_string();
+        let (status, json) = request("GET", "/api/username/johndoe", api_key(&reader), String::new()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["username"], "johndoe");
+        let (status, json) = request("POST", "/api/username/johndoe", api_key(&reader), update.clone()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(json["error"]["message_id"], "api-key-forbidden");
+
+        // A write key updates users, within its quota
+        let (status, json) = request("POST", "/api/username/johndoe", api_key(&writer), update.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["display_name"], "Synced Name");
+        let (status, _) = request("POST", "/api/username/johndoe", api_key(&writer), update.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = request("POST", "/api/username/johndoe", api_key(&writer), update.clone()).await;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        // Keys only stand in for a user on the /api/username routes
+        let (status, _) = request("POST", "/api/username", api_key(&writer), update.clone()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = request("GET", "/api/keys", api_key(&writer), String::new()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // Keys are restricted to their address ranges and tenant, and clients cannot claim another address
+        let outside = Request::builder()
+            .uri("/api/username/johndoe")
+            .method("POST")
+            .header("x-api-key", writer["key"].as_str().unwrap())
+            .header("x-forwarded-for", "192.0.2.10")
+            .header(header::CONTENT_TYPE, "application/json")
+            .extension(ConnectInfo(SocketAddr::from(([198, 51, 100, 1], 40000))))
+            .body(Body::from(update.clone()))
+            .unwrap();
+        let response = app.clone().oneshot(outside).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let other_tenant = Request::builder()
+            .uri("/api/username/johndoe")
+            .header(header::HOST, "other.example.com")
+            .header("x-api-key", reader["key"].as_str().unwrap())
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(other_tenant).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()[header::WWW_AUTHENTICATE],
+            r#"Bearer error="invalid_token", error_description="The API key is invalid""#
+        );
+        assert_eq!(
+            response.extensions().get::<AuthFailure>(),
+            Some(&AuthFailure("invalid_api_key"))
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"]["code"], "Unauthorized");
+        assert_eq!(json["error"]["message_id"], "api-key-invalid");
+
+        // Deleted keys stop working
+        let path = format!("/api/keys/{}", reader["id"].as_str().unwrap());
+        let (status, _) = request("DELETE", &path, admin(), String::new()).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = request("DELETE", &path, admin(), String::new()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = request("GET", "/api/username/johndoe", api_key(&reader), String::new()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
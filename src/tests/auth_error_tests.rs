@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_typed_auth_failures() {
+        let app = TestApp::default().build();
+        let token = TestToken::new("alice").jti("typed-1").issued_ago(10);
+        let valid = token.bearer();
+        let tampered = format!("{}AAAA", valid.trim_end_matches(|c: char| c != '.'));
+
+        let admin = TestToken::new("carol").roles(&["admin"]);
+        let (status, _) = send(&app, "POST", "/api/revocations", &admin, Some(r#"{"jti":"typed-1"}"#)).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let cases = [
+            (None, "token-missing"),
+            (Some("Bearer not-a-jwt".to_string()), "token-malformed"),
+            (Some(token.clone().expires_in(-120).bearer()), "token-expired"),
+            (Some(tampered), "token-signature-invalid"),
+            (Some(token.clone().audience("other-service").bearer()), "token-audience-invalid"),
+            (
+                Some(token.clone().issuer("https://evil.example.com").bearer()),
+                "token-issuer-invalid",
+            ),
+            (Some(valid), "token-revoked"),
+        ];
+        for (token, message_id) in cases {
+            let mut request = Request::builder().uri("/edit");
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, token);
+            }
+            let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{message_id}");
+
+            let challenge = response.headers()[header::WWW_AUTHENTICATE].to_str().unwrap().to_string();
+            if message_id == "token-missing" {
+                assert_eq!(challenge, "Bearer");
+            } else {
+                assert!(
+                    challenge.starts_with(r#"Bearer error="invalid_token", error_description=""#),
+                    "{challenge}"
+                );
+            }
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(json["error"]["code"], "Unauthorized");
+            assert_eq!(json["error"]["message_id"], message_id);
+        }
+    }
+}
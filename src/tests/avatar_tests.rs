@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt; // for oneshot
+
+    fn avatar_upload(username: &str, bytes: &[u8]) -> Request<Body> {
+        let boundary = "avatar-test-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"avatar.png\"\r\n\
+             Content-Type: image/png\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        Request::builder()
+            .uri("/api/avatar")
+            .method("POST")
+            .header(header::AUTHORIZATION, TestToken::new(username).bearer())
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={boundary}"))
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_avatar_upload_and_serving() {
+        let app = TestApp::default().build();
+
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgb8(64, 48)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let response = app.clone().oneshot(avatar_upload("testuser", &png)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let avatar_url = json["avatar_url"].as_str().unwrap().to_string();
+        let etag = json["etag"].as_str().unwrap().to_string();
+
+        // Versioned URLs are cached for a long time
+        let request = Request::builder().uri(&avatar_url).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        assert_eq!(response.headers()[header::ETAG], format!("\"{etag}\""));
+        assert!(response.headers()[header::CACHE_CONTROL]
+            .to_str()
+            .unwrap()
+            .contains("immutable"));
+
+        let request = Request::builder()
+            .uri("/avatar/testuser")
+            .header(header::IF_NONE_MATCH, format!("\"{etag}\""))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let (_, body) = send(&app, "GET", "/display/username/testuser", &TestToken::new("testuser"), None).await;
+        assert!(body.contains(r#"id="avatar""#));
+
+        // Files that are not images are rejected; oversized uploads hit the upload body limit
+        let response = app.clone().oneshot(avatar_upload("testuser", b"not an image")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .clone()
+            .oneshot(avatar_upload("testuser", &vec![0; 3 * 1024 * 1024]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let (status, _) = send(&app, "GET", "/avatar/johndoe", &TestToken::new("testuser"), None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Other routes keep the 16KB limit
+        let large_json = format!(r#"{{"display_name":"{}"}}"#, "a".repeat(20 * 1024));
+        let (status, _) = send(&app, "POST", "/api/username", &TestToken::new("testuser"), Some(&large_json)).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_identicon_fallback() {
+        let app = TestApp::default().build();
+
+        let request = Request::builder().uri("/avatar/johndoe.svg").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/svg+xml");
+        assert!(response.headers()[header::CACHE_CONTROL]
+            .to_str()
+            .unwrap()
+            .contains("immutable"));
+        let etag = response.headers()[header::ETAG].clone();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let svg = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(svg.starts_with("<svg"));
+
+        // Content depends only on the canonical username
+        let (status, body) = send(&app, "GET", "/avatar/JohnDoe.svg", &TestToken::new("testuser"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, svg);
+
+        let request = Request::builder()
+            .uri("/avatar/johndoe.svg")
+            .header(header::IF_NONE_MATCH, etag)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let (status, _) = send(&app, "GET", "/avatar/a.svg", &TestToken::new("testuser"), None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Users without an uploaded avatar get the identicon inlined on their display page
+        let (_, body) = send(&app, "GET", "/display/username/johndoe", &TestToken::new("testuser"), None).await;
+        assert!(body.contains(&svg));
+    }
+}
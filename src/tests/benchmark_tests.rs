@@ -1,14 +1,16 @@
 #[cfg(test)]
 mod tests {
     use crate::database::mock::MockUserDatabase;
-    use crate::handlers::get_api_username::get_api_username;
+    use crate::handlers::get_api_username::{get_api_username, AppQuery};
     use crate::handlers::get_display::get_display_username;
-    use crate::metrics::AppMetrics;
+    use crate::i18n::{Locale, RequestedLanguages};
     use crate::router::AppState;
     use crate::template::TemplateService;
+    use crate::tenant::Tenant;
     use crate::validation::ValidatedUsername;
 
-    use axum::extract::{Path, State};
+    use axum::extract::{Path, Query, State};
+    use axum::http::HeaderMap;
     use std::sync::Arc;
     use std::time::{Duration, Instant};
 
@@ -47,9 +49,8 @@ mod tests {
             let template_service = TemplateService::new(true, true).unwrap(); // Enable caching and minification
 
             let app_state = Arc::new(AppState {
-                database: db,
                 template_service,
-                metrics: AppMetrics::new_for_tests(),
+                ..AppState::for_tests(db.clone())
             });
 
             let state = State(app_state);
@@ -59,7 +60,16 @@ mod tests {
         };
 
         let run = |(state, username)| async move {
-            let _ = get_display_username(state, username).await;
+            let _ = get_display_username(
+                state,
+                Tenant::default(),
+                Locale::default(),
+                RequestedLanguages::default(),
+                username,
+                Query(AppQuery::default()),
+                HeaderMap::new(),
+            )
+            .await;
         };
 
         run_benchmark("display_handler", iterations, setup, run).await;
@@ -75,9 +85,8 @@ mod tests {
             let template_service = TemplateService::new(true, true).unwrap();
 
             let app_state = Arc::new(AppState {
-                database: db,
                 template_service,
-                metrics: AppMetrics::new_for_tests(),
+                ..AppState::for_tests(db.clone())
             });
 
             let state = State(app_state);
@@ -87,7 +96,15 @@ mod tests {
         };
 
         let run = |(state, username)| async move {
-            let _ = get_api_username(state, username).await;
+            let _ = get_api_username(
+                state,
+                Tenant::default(),
+                RequestedLanguages::default(),
+                username,
+                Query(AppQuery::default()),
+                HeaderMap::new(),
+            )
+            .await;
         };
 
         run_benchmark("api_handler", iterations, setup, run).await;
@@ -124,7 +141,7 @@ mod tests {
         };
 
         let run = |(template_service, context): (TemplateService, minijinja::value::Value)| async move {
-            let _ = template_service.render("display.html", context);
+            let _ = template_service.render(&Tenant::default(), "display.html", context);
         };
 
         let uncached_time = run_benchmark("template_rendering_uncached", iterations, setup, run).await;
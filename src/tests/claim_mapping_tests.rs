@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use crate::middleware::ClaimMapping;
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_claim_mapping_provides_username() {
+        let app = TestApp::default()
+            .claims(ClaimMapping {
+                username: "preferred_username".to_string(),
+                display_name: Some("name".to_string()),
+                ..ClaimMapping::default()
+            })
+            .build();
+        let anonymous = TestToken::new("f3b1c2d4-9a8e-4f6b-8c7d-1e2f3a4b5c6d");
+
+        // The handle comes from preferred_username, and the token's name is suggested on first edit
+        let dinah = anonymous
+            .clone()
+            .claim("preferred_username", "Dinah")
+            .claim("name", "Dinah Liddell");
+        let (status, body) = send(&app, "GET", "/edit", &dinah, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("Dinah Liddell"));
+
+        let (status, body) = send(&app, "POST", "/api/username", &dinah, Some(r#"{"display_name":"Dinah"}"#)).await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["username"], "dinah");
+
+        // A valid token without a usable mapped claim is forbidden, with a reason
+        for token in [
+            anonymous.clone(),
+            anonymous.clone().claim("preferred_username", "not a handle"),
+            anonymous.clone().claim("preferred_username", "alice").claim("name", 7),
+        ] {
+            let (status, body) = send(&app, "GET", "/edit", &token, None).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{token:?}");
+            let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(json["error"]["message_id"], "token-claim-invalid");
+        }
+    }
+}
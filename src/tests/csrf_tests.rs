@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use crate::middleware::{csrf_token, CSRF_TOKEN_HEADER};
+    use crate::tests::e2e_fixtures::{TestApp, TestToken};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_csrf_protection_for_cookie_sessions() {
+        let app = TestApp::default().build();
+        let send = |method: &str, uri: &str, headers: Vec<(&'static str, String)>| {
+            let mut request = Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(header::HOST, "example.com")
+                .header(header::CONTENT_TYPE, "application/json");
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            let request = request.body(Body::from(r#"{"display_name":"Cookie Name"}"#)).unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, String::from_utf8(bytes.to_vec()).unwrap())
+            }
+        };
+        let token = TestToken::new("alice").jwt();
+        let cookie = || ("cookie", format!("__Host-session={token}"));
+        let csrf = csrf_token(&token);
+
+        // The edit page embeds the session's CSRF token
+        let (status, body) = send("GET", "/edit", vec![cookie()]).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(&csrf));
+
+        // Cookie-authenticated writes need the token and must not come from another site
+        let (status, body) = send("POST", "/api/username", vec![cookie()]).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["error"]["message_id"], "csrf-check-failed");
+        for headers in [
+            vec![cookie(), (CSRF_TOKEN_HEADER, csrf_token("another-token"))],
+            vec![
+                cookie(),
+                (CSRF_TOKEN_HEADER, csrf.clone()),
+                ("sec-fetch-site", "cross-site".to_string()),
+            ],
+            vec![
+                cookie(),
+                (CSRF_TOKEN_HEADER, csrf.clone()),
+                ("origin", "https://evil.example".to_string()),
+            ],
+        ] {
+            let (status, _) = send("POST", "/api/username", headers.clone()).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{headers:?}");
+        }
+        let (status, _) = send(
+            "POST",
+            "/api/username",
+            vec![
+                cookie(),
+                (CSRF_TOKEN_HEADER, csrf.clone()),
+                ("origin", "https://example.com".to_string()),
+                ("sec-fetch-site", "same-origin".to_string()),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // The legacy jwt_token cookie is protected too, while bearer tokens are exempt
+        let (status, _) = send("POST", "/api/username", vec![("cookie", format!("jwt_token={token}"))]).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(
+            "POST",
+            "/api/username",
+            vec![
+                ("authorization", format!("Bearer {token}")),
+                ("origin", "https://host-app.example".to_string()),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -1,13 +1,15 @@
 #[cfg(test)]
 mod tests {
     use crate::database::{mock::MockUserDatabase, UserDatabase};
+    use crate::tenant::Tenant;
 
     #[tokio::test]
     async fn test_mock_database_get_user() {
         let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
 
         // Test getting an existing user
-        let user = db.get_user("testuser").await.unwrap();
+        let user = db.get_user(&tenant, "testuser").await.unwrap();
         assert!(user.is_some());
 
         let user = user.unwrap();
@@ -15,26 +17,27 @@ mod tests {
         assert_eq!(user.display_name, "testuser"); // Fixed to match actual implementation
 
         // Test getting a non-existent user
-        let user = db.get_user("nonexistent").await.unwrap();
+        let user = db.get_user(&tenant, "nonexistent").await.unwrap();
         assert!(user.is_none());
     }
 
     #[tokio::test]
     async fn test_mock_database_update_user() {
         let db = MockUserDatabase::new();
+        let tenant = Tenant::default();
 
         // Update an existing user
-        db.update_user_display_name("testuser", "Updated Name").await.unwrap();
+        db.update_user_display_name(&tenant, "testuser", "Updated Name").await.unwrap();
 
         // Verify the update
-        let user = db.get_user("testuser").await.unwrap().unwrap();
+        let user = db.get_user(&tenant, "testuser").await.unwrap().unwrap();
         assert_eq!(user.display_name, "Updated Name");
 
         // Test updating a non-existent user (should create it)
-        db.update_user_display_name("newuser", "New User").await.unwrap();
+        db.update_user_display_name(&tenant, "newuser", "New User").await.unwrap();
 
         // Verify the new user was created
-        let user = db.get_user("newuser").await.unwrap().unwrap();
+        let user = db.get_user(&tenant, "newuser").await.unwrap().unwrap();
         assert_eq!(user.username, "newuser");
         assert_eq!(user.display_name, "New User");
     }
@@ -0,0 +1,211 @@
+//! Signed test tokens and an app trusting them, for the end-to-end tests.
+
+use axum::body::Body;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use jsonwebtoken::{encode, Algorithm, Header};
+use serde_json::{Map, Value};
+use std::sync::{Arc, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tower::ServiceExt; // for oneshot
+
+use crate::config::moderation::ModerationConfig;
+use crate::config::tenancy::TenancyConfig;
+use crate::database::mock::MockUserDatabase;
+use crate::middleware::jwt_auth::{IssuerConfig, JwtConfig, JwtVerifier, VerificationKeys};
+use crate::middleware::ClaimMapping;
+use crate::router::{create_app, AppState};
+use crate::template::TemplateService;
+use crate::tests::test_keys::{generate_key_pair, KeyPair, RSA_KEY};
+
+/// Issuer and audience the test app trusts
+pub(crate) const ISSUER: &str = "test-auth-service";
+pub(crate) const AUDIENCE: &str = "micro-frontend-service";
+
+/// RS256 key of the test issuer, generated once per test run
+fn issuer_key() -> &'static KeyPair {
+    static KEY: OnceLock<KeyPair> = OnceLock::new();
+    KEY.get_or_init(|| {
+        let dir = tempfile::tempdir().unwrap();
+        generate_key_pair(dir.path(), "issuer", RSA_KEY)
+    })
+}
+
+fn now() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64
+}
+
+/// Claims of a test token, signed with the test issuer's key unless another one is given
+#[derive(Debug, Clone)]
+pub(crate) struct TestToken {
+    claims: Map<String, Value>,
+}
+
+impl TestToken {
+    /// Token for `sub` from the trusted issuer, issued now and valid for an hour
+    pub(crate) fn new(sub: &str) -> Self {
+        let now = now();
+        let claims = serde_json::json!({
+            "sub": sub,
+            "iat": now,
+            "exp": now + 3600,
+            "aud": AUDIENCE,
+            "iss": ISSUER,
+        });
+        Self {
+            claims: claims.as_object().unwrap().clone(),
+        }
+    }
+
+    pub(crate) fn tenant(self, tenant: &str) -> Self {
+        self.claim("tenant", tenant)
+    }
+
+    pub(crate) fn roles(self, roles: &[&str]) -> Self {
+        self.claim("roles", roles)
+    }
+
+    pub(crate) fn jti(self, jti: &str) -> Self {
+        self.claim("jti", jti)
+    }
+
+    /// Issued `seconds` ago; negative values issue the token in the future
+    pub(crate) fn issued_ago(self, seconds: i64) -> Self {
+        self.claim("iat", now() - seconds)
+    }
+
+    /// Expiring in `seconds`; negative values make an expired token
+    pub(crate) fn expires_in(self, seconds: i64) -> Self {
+        self.claim("exp", now() + seconds)
+    }
+
+    pub(crate) fn audience(self, audience: &str) -> Self {
+        self.claim("aud", audience)
+    }
+
+    pub(crate) fn issuer(self, issuer: &str) -> Self {
+        self.claim("iss", issuer)
+    }
+
+    /// Set any other claim, replacing its current value
+    pub(crate) fn claim(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.claims.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// The JWT, signed with the test issuer's RS256 key
+    pub(crate) fn jwt(&self) -> String {
+        self.jwt_with(Algorithm::RS256, issuer_key())
+    }
+
+    /// The JWT, signed with `key` using `algorithm`
+    pub(crate) fn jwt_with(&self, algorithm: Algorithm, key: &KeyPair) -> String {
+        encode(&Header::new(algorithm), &self.claims, &key.encoding_key(algorithm)).expect("Failed to sign test token")
+    }
+
+    /// `Authorization` header value carrying `jwt()`
+    pub(crate) fn bearer(&self) -> String {
+        format!("Bearer {}", self.jwt())
+    }
+
+    /// `Authorization` header value carrying `jwt_with(algorithm, key)`
+    pub(crate) fn bearer_with(&self, algorithm: Algorithm, key: &KeyPair) -> String {
+        format!("Bearer {}", self.jwt_with(algorithm, key))
+    }
+}
+
+/// App backed by a mock database, trusting tokens of the test issuer
+pub(crate) struct TestApp {
+    moderation: ModerationConfig,
+    tenancy: TenancyConfig,
+    key: Option<(String, Algorithm)>,
+    claims: ClaimMapping,
+}
+
+impl Default for TestApp {
+    fn default() -> Self {
+        Self {
+            moderation: ModerationConfig::default(),
+            tenancy: TenancyConfig::default(),
+            key: None,
+            claims: ClaimMapping {
+                roles: Some("roles".to_string()),
+                tenant: Some("tenant".to_string()),
+                ..ClaimMapping::default()
+            },
+        }
+    }
+}
+
+impl TestApp {
+    /// Hold display name changes for review
+    pub(crate) fn moderation(mut self, enabled: bool) -> Self {
+        self.moderation = ModerationConfig { enabled };
+        self
+    }
+
+    pub(crate) fn tenancy(mut self, tenancy: TenancyConfig) -> Self {
+        self.tenancy = tenancy;
+        self
+    }
+
+    /// Trust `algorithm` tokens signed with `key` instead of the issuer's RS256 key
+    pub(crate) fn issuer_key(mut self, key: &KeyPair, algorithm: Algorithm) -> Self {
+        self.key = Some((key.public_pem.clone(), algorithm));
+        self
+    }
+
+    /// Read the user from these claims instead of `sub`, `roles` and `tenant`
+    pub(crate) fn claims(mut self, claims: ClaimMapping) -> Self {
+        self.claims = claims;
+        self
+    }
+
+    pub(crate) fn build(self) -> Router {
+        let (public_key, algorithm) = self.key.unwrap_or_else(|| (issuer_key().public_pem.clone(), Algorithm::RS256));
+        let claims = self.claims;
+        let jwt = JwtVerifier::new(move || {
+            let mut issuer = IssuerConfig::new(
+                ISSUER,
+                VerificationKeys::from_pem(&public_key, algorithm)?,
+                algorithm,
+                &[AUDIENCE.to_string()],
+            )?;
+            issuer.claims = claims.clone();
+            Ok(JwtConfig::new(vec![issuer]))
+        })
+        .unwrap_or_else(|e| panic!("{algorithm:?} test issuer should load: {e:#}"));
+
+        create_app(AppState {
+            template_service: TemplateService::new(true, false).unwrap(),
+            moderation: self.moderation,
+            tenancy: self.tenancy,
+            jwt,
+            ..AppState::for_tests(Arc::new(MockUserDatabase::new()))
+        })
+    }
+}
+
+/// Send a request authenticated by `token`, with a JSON `body`, returning the status and body
+pub(crate) async fn send(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    token: &TestToken,
+    body: Option<&str>,
+) -> (StatusCode, String) {
+    let mut request = Request::builder()
+        .uri(uri)
+        .method(method)
+        .header(header::AUTHORIZATION, token.bearer());
+    if body.is_some() {
+        request = request.header(header::CONTENT_TYPE, "application/json");
+    }
+
+    let request = request.body(Body::from(body.unwrap_or_default().to_string())).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    (status, String::from_utf8(bytes.to_vec()).unwrap())
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::errors::{AppError, ErrorCode};
+    use crate::errors::{AppError, AuthError, AuthFailure, ErrorCode};
     use axum::http::StatusCode;
     use axum::response::IntoResponse;
 
@@ -23,6 +23,14 @@ mod tests {
         assert!(matches!(invalid_input.code, ErrorCode::InvalidInput));
         assert_eq!(invalid_input.message, "Invalid JSON");
 
+        let forbidden = AppError::forbidden("Moderator role required");
+        assert!(matches!(forbidden.code, ErrorCode::Forbidden));
+        assert_eq!(forbidden.message, "Moderator role required");
+
+        let too_large = AppError::payload_too_large("Avatar too large");
+        assert!(matches!(too_large.code, ErrorCode::PayloadTooLarge));
+        assert_eq!(too_large.message, "Avatar too large");
+
         let server_error = AppError::internal_server_error("Unknown error");
         assert!(matches!(server_error.code, ErrorCode::InternalServerError));
         assert_eq!(server_error.message, "Unknown error");
@@ -49,6 +57,12 @@ mod tests {
         let invalid_input = AppError::invalid_input("Bad input").into_response();
         assert_eq!(invalid_input.status(), StatusCode::BAD_REQUEST);
 
+        let forbidden = AppError::forbidden("Not allowed").into_response();
+        assert_eq!(forbidden.status(), StatusCode::FORBIDDEN);
+
+        let too_large = AppError::payload_too_large("Avatar too large").into_response();
+        assert_eq!(too_large.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
         let server_error = AppError::internal_server_error("Unknown error").into_response();
         assert_eq!(server_error.status(), StatusCode::INTERNAL_SERVER_ERROR);
     }
@@ -70,4 +84,26 @@ mod tests {
         let app_error = AppError::from(json_error);
         assert!(matches!(app_error.code, ErrorCode::InvalidInput));
     }
+
+    #[tokio::test]
+    async fn test_auth_error_responses() {
+        let response = AuthError::TokenExpired.into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            response.headers()["www-authenticate"],
+            r#"Bearer error="invalid_token", error_description="The token has expired""#
+        );
+        assert_eq!(response.extensions().get::<AuthFailure>(), Some(&AuthFailure("token_expired")));
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["error"]["code"], "Unauthorized");
+        assert_eq!(json["error"]["message_id"], "token-expired");
+
+        // A request without credentials gets a challenge without an error code (RFC 6750, section 3.1)
+        assert_eq!(AuthError::MissingToken.challenge(), "Bearer");
+        assert_eq!(
+            AuthError::TokenNotFresh { max_age: 600 }.challenge(),
+            r#"Bearer error="insufficient_user_authentication", error_description="A more recent authentication is required", max_age="600""#
+        );
+    }
 }
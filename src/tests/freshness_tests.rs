@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{TestApp, TestToken};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_token_age_and_freshness() {
+        let app = TestApp::default().build();
+        let send = |method: &str, uri: &str, token: String| {
+            let request = Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(header::AUTHORIZATION, token)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"display_name":"Fresh Name"}"#))
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let challenge = response
+                    .headers()
+                    .get(header::WWW_AUTHENTICATE)
+                    .map(|value| value.to_str().unwrap().to_string());
+                (response.status(), challenge)
+            }
+        };
+
+        // Tokens older than JWT_MAX_AGE_SECONDS, or issued in the future, are refused everywhere
+        let (status, challenge) = send("GET", "/edit", TestToken::new("alice").issued_ago(2 * 3600).bearer()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(challenge.unwrap().contains(r#"error="invalid_token""#));
+        let future = TestToken::new("alice").issued_ago(-600).bearer();
+        let (status, _) = send("GET", "/edit", future).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // A 15 minute old token may view the edit page, but not change the display name
+        let (status, _) = send("GET", "/edit", TestToken::new("alice").issued_ago(900).bearer()).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, challenge) = send("POST", "/api/username", TestToken::new("alice").issued_ago(900).bearer()).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let challenge = challenge.unwrap();
+        assert!(challenge.contains(r#"error="insufficient_user_authentication""#));
+        assert!(challenge.contains(r#"max_age="600""#));
+
+        let (status, _) = send("POST", "/api/username", TestToken::new("alice").issued_ago(60).bearer()).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -1,8 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::database::mock::MockUserDatabase;
-    use crate::router::create_app;
-    use crate::template::TemplateService;
+    use crate::router::{create_app, AppState};
     use axum::body::{Body, Bytes};
     use axum::http::{Request, StatusCode};
     use std::sync::Arc;
@@ -18,14 +17,11 @@ mod tests {
         // Create mock database
         let db = Arc::new(MockUserDatabase::new());
 
-        // Create template service with caching disabled for tests
-        let template_service = TemplateService::new(false, false).unwrap();
-
         // Reset metrics registry before creating the app to avoid AlreadyReg errors
         crate::metrics::AppMetrics::reset_registry();
 
         // Create app with mocks
-        create_app(db, template_service)
+        create_app(AppState::for_tests(db))
     }
 
     #[tokio::test]
@@ -110,6 +106,101 @@ mod tests {
         assert!(body_str.contains("\"display_name\":\"Administrator\""));
     }
 
+    async fn get_availability(app: axum::Router, username: &str) -> serde_json::Value {
+        let request = Request::builder()
+            .uri(format!("/api/username/{}/availability", username))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body_bytes = extract_body_bytes(response.into_body()).await;
+        serde_json::from_slice(&body_bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_username_availability() {
+        let app = setup_test_app().await;
+
+        // Unclaimed handle
+        let body = get_availability(app.clone(), "BrandNewUser").await;
+        assert_eq!(body["username"], "brandnewuser");
+        assert_eq!(body["available"], true);
+        assert_eq!(body["status"], "available");
+        assert!(body["suggestions"].as_array().unwrap().is_empty());
+
+        // Existing user (johndoe is in mock database)
+        let body = get_availability(app.clone(), "JohnDoe").await;
+        assert_eq!(body["available"], false);
+        assert_eq!(body["status"], "taken");
+        let suggestions = body["suggestions"].as_array().unwrap();
+        assert_eq!(suggestions.len(), 3);
+        assert_eq!(suggestions[0], "johndoe1");
+
+        // Reserved names, including route names
+        let body = get_availability(app.clone(), "metrics").await;
+        assert_eq!(body["status"], "reserved");
+        let body = get_availability(app.clone(), "display").await;
+        assert_eq!(body["status"], "reserved");
+
+        // Variants of reserved names are never suggested
+        let body = get_availability(app.clone(), "admin").await;
+        assert_eq!(body["status"], "reserved");
+        assert!(body["suggestions"].as_array().unwrap().is_empty());
+        let body = get_availability(app.clone(), "%D0%B0dmin").await; // Cyrillic а
+        assert_eq!(body["status"], "invalid");
+        assert!(body["suggestions"].as_array().unwrap().is_empty());
+
+        // Invalid handle still gets usable suggestions
+        let body = get_availability(app, "ab").await;
+        assert_eq!(body["status"], "invalid");
+        assert!(body["reason"].as_str().unwrap().contains("at least 3 characters"));
+        assert_eq!(body["suggestions"][0], "ab1");
+    }
+
+    #[tokio::test]
+    async fn test_username_availability_is_rate_limited() {
+        use crate::config::rate_limit::RateLimitConfig;
+        use crate::middleware::RateLimiter;
+        use axum::extract::ConnectInfo;
+        use std::net::SocketAddr;
+
+        let limit = 3;
+        let app = create_app(AppState {
+            rate_limiter: RateLimiter::new(RateLimitConfig {
+                max_requests: limit,
+                ..RateLimitConfig::default()
+            }),
+            ..AppState::for_tests(Arc::new(MockUserDatabase::new()))
+        });
+        let request = |peer: [u8; 4], forwarded_for: &str| {
+            Request::builder()
+                .uri("/api/username/johndoe/availability")
+                .header("x-forwarded-for", forwarded_for)
+                .extension(ConnectInfo(SocketAddr::from((peer, 40000))))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        // A new X-Forwarded-For value on each request does not reset an untrusted client's budget
+        for i in 0..limit {
+            let response = app
+                .clone()
+                .oneshot(request([192, 0, 2, 10], &format!("198.51.100.{i}")))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = app.clone().oneshot(request([192, 0, 2, 10], "198.51.100.99")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key("retry-after"));
+
+        // Other clients keep their own budget
+        let response = app.oneshot(request([192, 0, 2, 11], "198.51.100.99")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn test_static_endpoints() {
         let app = setup_test_app().await;
@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_localized_display_names() {
+        let app = TestApp::default().build();
+
+        let (status, body) = send(
+            &app,
+            "PUT",
+            "/api/username/locales/JA",
+            &TestToken::new("alice"),
+            Some(r#"{"display_name":"アリス"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name_locale"], "ja");
+
+        let get = |uri: &'static str, accept_language: &'static str| {
+            let app = app.clone();
+            async move {
+                let request = Request::builder()
+                    .uri(uri)
+                    .header(header::ACCEPT_LANGUAGE, accept_language)
+                    .body(Body::empty())
+                    .unwrap();
+                let response = app.oneshot(request).await.unwrap();
+                let vary = response.headers().get_all(header::VARY).iter().any(|v| v == "Accept-Language");
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (vary, String::from_utf8(bytes.to_vec()).unwrap())
+            }
+        };
+
+        // The best variant for the reader's languages, with the default as fallback
+        let (vary, body) = get("/api/username/alice", "ja-JP, en;q=0.8").await;
+        assert!(vary);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "アリス");
+        assert_eq!(json["display_name_locale"], "ja");
+
+        let (_, body) = get("/api/username/alice", "de, en;q=0.5").await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "Alice Smith");
+        assert!(json.get("display_name_locale").is_none());
+
+        let (vary, body) = get("/display/username/alice", "ja").await;
+        assert!(vary);
+        assert!(body.contains(r#"lang="ja">アリス"#));
+
+        // The lang query parameter and cookie pick the variant too, like the page language
+        let (_, body) = get("/api/username/alice?lang=ja", "de").await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "アリス");
+        let request = Request::builder()
+            .uri("/display/username/alice")
+            .header(header::ACCEPT_LANGUAGE, "de")
+            .header(header::COOKIE, "lang=ja")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert!(response.headers().get_all(header::VARY).iter().any(|v| v == "Cookie"));
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(String::from_utf8(bytes.to_vec()).unwrap().contains(r#"lang="ja">アリス"#));
+
+        let (status, _) = send(
+            &app,
+            "PUT",
+            "/api/username/locales/not_a_tag",
+            &TestToken::new("alice"),
+            Some(r#"{"display_name":"x"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send(&app, "DELETE", "/api/username/locales/ja", &TestToken::new("alice"), None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, body) = get("/api/username/alice", "ja").await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "Alice Smith");
+    }
+
+    #[tokio::test]
+    async fn test_localized_pages_and_errors() {
+        let app = TestApp::default().build();
+
+        let get = |uri: &'static str, headers: Vec<(header::HeaderName, &'static str)>| {
+            let app = app.clone();
+            async move {
+                let mut request = Request::builder().uri(uri);
+                for (name, value) in headers {
+                    request = request.header(name, value);
+                }
+                let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+                let status = response.status();
+                let content_language = response.headers().get(header::CONTENT_LANGUAGE).cloned();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, content_language, String::from_utf8(bytes.to_vec()).unwrap())
+            }
+        };
+
+        // Pages follow Accept-Language; the lang query parameter and cookie override it
+        let (_, content_language, body) =
+            get("/display/username/admin", vec![(header::ACCEPT_LANGUAGE, "fr-CA, en;q=0.5")]).await;
+        assert_eq!(content_language.unwrap(), "fr");
+        assert!(body.contains(r#"<html lang="fr">"#));
+        assert!(body.contains("Affichage du nom d’utilisateur"));
+
+        let (_, _, body) = get("/display/username/admin?lang=en", vec![(header::ACCEPT_LANGUAGE, "fr")]).await;
+        assert!(body.contains("Username Display"));
+
+        let (_, _, body) = get("/display/username/admin", vec![(header::COOKIE, "lang=fr")]).await;
+        assert!(body.contains("Affichage du nom d’utilisateur"));
+
+        // Unsupported languages fall back to English
+        let (_, content_language, body) = get("/display/username/admin", vec![(header::ACCEPT_LANGUAGE, "xx")]).await;
+        assert_eq!(content_language.unwrap(), "en");
+        assert!(body.contains(r#"<html lang="en">"#));
+
+        // Errors keep their stable code and message id, with the message in the reader's language
+        let (status, content_language, body) = get("/api/username/ab", vec![(header::ACCEPT_LANGUAGE, "fr")]).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(content_language.unwrap(), "fr");
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["error"]["code"], "ValidationFailed");
+        assert_eq!(json["error"]["message_id"], "username-too-short");
+        assert_eq!(
+            json["error"]["message"],
+            "Le nom d’utilisateur doit comporter au moins 3 caractères"
+        );
+
+        let (_, _, body) = get("/api/username/ab", vec![]).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["error"]["message"], "Username must be at least 3 characters long");
+    }
+}
@@ -9,12 +9,15 @@ mod tests {
 
         // The fact that we can create the metrics without panicking is a sufficient test
         // We can also increment some metrics to verify they work as expected
-        metrics.http_requests_total.with_label_values(&["GET", "/", "200"]).inc();
+        metrics
+            .http_requests_total
+            .with_label_values(&["GET", "/", "200", "default"])
+            .inc();
 
         // We can also observe a value in a histogram
         metrics
             .template_render_duration_seconds
-            .with_label_values(&["template_name"])
+            .with_label_values(&["template_name", "default"])
             .observe(0.1);
 
         // No assertions needed - if the above code runs without panic, the test passes
@@ -30,7 +30,10 @@ mod tests {
         let jwt_config = JwtConfig::from_env().unwrap();
 
         // Check that the config was properly initialized
-        assert_eq!(jwt_config.validation.algorithms, vec![jsonwebtoken::Algorithm::RS256]);
+        assert_eq!(
+            jwt_config.issuers[0].validation.algorithms,
+            vec![jsonwebtoken::Algorithm::RS256]
+        );
 
         // Restore original values
         if let Some(value) = original_key {
@@ -2,16 +2,33 @@
 //!
 //! This module aggregates all the unit and integration tests for the application.
 
+mod admin_tests;
+mod algorithm_tests;
+mod api_key_tests;
+mod auth_error_tests;
+mod avatar_tests;
 mod benchmark_tests;
+mod claim_mapping_tests;
+mod csrf_tests;
 mod database_tests;
+pub(crate) mod e2e_fixtures;
 mod errors_tests;
+mod freshness_tests;
 mod handler_tests;
 mod jwt_e2e_tests;
+mod locale_tests;
 mod logging_tests;
 mod metrics_tests;
 mod middleware_tests;
+mod moderation_tests;
+mod override_tests;
+mod profile_tests;
+mod revocation_tests;
 mod router_tests;
+mod session_tests;
 mod template_tests;
+mod tenant_tests;
+pub(crate) mod test_keys;
 mod validation_tests;
 
 // Add more test modules as needed
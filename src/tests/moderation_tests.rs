@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_display_name_moderation_flow() {
+        let app = TestApp::default().moderation(true).build();
+        let user = TestToken::new("testuser");
+        let moderator = TestToken::new("carol").roles(&["moderator"]);
+
+        // Submissions are held for review
+        let (status, body) = send(&app, "POST", "/api/username", &user, Some(r#"{"display_name":"Fresh Name"}"#)).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert!(body.contains(r#""display_name":"testuser""#));
+        assert!(body.contains(r#""pending_display_name":"Fresh Name""#));
+
+        let (_, body) = send(&app, "GET", "/api/username/testuser", &user, None).await;
+        assert!(!body.contains("Fresh Name"));
+
+        let (_, body) = send(&app, "GET", "/edit", &user, None).await;
+        assert!(body.contains("Awaiting review"));
+
+        // Only moderators can see or act on the queue
+        let (status, _) = send(&app, "GET", "/api/moderation/pending", &user, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&app, "POST", "/api/moderation/testuser/approve", &user, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = send(&app, "GET", "/api/moderation/pending", &moderator, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("Fresh Name"));
+
+        let (status, _) = send(&app, "POST", "/api/moderation/testuser/approve", &moderator, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, body) = send(&app, "GET", "/api/username/testuser", &user, None).await;
+        assert!(body.contains("Fresh Name"));
+
+        let (status, _) = send(&app, "POST", "/api/moderation/testuser/approve", &moderator, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Rejections need a reason and are shown to the user
+        send(&app, "POST", "/api/username", &user, Some(r#"{"display_name":"Spam Name"}"#)).await;
+
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/moderation/testuser/reject",
+            &moderator,
+            Some(r#"{"reason":"  "}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/api/moderation/testuser/reject",
+            &moderator,
+            Some(r#"{"reason":"Looks like spam"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains(r#""status":"rejected""#));
+
+        let (_, body) = send(&app, "GET", "/edit", &user, None).await;
+        assert!(body.contains("Looks like spam"));
+
+        let (_, body) = send(&app, "GET", "/api/username/testuser", &user, None).await;
+        assert!(body.contains("Fresh Name"));
+    }
+}
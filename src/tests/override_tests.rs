@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_display_name_overrides() {
+        let app = TestApp::default().build();
+
+        let (status, body) = send(
+            &app,
+            "PUT",
+            "/api/username/overrides/Billing",
+            &TestToken::new("johndoe"),
+            Some(r#"{"display_name":"Jonathan Doe"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["app"], "billing");
+        assert_eq!(json["display_name"], "Jonathan Doe");
+
+        // Readers in the app see the override, everyone else the default name
+        let (_, body) = send(
+            &app,
+            "GET",
+            "/api/username/johndoe?app=billing",
+            &TestToken::new("testuser"),
+            None,
+        )
+        .await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "Jonathan Doe");
+        assert_eq!(json["app"], "billing");
+
+        let (_, body) = send(&app, "GET", "/api/username/johndoe?app=chat", &TestToken::new("testuser"), None).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "John Doe");
+        assert!(json.get("app").is_none());
+
+        let (_, body) = send(
+            &app,
+            "GET",
+            "/display/username/johndoe?app=billing",
+            &TestToken::new("testuser"),
+            None,
+        )
+        .await;
+        assert!(body.contains("Jonathan Doe"));
+
+        let (status, _) = send(
+            &app,
+            "GET",
+            "/api/username/johndoe?app=not%20an%20app",
+            &TestToken::new("testuser"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // The edit component lists the overrides
+        let (_, body) = send(&app, "GET", "/edit", &TestToken::new("johndoe"), None).await;
+        assert!(body.contains(r#"data-key="billing""#));
+
+        let (status, _) = send(
+            &app,
+            "DELETE",
+            "/api/username/overrides/billing",
+            &TestToken::new("johndoe"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send(
+            &app,
+            "DELETE",
+            "/api/username/overrides/billing",
+            &TestToken::new("johndoe"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, body) = send(
+            &app,
+            "GET",
+            "/api/username/johndoe?app=billing",
+            &TestToken::new("testuser"),
+            None,
+        )
+        .await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "John Doe");
+
+        // Overrides follow the same rules as the display name
+        let (status, _) = send(
+            &app,
+            "PUT",
+            "/api/username/overrides/chat",
+            &TestToken::new("johndoe"),
+            Some(r#"{"display_name":""}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send(
+            &app,
+            "PUT",
+            "/api/username/overrides/chat",
+            &TestToken::new("nobody"),
+            Some(r#"{"display_name":"Nobody"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_post_profile_fields() {
+        let app = TestApp::default().build();
+
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/api/username",
+            &TestToken::new("alice"),
+            Some(r#"{"display_name":"Alice","pronouns":"she/her","bio":"Rust & tea","locale":"en-gb","website":"https://alice.example"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["pronouns"], "she/her");
+        assert_eq!(json["locale"], "en-GB");
+        assert_eq!(json["website"], "https://alice.example/");
+        assert!(json["timezone"].is_null());
+
+        // Clients sending only the display name leave the profile untouched
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/username",
+            &TestToken::new("alice"),
+            Some(r#"{"display_name":"Alice S."}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, body) = send(&app, "GET", "/api/username/alice", &TestToken::new("alice"), None).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "Alice S.");
+        assert_eq!(json["bio"], "Rust & tea");
+
+        let (_, body) = send(&app, "GET", "/display/username/alice", &TestToken::new("alice"), None).await;
+        assert!(body.contains("Rust &amp; tea"));
+        assert!(body.contains(r#"id="pronouns""#));
+
+        // Empty strings clear a field; invalid fields reject the whole update
+        let (_, body) = send(
+            &app,
+            "POST",
+            "/api/username",
+            &TestToken::new("alice"),
+            Some(r#"{"display_name":"Alice","bio":""}"#),
+        )
+        .await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert!(json["bio"].is_null());
+        assert_eq!(json["pronouns"], "she/her");
+
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/username",
+            &TestToken::new("alice"),
+            Some(r#"{"display_name":"Changed","website":"javascript:alert(1)"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (_, body) = send(&app, "GET", "/api/username/alice", &TestToken::new("alice"), None).await;
+        assert!(body.contains(r#""display_name":"Alice""#));
+    }
+}
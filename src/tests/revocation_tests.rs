@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use crate::tests::e2e_fixtures::{send, TestApp, TestToken};
+    use axum::http::StatusCode;
+
+    #[tokio::test]
+    async fn test_revoked_tokens_are_rejected() {
+        let app = TestApp::default().build();
+        let admin = TestToken::new("carol").roles(&["admin"]);
+
+        // Revoking one token leaves the subject's other tokens valid
+        let (status, _) = send(&app, "GET", "/edit", &TestToken::new("alice").jti("token-1"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = send(&app, "POST", "/api/revocations", &admin, Some(r#"{"jti":"token-1"}"#)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["jti"], "token-1");
+        assert_eq!(json["issuer"], "test-auth-service");
+        let (status, _) = send(&app, "GET", "/edit", &TestToken::new("alice").jti("token-1"), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(&app, "GET", "/edit", &TestToken::new("alice").jti("token-2"), None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Revoking a subject rejects only tokens issued before the cut-off
+        let issued_before = chrono::Utc::now() - chrono::Duration::seconds(60);
+        let body = serde_json::json!({ "subject": "alice", "issued_before": issued_before }).to_string();
+        let (status, _) = send(&app, "POST", "/api/revocations", &admin, Some(&body)).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, _) = send(
+            &app,
+            "GET",
+            "/edit",
+            &TestToken::new("alice").jti("token-3").issued_ago(120),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(&app, "GET", "/edit", &TestToken::new("alice").jti("token-4"), None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Exactly one target is required, and only admins may revoke
+        for body in [
+            r#"{}"#,
+            r#"{"jti":"token-5","subject":"alice"}"#,
+            r#"{"subject":""}"#,
+            r#"{"subject":"alice","issuer":"untrusted-issuer"}"#,
+        ] {
+            let (status, _) = send(&app, "POST", "/api/revocations", &admin, Some(body)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{body}");
+        }
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/revocations",
+            &TestToken::new("alice"),
+            Some(r#"{"subject":"carol"}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+}
@@ -1,11 +1,13 @@
 #[cfg(test)]
 mod tests {
     use crate::database::mock::MockUserDatabase;
-    use crate::metrics::AppMetrics;
+    use crate::errors::{AppError, AuthError};
+    use crate::middleware::{auth_metrics_middleware, AuthenticatedUser};
     use crate::router::AppState;
-    use crate::template::TemplateService;
+    use crate::tenant::Tenant;
     use axum::body::Body;
     use axum::http::{Request, StatusCode};
+    use axum::response::IntoResponse;
     use std::sync::Arc;
     use tower::ServiceExt; // for oneshot
 
@@ -14,17 +16,9 @@ mod tests {
         // Create mock database
         let db = Arc::new(MockUserDatabase::new());
 
-        // Create template service with caching disabled for tests
-        let template_service = TemplateService::new(false, false).unwrap();
-
         // For tests, we'll initialize the app state directly to avoid
         // creating new Prometheus metrics (which would fail on subsequent test runs)
-        let app_state = Arc::new(AppState {
-            database: db.clone(),
-            template_service: template_service.clone(),
-            // Use the test-specific metrics implementation
-            metrics: AppMetrics::new_for_tests(),
-        });
+        let app_state = Arc::new(AppState::for_tests(db));
 
         // Create a simplified test router
         let app = axum::Router::new()
@@ -41,5 +35,75 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_auth_failure_reason_label() {
+        let db = Arc::new(MockUserDatabase::new());
+        let app_state = Arc::new(AppState::for_tests(db));
+
+        let app = axum::Router::new()
+            .route(
+                "/admin",
+                axum::routing::get(|| async {
+                    Err::<(), _>(AppError::forbidden("Admin role required").auth_failure("admin_role_required"))
+                }),
+            )
+            .route("/other", axum::routing::get(|| async { StatusCode::FORBIDDEN }))
+            .route("/revoked", axum::routing::get(|| async { AuthError::TokenRevoked }))
+            .route("/expired", axum::routing::get(|| async { AuthError::TokenExpired }))
+            .route("/unlabelled", axum::routing::get(|| async { StatusCode::UNAUTHORIZED }))
+            .route(
+                "/authenticated",
+                axum::routing::get(|| async {
+                    let mut response = StatusCode::OK.into_response();
+                    response.extensions_mut().insert(AuthenticatedUser {
+                        username: "alice".to_string(),
+                        subject: "alice".to_string(),
+                        issuer: "test-auth-service".to_string(),
+                        audience: "micro-frontend-service".into(),
+                        display_name: None,
+                        roles: Vec::new(),
+                        tenant: None,
+                        issued_at: 0,
+                    });
+                    response
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(app_state.clone(), auth_metrics_middleware))
+            .with_state(app_state.clone());
+
+        for uri in ["/admin", "/admin", "/other"] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+        for uri in ["/revoked", "/expired", "/unlabelled"] {
+            let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let failures = |reason: &str| {
+            app_state
+                .metrics
+                .auth_failure_total
+                .with_label_values(&[reason, Tenant::default().as_str()])
+                .get()
+        };
+        assert_eq!(failures("admin_role_required"), 2);
+        assert_eq!(failures("insufficient_permissions"), 1);
+        assert_eq!(failures("token_revoked"), 1);
+        assert_eq!(failures("token_expired"), 1);
+        assert_eq!(failures("invalid_token"), 1);
+
+        let request = Request::builder().uri("/authenticated").body(Body::empty()).unwrap();
+        assert_eq!(app.clone().oneshot(request).await.unwrap().status(), StatusCode::OK);
+        let successes = app_state
+            .metrics
+            .auth_success_total
+            .with_label_values(&["test-auth-service", Tenant::default().as_str()])
+            .get();
+        assert_eq!(successes, 1);
+    }
+
     // More route tests would follow a similar pattern
 }
@@ -0,0 +1,95 @@
+#[cfg(test)]
+mod tests {
+    use crate::middleware::{csrf_token, CSRF_TOKEN_HEADER};
+    use crate::tests::e2e_fixtures::{TestApp, TestToken};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_session_cookie_exchange() {
+        let app = TestApp::default().build();
+        let send = |uri: &str, method: &str, headers: Vec<(header::HeaderName, String)>| {
+            let mut request = Request::builder().uri(uri).method(method);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            let request = request.body(Body::empty()).unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let cookie = response
+                    .headers()
+                    .get(header::SET_COOKIE)
+                    .map(|value| value.to_str().unwrap().to_string());
+                (response.status(), cookie)
+            }
+        };
+
+        // The bearer token is exchanged for an HttpOnly cookie that expires with it
+        let (status, cookie) = send(
+            "/auth/session",
+            "POST",
+            vec![(header::AUTHORIZATION, TestToken::new("alice").bearer())],
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let cookie = cookie.unwrap();
+        assert!(cookie.starts_with("__Host-session="));
+        for attribute in ["Path=/", "HttpOnly", "Secure", "SameSite=Strict"] {
+            assert!(cookie.contains(attribute), "{cookie}");
+        }
+        let max_age: u64 = cookie
+            .split("Max-Age=")
+            .nth(1)
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(max_age > 3500 && max_age <= 3600, "{max_age}");
+
+        // The cookie alone authenticates, and wins over a bearer token for another user
+        let session = cookie.split(';').next().unwrap().to_string();
+        let (status, _) = send("/edit", "GET", vec![(header::COOKIE, session.clone())]).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(
+            "/api/revocations",
+            "POST",
+            vec![
+                (header::COOKIE, session.clone()),
+                (header::AUTHORIZATION, TestToken::new("carol").roles(&["admin"]).bearer()),
+                (
+                    header::HeaderName::from_static(CSRF_TOKEN_HEADER),
+                    csrf_token(&session["__Host-session=".len()..]),
+                ),
+            ],
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Only a valid bearer token can start a session
+        let (status, cookie) = send("/auth/session", "POST", vec![]).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(cookie.is_none());
+        let (status, _) = send(
+            "/auth/session",
+            "POST",
+            vec![(header::AUTHORIZATION, "Bearer invalid.token.here".to_string())],
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = send(
+            "/auth/session",
+            "POST",
+            vec![(header::COOKIE, format!("jwt_token={}", TestToken::new("alice").jwt()))],
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, cookie) = send("/auth/logout", "POST", vec![]).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(cookie.unwrap().starts_with("__Host-session=; Max-Age=0;"));
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod tests {
+    use crate::config::tenancy::{parse_tenant_hosts, TenancyConfig};
+    use crate::tenant::Tenant;
+    use crate::tests::e2e_fixtures::{TestApp, TestToken};
+    use axum::body::Body;
+    use axum::http::{header, Request, StatusCode};
+    use tower::ServiceExt; // for oneshot
+
+    #[tokio::test]
+    async fn test_tenant_isolation() {
+        let app = TestApp::default()
+            .tenancy(TenancyConfig {
+                claim: Some("tenant".to_string()),
+                hosts: parse_tenant_hosts("brand-a.example=brand-a").unwrap(),
+                default_tenant: Tenant::default(),
+            })
+            .build();
+
+        let request = |method: &str, uri: &str, host: &str, token: Option<String>, body: &'static str| {
+            let mut request = Request::builder()
+                .uri(uri)
+                .method(method)
+                .header(header::HOST, host)
+                .header(header::CONTENT_TYPE, "application/json");
+            if let Some(token) = token {
+                request = request.header(header::AUTHORIZATION, token);
+            }
+            let request = request.body(Body::from(body)).unwrap();
+            let app = app.clone();
+            async move {
+                let response = app.oneshot(request).await.unwrap();
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, String::from_utf8(bytes.to_vec()).unwrap())
+            }
+        };
+
+        // "alice" on brand-a is a different user from the seeded default-tenant "alice"
+        let token = TestToken::new("alice").tenant("brand-a").bearer();
+        let (status, _) = request(
+            "POST",
+            "/api/username",
+            "brand-a.example",
+            Some(token),
+            r#"{"display_name":"Alice Brand"}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, body) = request("GET", "/api/username/alice", "brand-a.example", None, "").await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "Alice Brand");
+
+        let (_, body) = request("GET", "/api/username/alice", "localhost:3000", None, "").await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["display_name"], "Alice Smith");
+
+        let (status, _) = request("GET", "/api/username/admin", "brand-a.example", None, "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Tokens must name the tenant, and the tenant of the host they are used on
+        let token = TestToken::new("alice").tenant("brand-b").bearer();
+        let (status, _) = request("GET", "/edit", "brand-a.example", Some(token), "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = request("GET", "/edit", "brand-a.example", Some(TestToken::new("alice").bearer()), "").await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // On an unmapped host the claim alone selects the tenant
+        let token = TestToken::new("alice").tenant("brand-a").bearer();
+        let (status, body) = request("GET", "/edit", "localhost:3000", Some(token), "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("Alice Brand"));
+    }
+}
@@ -0,0 +1,115 @@
+//! Signing keys for JWT tests, generated with openssl.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// `openssl genpkey` arguments for each key type
+pub(crate) const RSA_KEY: &[&str] = &["-algorithm", "RSA", "-pkeyopt", "rsa_keygen_bits:2048"];
+pub(crate) const P256_KEY: &[&str] = &["-algorithm", "EC", "-pkeyopt", "ec_paramgen_curve:P-256"];
+pub(crate) const P384_KEY: &[&str] = &["-algorithm", "EC", "-pkeyopt", "ec_paramgen_curve:P-384"];
+pub(crate) const ED25519_KEY: &[&str] = &["-algorithm", "ed25519"];
+
+/// Key pair written to `<name>_private.pem` and `<name>_public.pem`
+pub(crate) struct KeyPair {
+    /// PKCS#8 private key, as PEM
+    pub private_pem: String,
+    /// Public key, as PEM
+    pub public_pem: String,
+    pub private_key_file: PathBuf,
+    pub public_key_file: PathBuf,
+}
+
+/// Generate a key pair with `openssl genpkey`
+pub(crate) fn generate_key_pair(dir: &Path, name: &str, genpkey_args: &[&str]) -> KeyPair {
+    let private_key_file = dir.join(format!("{name}_private.pem"));
+    let public_key_file = dir.join(format!("{name}_public.pem"));
+
+    openssl(
+        Command::new("openssl")
+            .arg("genpkey")
+            .args(genpkey_args)
+            .arg("-out")
+            .arg(&private_key_file),
+    );
+    openssl(
+        Command::new("openssl")
+            .args(["pkey", "-pubout", "-in"])
+            .arg(&private_key_file)
+            .arg("-out")
+            .arg(&public_key_file),
+    );
+
+    KeyPair {
+        private_pem: std::fs::read_to_string(&private_key_file).unwrap(),
+        public_pem: std::fs::read_to_string(&public_key_file).unwrap(),
+        private_key_file,
+        public_key_file,
+    }
+}
+
+fn openssl(command: &mut Command) -> Vec<u8> {
+    let output = command.output().expect("Failed to execute openssl");
+    assert!(output.status.success(), "{command:?} failed");
+    output.stdout
+}
+
+impl KeyPair {
+    /// Key signing tokens with `algorithm`
+    pub(crate) fn encoding_key(&self, algorithm: Algorithm) -> EncodingKey {
+        match algorithm {
+            Algorithm::ES256 | Algorithm::ES384 => EncodingKey::from_ec_pem(self.private_pem.as_bytes()),
+            Algorithm::EdDSA => EncodingKey::from_ed_pem(self.private_pem.as_bytes()),
+            _ => EncodingKey::from_rsa_pem(self.private_pem.as_bytes()),
+        }
+        .expect("Failed to parse private key")
+    }
+
+    /// Modulus of an RSA key, big-endian
+    fn rsa_modulus(&self) -> Vec<u8> {
+        let output = openssl(
+            Command::new("openssl")
+                .args(["rsa", "-noout", "-modulus", "-in"])
+                .arg(&self.private_key_file),
+        );
+        let modulus = String::from_utf8(output).unwrap();
+        let modulus = modulus.trim().trim_start_matches("Modulus=");
+        (0..modulus.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&modulus[i..i + 2], 16).unwrap())
+            .collect()
+    }
+}
+
+/// RSA key identified by `kid`, with its public half as a JWK
+pub(crate) struct TestKey {
+    pub kid: String,
+    pub encoding_key: EncodingKey,
+    pub jwk: serde_json::Value,
+}
+
+impl TestKey {
+    pub(crate) fn generate(dir: &Path, kid: &str) -> Self {
+        let pair = generate_key_pair(dir, kid, RSA_KEY);
+        Self {
+            kid: kid.to_string(),
+            encoding_key: pair.encoding_key(Algorithm::RS256),
+            jwk: serde_json::json!({
+                "kty": "RSA",
+                "use": "sig",
+                "alg": "RS256",
+                "kid": kid,
+                "n": URL_SAFE_NO_PAD.encode(pair.rsa_modulus()),
+                "e": "AQAB",
+            }),
+        }
+    }
+
+    pub(crate) fn sign(&self, claims: &serde_json::Value) -> String {
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(self.kid.clone());
+        encode(&header, claims, &self.encoding_key).unwrap()
+    }
+}
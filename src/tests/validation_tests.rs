@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::errors::ErrorCode;
-    use crate::validation::{ValidatedDisplayName, ValidatedUsername};
+    use crate::validation::{ValidatedAppKey, ValidatedDisplayName, ValidatedUsername};
 
     #[test]
     fn test_validated_username_new() {
@@ -64,4 +64,17 @@ mod tests {
         let display_name = ValidatedDisplayName::new("John Doe".to_string()).unwrap();
         assert_eq!(format!("{}", display_name), "John Doe");
     }
+
+    #[test]
+    fn test_validated_app_key_new() {
+        assert_eq!(ValidatedAppKey::new("billing").unwrap().as_str(), "billing");
+        assert_eq!(ValidatedAppKey::new(" Chat.Example.com ").unwrap().as_str(), "chat.example.com");
+        assert_eq!(ValidatedAppKey::new("app_2-beta").unwrap().to_string(), "app_2-beta");
+
+        assert!(ValidatedAppKey::new("").is_err());
+        assert!(ValidatedAppKey::new(".hidden").is_err());
+        assert!(ValidatedAppKey::new("two words").is_err());
+        assert!(ValidatedAppKey::new("app/path").is_err());
+        assert!(ValidatedAppKey::new(&"a".repeat(65)).is_err());
+    }
 }
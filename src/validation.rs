@@ -1,6 +1,17 @@
 use crate::errors::AppError;
+use anyhow::Context;
 use regex::Regex;
+use std::collections::HashSet;
 use std::sync::OnceLock;
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::{confusable_detection::skeleton, MixedScript};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Maximum display name length in user-perceived characters (extended grapheme clusters)
+pub const DISPLAY_NAME_MAX_GRAPHEMES: usize = 100;
+
+/// Upper bound on code points, so a few heavily combined graphemes cannot overflow the storage column
+pub const DISPLAY_NAME_MAX_CODE_POINTS: usize = 255;
 
 // Username validation regex (Twitter-style handles: alphanumeric, underscores, hyphens)
 static USERNAME_REGEX: OnceLock<Regex> = OnceLock::new();
@@ -9,13 +20,30 @@ fn get_username_regex() -> &'static Regex {
     USERNAME_REGEX.get_or_init(|| Regex::new(r"^[a-zA-Z0-9_-]{3,50}$").expect("Invalid username regex"))
 }
 
+/// A validated username in its canonical (lowercase) form
 #[derive(Debug, Clone)]
 pub struct ValidatedUsername(String);
 
 impl ValidatedUsername {
+    /// Validate a username that is about to be claimed; reserved names are rejected
     pub fn new(username: String) -> Result<Self, AppError> {
+        let validated = Self::existing(username)?;
+
+        if is_reserved_username(validated.as_str()) {
+            return Err(AppError::validation_failed(format!("Username '{}' is reserved", validated))
+                .localized("username-reserved", [("username", validated.to_string().into())]));
+        }
+
+        Ok(validated)
+    }
+
+    /// Validate a username that refers to an existing account.
+    ///
+    /// Reserved names are accepted here because accounts may predate the reserved list
+    /// (e.g. the seeded `admin` user).
+    pub fn existing(username: String) -> Result<Self, AppError> {
         validate_username(&username)?;
-        Ok(ValidatedUsername(username))
+        Ok(ValidatedUsername(canonical_username(&username)))
     }
 
     pub fn as_str(&self) -> &str {
@@ -57,24 +85,160 @@ impl std::fmt::Display for ValidatedDisplayName {
     }
 }
 
+// Application keys for display name overrides, e.g. `billing` or `chat.example.com`
+static APP_KEY_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_app_key_regex() -> &'static Regex {
+    APP_KEY_REGEX.get_or_init(|| Regex::new(r"^[a-z0-9][a-z0-9._-]{0,63}$").expect("Invalid app key regex"))
+}
+
+/// A validated application key in its canonical (lowercase) form
+#[derive(Debug, Clone)]
+pub struct ValidatedAppKey(String);
+
+impl ValidatedAppKey {
+    pub fn new(app: &str) -> Result<Self, AppError> {
+        let app = app.trim().to_lowercase();
+
+        if !get_app_key_regex().is_match(&app) {
+            return Err(AppError::validation_failed(
+                "App must be 1-64 characters: letters, numbers, dots, underscores and hyphens",
+            )
+            .localized("app-key-invalid", []));
+        }
+
+        Ok(ValidatedAppKey(app))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for ValidatedAppKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Usernames that can never be claimed, in addition to route names and `RESERVED_USERNAMES_FILE`
+pub const DEFAULT_RESERVED_USERNAMES: &[&str] = &[
+    "about",
+    "admin",
+    "administrator",
+    "api",
+    "assets",
+    "auth",
+    "avatar",
+    "debug",
+    "edit",
+    "health",
+    "help",
+    "login",
+    "logout",
+    "metrics",
+    "moderator",
+    "null",
+    "root",
+    "settings",
+    "static",
+    "support",
+    "system",
+    "undefined",
+    "www",
+];
+
+static RESERVED_USERNAMES: OnceLock<HashSet<String>> = OnceLock::new();
+
+/// Load the reserved username list, extending the defaults with `RESERVED_USERNAMES_FILE` if set.
+///
+/// Called once at startup so a missing or unreadable file fails fast; without it the
+/// defaults and route names are used.
+pub fn init_reserved_usernames() -> anyhow::Result<()> {
+    let mut reserved = default_reserved_usernames();
+
+    if let Ok(path) = std::env::var("RESERVED_USERNAMES_FILE") {
+        let contents =
+            std::fs::read_to_string(&path).with_context(|| format!("Failed to read reserved usernames from {path}"))?;
+        let names = parse_reserved_usernames(&contents);
+        tracing::info!("Loaded {} reserved usernames from {}", names.len(), path);
+        reserved.extend(names);
+    }
+
+    if RESERVED_USERNAMES.set(reserved).is_err() {
+        tracing::warn!("Reserved usernames were already initialized");
+    }
+
+    Ok(())
+}
+
+/// Parse a reserved username file: one name per line, blank lines and `#` comments ignored
+pub fn parse_reserved_usernames(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(canonical_username)
+        .collect()
+}
+
+/// Whether a username is reserved (built-in list, route names or the configured file)
+pub fn is_reserved_username(username: &str) -> bool {
+    RESERVED_USERNAMES
+        .get_or_init(default_reserved_usernames)
+        .contains(&canonical_username(username))
+}
+
+/// Whether a handle is a reserved username dressed up with a `the` prefix, trailing digits or
+/// separators, such as `the_admin`, `admin_26` or `ad-min`
+pub fn resembles_reserved_username(username: &str) -> bool {
+    let username = canonical_username(username);
+    let trimmed = username
+        .trim_start_matches("the")
+        .trim_matches(|c: char| c.is_ascii_digit() || c == '_' || c == '-');
+    let letters: String = username.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+
+    [username.as_str(), trimmed, letters.as_str()]
+        .iter()
+        .any(|form| !form.is_empty() && is_reserved_username(form))
+}
+
+fn default_reserved_usernames() -> HashSet<String> {
+    DEFAULT_RESERVED_USERNAMES
+        .iter()
+        .map(|name| name.to_string())
+        .chain(crate::router::route_names())
+        .collect()
+}
+
+/// Canonical form of a username, used for storage, lookups and cache keys.
+///
+/// Usernames are ASCII-only, so `Admin` and `admin` always identify the same user.
+pub fn canonical_username(username: &str) -> String {
+    username.to_ascii_lowercase()
+}
+
 /// Validate username format and length
 pub fn validate_username(username: &str) -> Result<(), AppError> {
     if username.is_empty() {
-        return Err(AppError::validation_failed("Username cannot be empty"));
+        return Err(AppError::validation_failed("Username cannot be empty").localized("username-empty", []));
     }
 
     if username.len() < 3 {
-        return Err(AppError::validation_failed("Username must be at least 3 characters long"));
+        return Err(AppError::validation_failed("Username must be at least 3 characters long")
+            .localized("username-too-short", [("min", 3u32.into())]));
     }
 
     if username.len() > 50 {
-        return Err(AppError::validation_failed("Username must be at most 50 characters long"));
+        return Err(AppError::validation_failed("Username must be at most 50 characters long")
+            .localized("username-too-long", [("max", 50u32.into())]));
     }
 
     if !get_username_regex().is_match(username) {
         return Err(AppError::validation_failed(
             "Username must contain only alphanumeric characters, underscores, and hyphens",
-        ));
+        )
+        .localized("username-invalid-characters", []));
     }
 
     Ok(())
@@ -83,16 +247,20 @@ pub fn validate_username(username: &str) -> Result<(), AppError> {
 /// Validate display name format and length
 pub fn validate_display_name(display_name: &str) -> Result<(), AppError> {
     if display_name.is_empty() {
-        return Err(AppError::validation_failed("Display name cannot be empty"));
+        return Err(AppError::validation_failed("Display name cannot be empty").localized("display-name-empty", []));
     }
 
-    if display_name.len() > 100 {
-        return Err(AppError::validation_failed("Display name must be at most 100 characters long"));
+    if display_name.graphemes(true).count() > DISPLAY_NAME_MAX_GRAPHEMES
+        || display_name.chars().count() > DISPLAY_NAME_MAX_CODE_POINTS
+    {
+        return Err(AppError::validation_failed("Display name must be at most 100 characters long")
+            .localized("display-name-too-long", [("max", DISPLAY_NAME_MAX_GRAPHEMES.into())]));
     }
 
     // Check for dangerous characters that could be used for XSS
     if display_name.contains('<') || display_name.contains('>') || display_name.contains('&') {
-        return Err(AppError::validation_failed("Display name cannot contain HTML characters"));
+        return Err(AppError::validation_failed("Display name cannot contain HTML characters")
+            .localized("display-name-html-characters", []));
     }
 
     // Check for control characters
@@ -100,15 +268,108 @@ pub fn validate_display_name(display_name: &str) -> Result<(), AppError> {
         .chars()
         .any(|c| c.is_control() && c != '\t' && c != '\n' && c != '\r')
     {
-        return Err(AppError::validation_failed("Display name cannot contain control characters"));
+        return Err(AppError::validation_failed("Display name cannot contain control characters")
+            .localized("display-name-control-characters", []));
+    }
+
+    // Bidi overrides can make a name render differently from how it is stored
+    if display_name.chars().any(is_bidi_control) {
+        return Err(
+            AppError::validation_failed("Display name cannot contain bidirectional control characters")
+                .localized("display-name-bidi-characters", []),
+        );
+    }
+
+    if display_name.chars().any(is_invisible) || has_dangling_joiner(display_name) {
+        return Err(AppError::validation_failed("Display name cannot contain invisible characters")
+            .localized("display-name-invisible-characters", []));
     }
 
     Ok(())
 }
 
-/// Sanitize display name by trimming whitespace and normalizing
+/// Reject display names that mix scripts, e.g. Latin letters with look-alike Cyrillic ones
+pub fn validate_display_name_script(display_name: &str) -> Result<(), AppError> {
+    if !display_name.is_single_script() {
+        return Err(
+            AppError::validation_failed("Display name cannot mix characters from different scripts")
+                .localized("display-name-mixed-scripts", []),
+        );
+    }
+
+    Ok(())
+}
+
+/// Sanitize display name by trimming whitespace and normalizing to NFC
 pub fn sanitize_display_name(display_name: &str) -> String {
-    display_name.trim().to_string()
+    display_name.trim().nfc().collect()
+}
+
+/// Map a display name to the username it visually resembles, if any.
+///
+/// The name is NFKC-folded, whitespace is dropped and remaining non-ASCII characters
+/// are replaced by their UTS #39 confusable prototype when that prototype is plain
+/// ASCII, so `ａｄｍｉｎ` and `Аdmin` (Cyrillic A) both map to `admin`.
+pub fn impersonation_candidate(display_name: &str) -> Option<String> {
+    let mut candidate = String::with_capacity(display_name.len());
+
+    for c in display_name.nfkc().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii() {
+            candidate.push(c);
+            continue;
+        }
+
+        let prototype: String = skeleton(c.encode_utf8(&mut [0; 4])).collect();
+        if prototype.is_ascii() {
+            candidate.push_str(&prototype);
+        } else {
+            candidate.push(c);
+        }
+    }
+
+    let candidate = candidate.to_ascii_lowercase();
+    validate_username(&candidate).ok().map(|_| candidate)
+}
+
+/// Bidirectional formatting characters (marks, embeddings, overrides and isolates)
+pub(crate) fn is_bidi_control(c: char) -> bool {
+    matches!(c, '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}')
+}
+
+/// Characters that render as nothing (or as blank space) and are commonly used to disguise names
+pub(crate) fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{115F}'
+            | '\u{1160}'
+            | '\u{17B4}'
+            | '\u{17B5}'
+            | '\u{180E}'
+            | '\u{200B}'
+            | '\u{2060}'..='\u{2064}' | '\u{2800}' | '\u{3164}' | '\u{FEFF}' | '\u{FFA0}'
+    )
+}
+
+fn is_joiner(c: char) -> bool {
+    matches!(c, '\u{200C}' | '\u{200D}')
+}
+
+/// ZWJ/ZWNJ are required by emoji sequences and some scripts, but only between two visible characters
+fn has_dangling_joiner(display_name: &str) -> bool {
+    let is_visible = |c: char| !c.is_whitespace() && !is_joiner(c) && !is_invisible(c);
+    let chars: Vec<char> = display_name.chars().collect();
+
+    chars.iter().enumerate().any(|(i, &c)| {
+        if !is_joiner(c) {
+            return false;
+        }
+
+        let prev = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1).copied();
+        !matches!((prev, next), (Some(p), Some(n)) if is_visible(p) && is_visible(n))
+    })
 }
 
 #[cfg(test)]
@@ -133,6 +394,49 @@ mod tests {
         assert!(validate_username("test.user").is_err()); // dot
     }
 
+    #[test]
+    fn test_reserved_usernames() {
+        assert!(is_reserved_username("admin"));
+        assert!(is_reserved_username("Admin"));
+        assert!(is_reserved_username("debug"));
+        assert!(!is_reserved_username("johndoe"));
+
+        // Route names are reserved automatically
+        assert!(is_reserved_username("display"));
+        assert!(is_reserved_username("robots"));
+        assert!(is_reserved_username("seed-status"));
+
+        assert!(ValidatedUsername::new("Metrics".to_string()).is_err());
+        assert!(ValidatedUsername::new("johndoe".to_string()).is_ok());
+
+        assert!(resembles_reserved_username("admin"));
+        assert!(resembles_reserved_username("the_admin"));
+        assert!(resembles_reserved_username("Admin_26"));
+        assert!(resembles_reserved_username("ad-min1"));
+        assert!(resembles_reserved_username("seed-status2"));
+        assert!(!resembles_reserved_username("johndoe1"));
+        assert!(!resembles_reserved_username("theodore"));
+
+        // Existing accounts may use a reserved name
+        assert_eq!(ValidatedUsername::existing("Admin".to_string()).unwrap().as_str(), "admin");
+        assert!(ValidatedUsername::existing("a".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_parse_reserved_usernames() {
+        let names = parse_reserved_usernames("# Brand names\nAcme\n\n  billing  # finance app\nsupport\n");
+        assert_eq!(names, vec!["acme", "billing", "support"]);
+    }
+
+    #[test]
+    fn test_canonical_username() {
+        assert_eq!(canonical_username("Admin"), "admin");
+        assert_eq!(canonical_username("TEST_User-1"), "test_user-1");
+        assert_eq!(canonical_username("admin"), "admin");
+
+        assert_eq!(ValidatedUsername::new("JohnDoe".to_string()).unwrap().as_str(), "johndoe");
+    }
+
     #[test]
     fn test_display_name_validation() {
         // Valid display names
@@ -155,5 +459,64 @@ mod tests {
         // Only test the sanitize_display_name function that actually exists
         assert_eq!(sanitize_display_name("  John Doe  "), "John Doe");
         assert_eq!(sanitize_display_name("Test User"), "Test User");
+
+        // Decomposed "é" (e + combining acute) is composed to a single code point
+        assert_eq!(sanitize_display_name("Jose\u{0301}"), "Jos\u{00E9}");
+    }
+
+    #[test]
+    fn test_display_name_grapheme_length() {
+        // 40 CJK characters are 120 bytes but well within the limit
+        assert!(validate_display_name(&"名".repeat(40)).is_ok());
+        assert!(validate_display_name(&"名".repeat(100)).is_ok());
+        assert!(validate_display_name(&"名".repeat(101)).is_err());
+
+        // Combining sequences count as one character each
+        assert!(validate_display_name(&"e\u{0301}".repeat(100)).is_ok());
+
+        // Emoji ZWJ sequences count as one character and are allowed
+        assert!(validate_display_name("Family 👨\u{200D}👩\u{200D}👧").is_ok());
+
+        // Heavily stacked combining marks are capped by code point count
+        assert!(validate_display_name(&format!("a{}", "\u{0301}".repeat(300))).is_err());
+    }
+
+    #[test]
+    fn test_display_name_bidi_and_invisible_characters() {
+        assert!(validate_display_name("John\u{202E}eoD").is_err()); // right-to-left override
+        assert!(validate_display_name("\u{2066}John\u{2069}").is_err()); // isolates
+        assert!(validate_display_name("John\u{200F}").is_err()); // right-to-left mark
+        assert!(validate_display_name("Jo\u{200B}hn").is_err()); // zero-width space
+        assert!(validate_display_name("John\u{FEFF}").is_err()); // byte order mark
+        assert!(validate_display_name("\u{3164}").is_err()); // Hangul filler
+
+        // Joiners must sit between two visible characters
+        assert!(validate_display_name("John\u{200D}").is_err());
+        assert!(validate_display_name("John \u{200C} Doe").is_err());
+        assert!(validate_display_name("می\u{200C}خواهم").is_ok());
+    }
+
+    #[test]
+    fn test_display_name_script_validation() {
+        assert!(validate_display_name_script("John Doe").is_ok());
+        assert!(validate_display_name_script("Иван Петров").is_ok());
+        assert!(validate_display_name_script("山田 太郎").is_ok());
+        assert!(validate_display_name_script("User 123!").is_ok());
+
+        // Latin "John" with a Cyrillic "о"
+        assert!(validate_display_name_script("J\u{043E}hn").is_err());
+    }
+
+    #[test]
+    fn test_impersonation_candidate() {
+        assert_eq!(impersonation_candidate("admin").as_deref(), Some("admin"));
+        assert_eq!(impersonation_candidate("Admin").as_deref(), Some("admin"));
+        assert_eq!(impersonation_candidate("\u{0410}dmin").as_deref(), Some("admin")); // Cyrillic А
+        assert_eq!(impersonation_candidate("ａｄｍｉｎ").as_deref(), Some("admin")); // fullwidth
+        assert_eq!(impersonation_candidate("john doe").as_deref(), Some("johndoe"));
+
+        // Names that cannot be a username have no candidate
+        assert_eq!(impersonation_candidate("John Doe!"), None);
+        assert_eq!(impersonation_candidate("山田"), None);
     }
 }